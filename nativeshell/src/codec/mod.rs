@@ -20,7 +20,7 @@ pub struct MethodCall<V> {
 
 pub type MethodCallResult<V> = Result<V, MethodCallError<V>>;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct MethodCallError<V> {
     pub code: String,
     pub message: Option<String>,
@@ -5,7 +5,7 @@ use crate::{
     Error, Result,
 };
 
-use super::{MessageCodec, MethodCall, MethodCallResult, MethodCodec};
+use super::{MessageCodec, MethodCall, MethodCallError, MethodCallResult, MethodCodec};
 
 // Cloneable invoker that can call channel methods
 #[derive(Clone)]
@@ -115,6 +115,28 @@ impl<V> EventSender<V> {
             Err(Error::InvalidContext)
         }
     }
+
+    // Delivers error to the event stream listener; Stream is not closed.
+    pub fn send_error(&self, error: &MethodCallError<V>) -> Result<()> {
+        if let Some(context) = self.context.get() {
+            let encoded = self.codec.encode_error_envelope(
+                &error.code,
+                error.message.as_deref(),
+                &error.details,
+            );
+            let engine_manager = context.engine_manager.borrow();
+            let engine = engine_manager.get_engine(self.engine_handle);
+            if let Some(engine) = engine {
+                engine
+                    .binary_messenger()
+                    .post_message(&self.channel_name, &encoded)
+            } else {
+                Err(Error::InvalidEngineHandle)
+            }
+        } else {
+            Err(Error::InvalidContext)
+        }
+    }
 }
 
 //
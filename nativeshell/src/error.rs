@@ -9,7 +9,9 @@ pub enum Error {
     Platform(PlatformError),
     Value(ValueError),
     InvalidMenuHandle,
+    InvalidNotificationHandle,
     InvalidStatusItemHandle,
+    InvalidWindowHandle,
 }
 
 impl Display for Error {
@@ -26,12 +28,21 @@ impl Display for Error {
             Error::InvalidMenuHandle => {
                 write!(f, "Provided menu handle does not match any known menu")
             }
+            Error::InvalidNotificationHandle => {
+                write!(
+                    f,
+                    "Provided notification handle does not match any known notification"
+                )
+            }
             Error::InvalidStatusItemHandle => {
                 write!(
                     f,
                     "Provided status item handle does not match any known status item"
                 )
             }
+            Error::InvalidWindowHandle => {
+                write!(f, "Provided window handle does not match any known window")
+            }
         }
     }
 }
@@ -24,6 +24,25 @@ pub(crate) mod channel {
 
     // Flutter channel for managing status items
     pub const STATUS_ITEM_MANAGER: &str = "nativeshell/status-item-manager";
+
+    // Flutter channel for application wide events
+    pub const APPLICATION_MANAGER: &str = "nativeshell/application-manager";
+
+    // Flutter channel for reading and writing clipboard
+    pub const CLIPBOARD_MANAGER: &str = "nativeshell/clipboard-manager";
+
+    // Flutter channel for file dialogs
+    pub const DIALOG_MANAGER: &str = "nativeshell/dialog-manager";
+
+    // Flutter channel for desktop notifications
+    pub const NOTIFICATION_MANAGER: &str = "nativeshell/notification-manager";
+
+    // Flutter channel for querying global pointer state
+    pub const POINTER_MANAGER: &str = "nativeshell/pointer-manager";
+
+    // Flutter event channel for global pointer events (delivered even when
+    // pointer is outside of application windows)
+    pub const POINTER_EVENTS: &str = "nativeshell/pointer-events";
 }
 
 pub const CURRENT_API_VERSION: i32 = 1;
@@ -71,19 +90,59 @@ pub(crate) mod method {
         pub const GET_GEOMETRY: &str = "Window.getGeometry";
         pub const SUPPORTED_GEOMETRY: &str = "Window.supportedGeometry";
         pub const GET_SCREEN_ID: &str = "Window.getScreenId";
+        // Argument is WindowPlacement; Applied together with first show (when
+        // both show and readyToShow were called), or immediately if window is
+        // already visible.
+        pub const SET_PLACEMENT: &str = "Window.setPlacement";
 
         pub const SET_STYLE: &str = "Window.setStyle";
         pub const SET_TITLE: &str = "Window.setTitle";
+        // Argument is list of ImageData in different resolutions
+        pub const SET_ICON: &str = "Window.setIcon";
+        // Overrides ContextOptions::application_id for this window (Linux only)
+        pub const SET_APPLICATION_ID: &str = "Window.setApplicationId";
         pub const SET_MINIMIZED: &str = "Window.setMinimized";
         pub const SET_MAXIMIZED: &str = "Window.setMaximized";
         pub const SET_FULL_SCREEN: &str = "Window.setFullScreen";
         pub const SET_COLLECTION_BEHAVIOR: &str = "Window.setCollectionBehavior";
         pub const GET_WINDOW_STATE_FLAGS: &str = "Window.getWindowStateFlags";
         pub const PERFORM_WINDOW_DRAG: &str = "Window.performWindowDrag";
+        // Starts resizing window from given edge; Must be called in response to mouse down
+        pub const PERFORM_WINDOW_RESIZE: &str = "Window.performWindowResize";
+        // Sets title bar regions handled natively; Argument is WindowDragRegions
+        pub const SET_DRAG_REGIONS: &str = "Window.setDragRegions";
+
+        // Makes window transparent for mouse events; Argument is IgnoreMouseEventsRequest
+        pub const SET_IGNORE_MOUSE_EVENTS: &str = "Window.setIgnoreMouseEvents";
+        // Restricts mouse input to given list of rects (in logical content
+        // coordinates); Mouse events outside fall through to windows below.
+        // Null argument resets the region to whole window.
+        pub const SET_INPUT_REGION: &str = "Window.setInputRegion";
+
+        // Requests user attention (urgency hint, flashing taskbar button, bouncing
+        // dock icon); Cleared automatically when window is activated
+        pub const SET_URGENT: &str = "Window.setUrgent";
+
+        // Returns ImageData with current window contents in physical pixels
+        pub const CAPTURE_IMAGE: &str = "Window.captureImage";
+
+        // Hides the cursor and keeps pointer in place; Pointer movement is
+        // reported through event:Window.relativePointerMotion
+        pub const LOCK_POINTER: &str = "Window.lockPointer";
+        // Confines pointer to given Rect (in logical content coordinates); Null
+        // argument confines pointer to the whole window
+        pub const CONFINE_POINTER: &str = "Window.confinePointer";
+        // Releases pointer locked or confined by this window
+        pub const RELEASE_POINTER: &str = "Window.releasePointer";
+        // Hides the cursor on key press until pointer moves again
+        pub const SET_HIDE_POINTER_WHILE_TYPING: &str = "Window.setHidePointerWhileTyping";
 
         pub const SHOW_POPUP_MENU: &str = "Window.showPopupMenu";
         pub const HIDE_POPUP_MENU: &str = "Window.hidePopupMenu";
 
+        // Shows window-modal alert; Returns AlertResponse once closed
+        pub const SHOW_ALERT: &str = "Window.showAlert";
+
         // Windows only
         pub const SHOW_SYSTEM_MENU: &str = "Window.showSystemMenu";
 
@@ -132,6 +191,7 @@ pub(crate) mod method {
     }
 
     pub mod screen_manager {
+        // Argument is ScreensChanged
         pub const SCREENS_CHANGED: &str = "ScreenManager.screensChanged";
         pub const GET_SCREENS: &str = "ScreenManager.getScreens";
         pub const GET_MAIN_SCREEN: &str = "ScreenManager.getMainScreen";
@@ -139,6 +199,11 @@ pub(crate) mod method {
         pub const SYSTEM_TO_LOGICAL: &str = "ScreenManager.systemToLogical";
     }
 
+    pub mod pointer_manager {
+        // Returns PointerState; Position is in logical coordinates
+        pub const GET_POINTER_STATE: &str = "PointerManager.getPointerState";
+    }
+
     pub mod status_item {
         pub const INIT: &str = "StatusItem.init";
         pub const CREATE: &str = "StatusItem.create";
@@ -151,6 +216,36 @@ pub(crate) mod method {
         pub const GET_SCREEN_ID: &str = "StatusItem.getScreenId";
         pub const ON_ACTION: &str = "StatusItem.onAction";
     }
+
+    pub mod application_manager {
+        // Called by Dart to receive application events
+        pub const INIT: &str = "ApplicationManager.init";
+        pub const ON_SECONDARY_INSTANCE_LAUNCHED: &str =
+            "ApplicationManager.onSecondaryInstanceLaunched";
+    }
+
+    pub mod clipboard_manager {
+        // Returns all data on clipboard that drag data adapters understand
+        pub const READ_DATA: &str = "ClipboardManager.readData";
+        pub const WRITE_DATA: &str = "ClipboardManager.writeData";
+        pub const CLEAR: &str = "ClipboardManager.clear";
+    }
+
+    pub mod dialog_manager {
+        // All return list of selected paths or null if dialog was cancelled
+        pub const SHOW_OPEN_DIALOG: &str = "DialogManager.showOpenDialog";
+        pub const SHOW_SAVE_DIALOG: &str = "DialogManager.showSaveDialog";
+        pub const SHOW_FOLDER_DIALOG: &str = "DialogManager.showFolderDialog";
+    }
+
+    pub mod notification {
+        pub const INIT: &str = "Notification.init";
+        // Returns notification handle
+        pub const SHOW: &str = "Notification.show";
+        pub const CLOSE: &str = "Notification.close";
+        pub const ON_ACTIVATED: &str = "Notification.onActivated";
+        pub const ON_CLOSED: &str = "Notification.onClosed";
+    }
 }
 
 pub(crate) mod event {
@@ -167,6 +262,18 @@ pub(crate) mod event {
         // Called when window geometry has changed
         pub const GEOMETRY_CHANGED: &str = "event:Window.geometryChanged";
 
+        // Called when window moved to another screen (screen id argument)
+        pub const SCREEN_CHANGED: &str = "event:Window.screenChanged";
+
+        // Called when scaling factor of window screen has changed, either
+        // because window moved to another screen or screen configuration
+        // changed (double argument)
+        pub const SCALE_FACTOR_CHANGED: &str = "event:Window.scaleFactorChanged";
+
+        // Called for pointer movement while pointer is locked (Point argument
+        // with delta in logical coordinates)
+        pub const RELATIVE_POINTER_MOTION: &str = "event:Window.relativePointerMotion";
+
         // Delivered when user requested closing the window; Target window is responsible
         // for actually closing the window
         pub const CLOSE_REQUEST: &str = "event:Window.closeRequest";
@@ -176,9 +283,15 @@ pub(crate) mod event {
     }
 }
 
+// Null platform has no drag & drop support
+#[cfg_attr(feature = "null_platform", allow(dead_code))]
 pub(crate) mod drag_data {
     pub mod key {
         pub const FILES: &str = "drag-data:internal:files";
         pub const URLS: &str = "drag-data:internal:urls";
+        pub const TEXT: &str = "drag-data:internal:text";
+        pub const HTML: &str = "drag-data:internal:html";
+        // Serialized ImageData (RGBA)
+        pub const IMAGE: &str = "drag-data:internal:image";
     }
 }
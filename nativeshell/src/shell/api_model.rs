@@ -2,7 +2,10 @@ use std::collections::HashMap;
 
 use crate::codec::Value;
 
-use super::{status_item_manager::StatusItemHandle, HotKeyHandle, MenuHandle, Point, Rect, Size};
+use super::{
+    notification_manager::NotificationHandle, status_item_manager::StatusItemHandle, HotKeyHandle,
+    MenuHandle, Point, Rect, Size, WindowHandle,
+};
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -73,6 +76,34 @@ impl WindowGeometryRequest {
     }
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum WindowPosition {
+    // Keep current position
+    #[default]
+    Default,
+    // Center on parent window; Same as CenterOnScreen for windows without parent
+    CenterOnParent,
+    // Center on work area of placement screen
+    CenterOnScreen,
+    // Offset from most recently created window
+    Cascade,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowPlacement {
+    #[serde(default)]
+    pub position: WindowPosition,
+    // Screen to place window on; Defaults to main screen for CenterOnScreen.
+    // For other positions window is moved to the screen keeping its offset
+    // relative to screen work area.
+    pub screen_id: Option<i64>,
+    // Moves and if necessary shrinks the window to fit screen work area
+    #[serde(default)]
+    pub constrain_to_work_area: bool,
+}
+
 #[derive(serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowActivateRequest {
@@ -115,6 +146,46 @@ pub struct HidePopupMenuRequest {
     pub handle: MenuHandle,
 }
 
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum AlertStyle {
+    #[default]
+    Info,
+    Warning,
+    Error,
+    Question,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRequest {
+    #[serde(default)]
+    pub title: String,
+    pub message: String,
+    pub informative_text: Option<String>,
+    // Button labels, in order of appearance; Single "OK" button if empty
+    #[serde(default)]
+    pub buttons: Vec<String>,
+    // Button activated by Enter
+    pub default_button: Option<usize>,
+    // Button activated by Escape or by closing the alert
+    pub cancel_button: Option<usize>,
+    #[serde(default)]
+    pub style: AlertStyle,
+    // Label of the "Do not show this again" checkbox; Checkbox is only shown
+    // when specified
+    pub suppression_text: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertResponse {
+    // Index of chosen button; None if alert was dismissed and there is no
+    // cancel button
+    pub button: Option<usize>,
+    pub suppressed: bool,
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowGeometryFlags {
@@ -200,6 +271,176 @@ pub struct WindowStyle {
     pub always_on_top: bool,
     pub always_on_top_level: Option<i64>,
     pub traffic_light_offset: Option<Point>,
+    // Width of invisible border (in logical pixels) where the window can be
+    // resized by mouse; Used for windows without system provided frame
+    pub resize_border_width: Option<f64>,
+    // Opacity, transparency and background color are ignored on Windows.
+    // Opacity of the whole window including frame (0.0 - 1.0); Default is 1.0
+    pub opacity: Option<f64>,
+    // Window background is transparent where not covered by Flutter content
+    #[serde(default)]
+    pub transparent: bool,
+    // Background painted before first frame is rendered and behind Flutter content
+    pub background_color: Option<Color>,
+
+    // Following options are Linux only and ignored on other platforms
+    pub layer_shell: Option<LayerShellOptions>,
+    #[serde(default)]
+    pub window_type: WindowType,
+    // Window is not shown in taskbar / task switcher
+    #[serde(default)]
+    pub skip_taskbar: bool,
+    // Window is not shown in workspace pager
+    #[serde(default)]
+    pub skip_pager: bool,
+    // Window is visible on all workspaces
+    #[serde(default)]
+    pub sticky: bool,
+}
+
+// Options that need to be known before the native window is created
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowCreateOptions {
+    // Style applied right after the window is created; On Wayland this is the
+    // only way to create layer shell window, as surface role can't be changed
+    // once the window is realized.
+    pub style: Option<WindowStyle>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WindowEdge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+// Title bar areas (in logical coordinates relative to window content) that
+// behave like native title bar - dragging moves the window, double click
+// toggles maximized state and right click shows window manager menu.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowDragRegions {
+    pub draggable: Vec<Rect>,
+    // Areas inside draggable regions that should receive mouse events (i.e. buttons)
+    #[serde(default)]
+    pub no_drag: Vec<Rect>,
+}
+
+impl WindowDragRegions {
+    pub fn is_empty(&self) -> bool {
+        self.draggable.is_empty()
+    }
+
+    pub fn is_draggable(&self, point: &Point) -> bool {
+        self.draggable.iter().any(|r| r.is_inside(point))
+            && !self.no_drag.iter().any(|r| r.is_inside(point))
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct IgnoreMouseEventsRequest {
+    pub ignore: bool,
+    // Keep delivering mouse move events (but not clicks) to Flutter while
+    // mouse events are ignored; Linux only
+    #[serde(default)]
+    pub forward_motion: bool,
+}
+
+// Hint for window manager about purpose of the window; Affects decorations,
+// stacking and placement.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum WindowType {
+    #[default]
+    Normal,
+    Dialog,
+    Utility,
+    Toolbar,
+    Splash,
+    Tooltip,
+    Notification,
+    Dock,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
+
+impl Color {
+    pub fn to_rgba_f64(&self) -> (f64, f64, f64, f64) {
+        (
+            self.red as f64 / 255.0,
+            self.green as f64 / 255.0,
+            self.blue as f64 / 255.0,
+            self.alpha as f64 / 255.0,
+        )
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum LayerShellLayer {
+    Background,
+    Bottom,
+    #[default]
+    Top,
+    Overlay,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum LayerShellKeyboardInteractivity {
+    #[default]
+    None,
+    Exclusive,
+    OnDemand,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LayerShellEdges<T> {
+    pub left: T,
+    pub top: T,
+    pub right: T,
+    pub bottom: T,
+}
+
+// Places window on a layer-shell surface (Wayland) instead of regular
+// toplevel. On X11 approximated using dock / notification window type.
+// On Wayland the window must be created with layer shell style in
+// WindowCreateOptions; Afterwards only the options can be changed.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LayerShellOptions {
+    #[serde(default)]
+    pub layer: LayerShellLayer,
+    #[serde(default)]
+    pub anchor: LayerShellEdges<bool>,
+    #[serde(default)]
+    pub margin: LayerShellEdges<i32>,
+    // Area reserved for the window along anchored edge; -1 prevents window
+    // from being moved to accommodate other surfaces
+    #[serde(default)]
+    pub exclusive_zone: i32,
+    // Reserves area matching window size; Overrides exclusive_zone
+    #[serde(default)]
+    pub auto_exclusive_zone: bool,
+    #[serde(default)]
+    pub keyboard_interactivity: LayerShellKeyboardInteractivity,
+    pub namespace: Option<String>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -216,6 +457,13 @@ impl Default for BoolTransition {
     }
 }
 
+impl BoolTransition {
+    // Value after the transition
+    pub fn is_set(&self) -> bool {
+        matches!(self, BoolTransition::Yes | BoolTransition::NoToYes)
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowStateFlags {
@@ -397,13 +645,122 @@ pub struct HotKeyPressed {
     pub handle: HotKeyHandle,
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Debug, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Screen {
     pub id: i64,
     pub frame: Rect,
     pub work_area: Rect,
     pub scaling_factor: f64,
+    // User visible name of the screen, if available
+    pub name: Option<String>,
+    pub model: Option<String>,
+    // Physical size in millimeters; None if unknown (i.e. projectors)
+    pub physical_size: Option<Size>,
+    // Refresh rate in Hz
+    pub refresh_rate: Option<f64>,
+    // Clockwise rotation in degrees (0, 90, 180, 270)
+    pub rotation: i32,
+    pub is_primary: bool,
+}
+
+// Argument of ScreenManager.screensChanged
+#[derive(serde::Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreensChanged {
+    pub added: Vec<Screen>,
+    pub removed: Vec<i64>,
+    pub changed: Vec<Screen>,
+}
+
+impl ScreensChanged {
+    pub fn new(old_screens: &[Screen], new_screens: &[Screen]) -> Self {
+        let find = |screens: &[Screen], id: i64| screens.iter().find(|s| s.id == id).cloned();
+        Self {
+            added: new_screens
+                .iter()
+                .filter(|s| find(old_screens, s.id).is_none())
+                .cloned()
+                .collect(),
+            removed: old_screens
+                .iter()
+                .filter(|s| find(new_screens, s.id).is_none())
+                .map(|s| s.id)
+                .collect(),
+            changed: new_screens
+                .iter()
+                .filter(|s| matches!(find(old_screens, s.id), Some(old) if old != **s))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+//
+// Pointer
+//
+
+// Pointer button bits, same values as Flutter's kPrimaryMouseButton, etc.
+pub const POINTER_BUTTON_PRIMARY: i64 = 0x01;
+pub const POINTER_BUTTON_SECONDARY: i64 = 0x02;
+pub const POINTER_BUTTON_MIDDLE: i64 = 0x04;
+pub const POINTER_BUTTON_BACK: i64 = 0x08;
+pub const POINTER_BUTTON_FORWARD: i64 = 0x10;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PointerModifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+// Result of PointerManager.getPointerState
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PointerState {
+    pub position: Point,
+    // Screen under the pointer
+    pub screen_id: Option<i64>,
+    // Currently pressed buttons (POINTER_BUTTON_* bits)
+    pub buttons: i64,
+    pub modifiers: PointerModifiers,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum GlobalPointerEventType {
+    Move,
+    ButtonDown,
+    ButtonUp,
+    // Modifier key pressed or released; Not reported on Windows
+    ModifiersChanged,
+}
+
+// Event sent on pointer events channel
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GlobalPointerEvent {
+    pub event_type: GlobalPointerEventType,
+    pub position: Point,
+    pub screen_id: Option<i64>,
+    // Button that was pressed or released (POINTER_BUTTON_* bit); 0 for move
+    pub button: i64,
+    // Buttons pressed after this event
+    pub buttons: i64,
+    pub modifiers: PointerModifiers,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SecondaryInstanceLaunched {
+    pub arguments: Vec<String>,
+    pub working_directory: Option<String>,
 }
 
 //
@@ -476,3 +833,125 @@ pub struct StatusItemAction {
     pub action: StatusItemActionType,
     pub position: Point,
 }
+
+//
+// Clipboard
+//
+
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ClipboardSelection {
+    #[default]
+    Clipboard,
+    // X11 / Wayland primary selection; Only available on Linux
+    Primary,
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardReadRequest {
+    #[serde(default)]
+    pub selection: ClipboardSelection,
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardWriteRequest {
+    #[serde(default)]
+    pub selection: ClipboardSelection,
+    pub data: DragData,
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardClearRequest {
+    #[serde(default)]
+    pub selection: ClipboardSelection,
+}
+
+//
+// File dialogs
+//
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileDialogType {
+    Open,
+    Save,
+    Folder,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDialogFilter {
+    pub name: String,
+    // Extensions without leading dot; "*" matches all files
+    pub extensions: Vec<String>,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDialogRequest {
+    // Dialog is modal to this window if specified
+    pub parent_window: Option<WindowHandle>,
+    pub title: Option<String>,
+    pub default_directory: Option<String>,
+    pub default_name: Option<String>,
+    #[serde(default)]
+    pub filters: Vec<FileDialogFilter>,
+    #[serde(default)]
+    pub multi_select: bool,
+}
+
+//
+// Notifications
+//
+
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationAction {
+    pub id: String,
+    pub label: String,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationShowRequest {
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    pub icon: Option<ImageData>,
+    #[serde(default)]
+    pub actions: Vec<NotificationAction>,
+    // Existing notification to be replaced; Its handle is reused
+    pub replace: Option<NotificationHandle>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationCloseRequest {
+    pub handle: NotificationHandle,
+}
+
+#[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationCloseReason {
+    Expired,
+    Dismissed,
+    Closed,
+    Unknown,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationActivated {
+    pub handle: NotificationHandle,
+    // None when notification itself was clicked
+    pub action: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationClosed {
+    pub handle: NotificationHandle,
+    pub reason: NotificationCloseReason,
+}
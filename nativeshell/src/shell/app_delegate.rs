@@ -0,0 +1,196 @@
+// Application delegate API shared by platforms where nativeshell drives
+// application lifecycle itself (Linux, Windows and null platform). macOS has
+// its own ApplicationDelegate with NSApplicationDelegate specific methods, but
+// it contains all methods declared here.
+
+use std::{
+    cell::RefCell,
+    path::Path,
+    rc::{Rc, Weak},
+};
+
+use url::Url;
+
+use crate::util::OkLog;
+
+use super::Context;
+
+// Passed to `application_should_terminate`; If the delegate replies with
+// `ApplicationTerminateReply::Later`, it must eventually call `terminate_reply`.
+pub struct AppTermination {}
+
+impl AppTermination {
+    pub fn terminate_reply(should_terminate: bool) {
+        let state = PENDING_TERMINATION.with(|p| p.take());
+        if let Some(state) = state.and_then(|s| s.upgrade()) {
+            state.termination_resolved(should_terminate);
+        }
+    }
+}
+
+pub enum ApplicationTerminateReply {
+    // Same as `Now`
+    DelegateToSuper,
+    Cancel,
+    Now,
+    Later,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionEndReason {
+    Logout,
+    Shutdown,
+}
+
+pub trait ApplicationDelegate {
+    fn application_did_become_active(&mut self) {}
+    fn application_did_resign_active(&mut self) {}
+
+    fn application_should_terminate(
+        &mut self,
+        _termination: AppTermination,
+    ) -> ApplicationTerminateReply {
+        ApplicationTerminateReply::DelegateToSuper
+    }
+
+    fn application_will_terminate(&mut self) {}
+
+    // Files and URLs passed on command line or sent by desktop environment;
+    // Files are represented as file:// URLs.
+    fn application_open_urls(&mut self, _urls: &[Url]) {}
+
+    // Called when user is logging out or system is shutting down. Followed
+    // by `application_should_terminate` for logout (and for shutdown on
+    // Windows, where it can be blocked as well).
+    fn application_session_will_end(&mut self, _reason: SessionEndReason) {}
+
+    // Another instance of the application was launched while in single
+    // instance mode. Arguments don't include the executable.
+    fn application_secondary_instance_launched(
+        &mut self,
+        _arguments: &[String],
+        _working_directory: Option<&Path>,
+    ) {
+    }
+}
+
+thread_local! {
+    static PENDING_TERMINATION: RefCell<Option<Weak<DelegateState>>> = const { RefCell::new(None) };
+}
+
+// Delegate and termination handling used by platform ApplicationDelegateManager
+// implementations.
+pub(crate) struct DelegateState {
+    context: Context,
+    delegate: RefCell<Option<Rc<RefCell<dyn ApplicationDelegate>>>>,
+    // Lets platform release resources held while termination was pending
+    on_termination_resolved: RefCell<Option<Box<dyn Fn(bool)>>>,
+}
+
+impl DelegateState {
+    pub fn new(context: Context) -> Self {
+        Self {
+            context,
+            delegate: RefCell::new(None),
+            on_termination_resolved: RefCell::new(None),
+        }
+    }
+
+    // Called with delegate decision after it replied with
+    // `ApplicationTerminateReply::Later`, before terminating.
+    pub fn set_on_termination_resolved<F>(&self, callback: F)
+    where
+        F: Fn(bool) + 'static,
+    {
+        self.on_termination_resolved
+            .replace(Some(Box::new(callback)));
+    }
+
+    fn termination_resolved(&self, should_terminate: bool) {
+        if let Some(callback) = &*self.on_termination_resolved.borrow() {
+            callback(should_terminate);
+        }
+        if should_terminate {
+            self.terminate();
+        }
+    }
+
+    pub fn set_delegate(&self, delegate: Rc<RefCell<dyn ApplicationDelegate>>) {
+        self.delegate.borrow_mut().replace(delegate);
+    }
+
+    pub fn with_delegate<F>(&self, callback: F)
+    where
+        F: FnOnce(&mut dyn ApplicationDelegate),
+    {
+        let delegate = self.delegate.borrow().clone();
+        if let Some(delegate) = delegate {
+            callback(&mut *delegate.borrow_mut());
+        }
+    }
+
+    // Asks delegate whether application should terminate and if so, shuts
+    // down engines and stops the run loop. Returns the reply so that platform
+    // can keep session from ending while termination is cancelled or pending.
+    pub fn request_terminate(self: &Rc<Self>) -> ApplicationTerminateReply {
+        let mut reply = ApplicationTerminateReply::Now;
+        self.with_delegate(|d| reply = d.application_should_terminate(AppTermination {}));
+        match reply {
+            ApplicationTerminateReply::DelegateToSuper | ApplicationTerminateReply::Now => {
+                self.terminate()
+            }
+            ApplicationTerminateReply::Cancel => {}
+            ApplicationTerminateReply::Later => {
+                PENDING_TERMINATION.with(|p| p.replace(Some(Rc::downgrade(self))));
+            }
+        }
+        reply
+    }
+
+    pub fn terminate(&self) {
+        self.with_delegate(|d| d.application_will_terminate());
+        if let Some(context) = self.context.get() {
+            // Engine shutdown can not happen while in middle of dispatching
+            // message from an engine
+            let context_weak = self.context.clone();
+            context
+                .run_loop
+                .borrow()
+                .schedule_now(move || {
+                    if let Some(context) = context_weak.get() {
+                        context.engine_manager.borrow_mut().shut_down().ok_log();
+                        context.run_loop.borrow().stop();
+                    }
+                })
+                .detach();
+        }
+    }
+
+    pub fn open_urls(&self, urls: &[Url]) {
+        if !urls.is_empty() {
+            self.with_delegate(|d| d.application_open_urls(urls));
+        }
+    }
+
+    pub fn session_will_end(&self, reason: SessionEndReason) {
+        self.with_delegate(|d| d.application_session_will_end(reason));
+    }
+
+    // Notifies both the delegate and Dart code
+    pub fn secondary_instance_launched(
+        &self,
+        arguments: &[String],
+        working_directory: Option<&Path>,
+    ) {
+        self.with_delegate(|d| {
+            d.application_secondary_instance_launched(arguments, working_directory)
+        });
+        if let Some(context) = self.context.get() {
+            context
+                .application_manager
+                .borrow()
+                .borrow()
+                .secondary_instance_launched(arguments, working_directory);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::{collections::HashSet, path::Path};
+
+use crate::{
+    codec::{value::to_value, MethodCall, MethodCallReply, Value},
+    util::{Late, OkLog},
+    Context,
+};
+
+use super::{
+    api_constants::{channel, method},
+    api_model::SecondaryInstanceLaunched,
+    EngineHandle, MethodCallHandler, MethodInvokerProvider, RegisteredMethodCallHandler,
+};
+
+// Delivers application wide events to Dart.
+pub struct ApplicationManager {
+    invoker_provider: Late<MethodInvokerProvider>,
+    engines: HashSet<EngineHandle>,
+}
+
+impl ApplicationManager {
+    pub(super) fn new(context: Context) -> RegisteredMethodCallHandler<Self> {
+        Self {
+            invoker_provider: Late::new(),
+            engines: HashSet::new(),
+        }
+        .register(context, channel::APPLICATION_MANAGER)
+    }
+
+    // Called by platform when another instance of the application was launched
+    // (in single instance mode) and forwarded its command line.
+    pub(crate) fn secondary_instance_launched(
+        &self,
+        arguments: &[String],
+        working_directory: Option<&Path>,
+    ) {
+        let event = SecondaryInstanceLaunched {
+            arguments: arguments.into(),
+            working_directory: working_directory.map(|d| d.to_string_lossy().into()),
+        };
+        for engine in &self.engines {
+            let invoker = self.invoker_provider.get_method_invoker_for_engine(*engine);
+            invoker
+                .call_method(
+                    method::application_manager::ON_SECONDARY_INSTANCE_LAUNCHED,
+                    to_value(&event).unwrap(),
+                    |_| {},
+                )
+                .ok_log();
+        }
+    }
+}
+
+impl MethodCallHandler for ApplicationManager {
+    fn on_method_call(
+        &mut self,
+        call: MethodCall<Value>,
+        reply: MethodCallReply<Value>,
+        engine: EngineHandle,
+    ) {
+        if call.method.as_str() == method::application_manager::INIT {
+            self.engines.insert(engine);
+            reply.send_ok(Value::Null);
+        }
+    }
+
+    fn on_engine_destroyed(&mut self, engine: EngineHandle) {
+        self.engines.remove(&engine);
+    }
+
+    fn assign_invoker_provider(&mut self, provider: MethodInvokerProvider) {
+        self.invoker_provider.set(provider);
+    }
+}
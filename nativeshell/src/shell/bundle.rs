@@ -16,7 +16,7 @@
 // Note: When debugging LLDB will pause on exec, to disable this you can add
 // "settings set target.process.stop-on-exec false" to LLDB configuration
 pub fn exec_bundle() {
-    #[cfg(target_os = "macos")]
+    #[cfg(all(target_os = "macos", not(feature = "null_platform")))]
     {
         use super::platform::bundle::macos_exec_bundle;
         macos_exec_bundle();
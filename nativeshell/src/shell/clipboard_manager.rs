@@ -0,0 +1,75 @@
+use std::rc::Rc;
+
+use crate::{
+    codec::{
+        value::{from_value, to_value},
+        MethodCall, MethodCallReply, MethodCallResult, Value,
+    },
+    Error, Result,
+};
+
+use super::{
+    api_constants::{channel, method},
+    api_model::{ClipboardClearRequest, ClipboardReadRequest, ClipboardWriteRequest},
+    platform::clipboard::PlatformClipboard,
+    Context, EngineHandle, MethodCallHandler, RegisteredMethodCallHandler,
+};
+
+// Clipboard contents are represented the same way as drag data; Conversion
+// between platform formats and DragData properties is done by drag data
+// adapters, including `ContextOptions::custom_drag_data_adapters`.
+pub struct ClipboardManager {
+    platform_clipboard: Rc<PlatformClipboard>,
+}
+
+impl ClipboardManager {
+    pub(super) fn new(context: Context) -> RegisteredMethodCallHandler<Self> {
+        Self {
+            platform_clipboard: Rc::new(PlatformClipboard::new(context.clone())),
+        }
+        .register(context, channel::CLIPBOARD_MANAGER)
+    }
+
+    pub fn platform_clipboard(&self) -> Rc<PlatformClipboard> {
+        self.platform_clipboard.clone()
+    }
+
+    fn map_result<T>(result: Result<T>) -> MethodCallResult<Value>
+    where
+        T: serde::Serialize,
+    {
+        result.map(|v| to_value(v).unwrap()).map_err(|e| e.into())
+    }
+}
+
+impl MethodCallHandler for ClipboardManager {
+    fn on_method_call(
+        &mut self,
+        call: MethodCall<Value>,
+        reply: MethodCallReply<Value>,
+        _engine: EngineHandle,
+    ) {
+        match call.method.as_str() {
+            method::clipboard_manager::READ_DATA => {
+                let request: ClipboardReadRequest = from_value(&call.args).unwrap();
+                self.platform_clipboard
+                    .read_data(request.selection, move |res| {
+                        reply.send(Self::map_result(res.map_err(Error::from)));
+                    });
+            }
+            method::clipboard_manager::WRITE_DATA => {
+                let request: ClipboardWriteRequest = from_value(&call.args).unwrap();
+                let res = self
+                    .platform_clipboard
+                    .write_data(request.selection, request.data);
+                reply.send(Self::map_result(res.map_err(Error::from)));
+            }
+            method::clipboard_manager::CLEAR => {
+                let request: ClipboardClearRequest = from_value(&call.args).unwrap();
+                let res = self.platform_clipboard.clear(request.selection);
+                reply.send(Self::map_result(res.map_err(Error::from)));
+            }
+            _ => {}
+        }
+    }
+}
@@ -8,14 +8,20 @@ use futures::Future;
 use crate::{util::LateRefCell, Error, Result};
 
 use super::{
+    application_manager::ApplicationManager,
+    clipboard_manager::ClipboardManager,
+    dialog_manager::DialogManager,
+    notification_manager::NotificationManager,
     platform::{
         app_delegate::ApplicationDelegateManager, drag_data::DragDataAdapter,
         engine::PlatformPlugin, init::init_platform,
     },
+    pointer_manager::PointerEventChannel,
     screen_manager::ScreenManager,
     status_item_manager::StatusItemManager,
     EngineManager, HotKeyManager, JoinHandle, KeyboardMapManager, MenuManager, MessageManager,
-    RegisteredMethodCallHandler, RunLoop, WindowManager, WindowMethodChannel,
+    PointerManager, RegisteredEventChannel, RegisteredMethodCallHandler, RunLoop, SessionManager,
+    WindowManager, WindowMethodChannel,
 };
 
 pub struct ContextOptions {
@@ -23,6 +29,17 @@ pub struct ContextOptions {
     pub flutter_plugins: Vec<PlatformPlugin>,
     pub on_last_engine_removed: Box<dyn Fn(&ContextRef)>,
     pub custom_drag_data_adapters: Vec<Box<dyn DragDataAdapter>>,
+
+    // When enabled, launching the application while another instance with
+    // same `app_namespace` is running forwards command line arguments and
+    // working directory to the running instance and exits the process.
+    // Ignored by the null platform.
+    pub single_instance: bool,
+
+    // Identifier used by desktop environment to associate windows with
+    // application's .desktop file (WM_CLASS on X11, app_id on Wayland).
+    // Defaults to executable name. Linux only.
+    pub application_id: Option<String>,
 }
 
 impl Default for ContextOptions {
@@ -32,6 +49,8 @@ impl Default for ContextOptions {
             flutter_plugins: Vec::new(),
             on_last_engine_removed: Box::new(|context| context.run_loop.borrow().stop()),
             custom_drag_data_adapters: Vec::new(),
+            single_instance: false,
+            application_id: None,
         }
     }
 }
@@ -44,11 +63,18 @@ pub struct ContextImpl {
     pub window_method_channel: LateRefCell<WindowMethodChannel>,
     pub window_manager: LateRefCell<WindowManager>,
     pub application_delegate_manager: LateRefCell<ApplicationDelegateManager>,
+    pub session_manager: LateRefCell<SessionManager>,
     pub(crate) menu_manager: LateRefCell<RegisteredMethodCallHandler<MenuManager>>,
     pub(crate) keyboard_map_manager: LateRefCell<RegisteredMethodCallHandler<KeyboardMapManager>>,
     pub(crate) hot_key_manager: LateRefCell<RegisteredMethodCallHandler<HotKeyManager>>,
     pub(crate) screen_manager: LateRefCell<RegisteredMethodCallHandler<ScreenManager>>,
     pub(crate) status_item_manager: LateRefCell<RegisteredMethodCallHandler<StatusItemManager>>,
+    pub(crate) application_manager: LateRefCell<RegisteredMethodCallHandler<ApplicationManager>>,
+    pub(crate) clipboard_manager: LateRefCell<RegisteredMethodCallHandler<ClipboardManager>>,
+    pub(crate) dialog_manager: LateRefCell<RegisteredMethodCallHandler<DialogManager>>,
+    pub(crate) notification_manager: LateRefCell<RegisteredMethodCallHandler<NotificationManager>>,
+    pub(crate) pointer_manager: LateRefCell<RegisteredMethodCallHandler<PointerManager>>,
+    pub(crate) pointer_event_channel: LateRefCell<RegisteredEventChannel<PointerEventChannel>>,
 }
 
 impl ContextImpl {
@@ -61,11 +87,18 @@ impl ContextImpl {
             window_method_channel: LateRefCell::new(),
             window_manager: LateRefCell::new(),
             application_delegate_manager: LateRefCell::new(),
+            session_manager: LateRefCell::new(),
             menu_manager: LateRefCell::new(),
             keyboard_map_manager: LateRefCell::new(),
             hot_key_manager: LateRefCell::new(),
             screen_manager: LateRefCell::new(),
             status_item_manager: LateRefCell::new(),
+            application_manager: LateRefCell::new(),
+            clipboard_manager: LateRefCell::new(),
+            dialog_manager: LateRefCell::new(),
+            notification_manager: LateRefCell::new(),
+            pointer_manager: LateRefCell::new(),
+            pointer_event_channel: LateRefCell::new(),
         });
         let res = ContextRef { context: res };
         res.initialize(&res)?;
@@ -73,7 +106,7 @@ impl ContextImpl {
     }
 
     fn initialize(&self, context: &ContextRef) -> Result<()> {
-        init_platform().map_err(Error::from)?;
+        init_platform(&self.options).map_err(Error::from)?;
 
         self.run_loop.set(RunLoop::new(context));
         self.engine_manager.set(EngineManager::new(context));
@@ -83,6 +116,7 @@ impl ContextImpl {
         self.window_manager.set(WindowManager::new(context));
         self.application_delegate_manager
             .set(ApplicationDelegateManager::new(context));
+        self.session_manager.set(SessionManager::new(context));
         self.menu_manager.set(MenuManager::new(context.weak()));
         self.keyboard_map_manager
             .set(KeyboardMapManager::new(context.weak()));
@@ -90,6 +124,17 @@ impl ContextImpl {
         self.screen_manager.set(ScreenManager::new(context.weak()));
         self.status_item_manager
             .set(StatusItemManager::new(context.weak()));
+        self.application_manager
+            .set(ApplicationManager::new(context.weak()));
+        self.clipboard_manager
+            .set(ClipboardManager::new(context.weak()));
+        self.dialog_manager.set(DialogManager::new(context.weak()));
+        self.notification_manager
+            .set(NotificationManager::new(context.weak()));
+        self.pointer_manager
+            .set(PointerManager::new(context.weak()));
+        self.pointer_event_channel
+            .set(PointerEventChannel::new(context.weak()));
 
         #[cfg(debug_assertions)]
         {
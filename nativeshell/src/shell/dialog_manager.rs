@@ -0,0 +1,87 @@
+use std::rc::Rc;
+
+use crate::{
+    codec::{
+        value::{from_value, to_value},
+        MethodCall, MethodCallReply, MethodCallResult, Value,
+    },
+    Error, Result,
+};
+
+use super::{
+    api_constants::{channel, method},
+    api_model::{FileDialogRequest, FileDialogType},
+    platform::dialog::PlatformDialogManager,
+    Context, EngineHandle, MethodCallHandler, RegisteredMethodCallHandler,
+};
+
+pub struct DialogManager {
+    context: Context,
+    platform_manager: Rc<PlatformDialogManager>,
+}
+
+impl DialogManager {
+    pub(super) fn new(context: Context) -> RegisteredMethodCallHandler<Self> {
+        Self {
+            context: context.clone(),
+            platform_manager: Rc::new(PlatformDialogManager::new(context.clone())),
+        }
+        .register(context, channel::DIALOG_MANAGER)
+    }
+
+    pub fn platform_manager(&self) -> Rc<PlatformDialogManager> {
+        self.platform_manager.clone()
+    }
+
+    fn show_file_dialog<F>(
+        &self,
+        dialog_type: FileDialogType,
+        request: FileDialogRequest,
+        on_done: F,
+    ) where
+        F: FnOnce(Result<Option<Vec<String>>>) + 'static,
+    {
+        let context = match self.context.get() {
+            Some(context) => context,
+            None => return on_done(Err(Error::InvalidContext)),
+        };
+        let parent = match request.parent_window {
+            Some(handle) => match context.window_manager.borrow().get_platform_window(handle) {
+                Some(window) => Some(window),
+                None => return on_done(Err(Error::InvalidWindowHandle)),
+            },
+            None => None,
+        };
+        self.platform_manager
+            .show_file_dialog(dialog_type, request, parent, move |res| {
+                on_done(res.map_err(Error::from))
+            });
+    }
+
+    fn map_result<T>(result: Result<T>) -> MethodCallResult<Value>
+    where
+        T: serde::Serialize,
+    {
+        result.map(|v| to_value(v).unwrap()).map_err(|e| e.into())
+    }
+}
+
+impl MethodCallHandler for DialogManager {
+    fn on_method_call(
+        &mut self,
+        call: MethodCall<Value>,
+        reply: MethodCallReply<Value>,
+        _engine: EngineHandle,
+    ) {
+        let dialog_type = match call.method.as_str() {
+            method::dialog_manager::SHOW_OPEN_DIALOG => FileDialogType::Open,
+            method::dialog_manager::SHOW_SAVE_DIALOG => FileDialogType::Save,
+            method::dialog_manager::SHOW_FOLDER_DIALOG => FileDialogType::Folder,
+            _ => return,
+        };
+        let request: FileDialogRequest = from_value(&call.args).unwrap();
+        self.show_file_dialog(dialog_type, request, move |res| {
+            reply.send(Self::map_result(res));
+        });
+    }
+}
@@ -64,6 +64,16 @@ impl EngineManager {
         self.engines.get(&handle).map(|a| a.borrow())
     }
 
+    // Returns in-memory engine that can be used to play the Dart side of platform
+    // channels (invoke methods, reply to method calls, listen to events).
+    #[cfg(feature = "null_platform")]
+    pub fn get_null_engine(
+        &self,
+        handle: EngineHandle,
+    ) -> Option<super::platform::engine::NullEngine> {
+        self.get_engine(handle).map(|e| e.platform_engine())
+    }
+
     // Returns the handle of engine responsible for creating provided engine. It is valid
     // for this method to return handle to engine that is no longer active.
     pub fn get_parent_engine(&self, handle: EngineHandle) -> Option<EngineHandle> {
@@ -4,7 +4,10 @@ use std::{
     rc::{Rc, Weak},
 };
 
-use crate::{codec::Value, Error, Result};
+use crate::{
+    codec::{MethodCallError, Value},
+    Error, Result,
+};
 
 use super::{Context, EngineHandle, MethodCallHandler, RegisteredMethodCallHandler};
 
@@ -31,6 +34,18 @@ impl EventSink {
             Err(Error::InvalidContext)
         }
     }
+
+    pub fn send_error(&self, error: &MethodCallError<Value>) -> Result<()> {
+        if let Some(context) = self.context.get() {
+            context
+                .message_manager
+                .borrow()
+                .get_event_sender(self.engine_handle, &self.channel_name)
+                .send_error(error)
+        } else {
+            Err(Error::InvalidContext)
+        }
+    }
 }
 
 pub trait EventChannelHandler: Sized + 'static {
@@ -116,6 +131,10 @@ impl<T: EventChannelHandler> MethodCallHandler for EventChannelInternal<T> {
                     channel_name: self.channel_name.clone(),
                     engine_handle: engine,
                 };
+                if let Some(sink_id) = self.engine_to_sink.insert(engine, sink_id) {
+                    // Engine re-subscribed without canceling previous subscription
+                    self.handler.borrow_mut().unregister_event_sink(sink_id);
+                }
                 self.handler
                     .borrow_mut()
                     .register_event_sink(sink, call.args);
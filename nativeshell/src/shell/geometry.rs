@@ -1,5 +1,21 @@
 use std::ops::{Add, Div, Mul, Sub};
 
+fn partial_min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+fn partial_max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct _Rect<T> {
     pub x: T,
@@ -102,6 +118,49 @@ where
         }
     }
 
+    // Returns true if other rectangle lies entirely within this rectangle
+    pub fn contains(&self, other: &_Rect<T>) -> bool {
+        other.x >= self.x && other.y >= self.y && other.x2() <= self.x2() && other.y2() <= self.y2()
+    }
+
+    // Returns None if rectangles don't overlap
+    pub fn intersect(&self, other: &_Rect<T>) -> Option<_Rect<T>> {
+        let x = partial_max(self.x, other.x);
+        let y = partial_max(self.y, other.y);
+        let x2 = partial_min(self.x2(), other.x2());
+        let y2 = partial_min(self.y2(), other.y2());
+        if x2 > x && y2 > y {
+            Some(_Rect::xywh(x, y, x2 - x, y2 - y))
+        } else {
+            None
+        }
+    }
+
+    // Shrinks the rectangle by dx on left and right and dy on top and bottom;
+    // Negative values grow the rectangle.
+    pub fn inset(&self, dx: T, dy: T) -> Self {
+        let zero = T::from(0);
+        Self {
+            x: self.x + dx,
+            y: self.y + dy,
+            width: partial_max(self.width - dx - dx, zero),
+            height: partial_max(self.height - dy - dy, zero),
+        }
+    }
+
+    // Moves the rectangle so that it fits within bounds; If the rectangle is
+    // larger than bounds it is shrunk.
+    pub fn clamp(&self, bounds: &_Rect<T>) -> Self {
+        let width = partial_min(self.width, bounds.width);
+        let height = partial_min(self.height, bounds.height);
+        Self {
+            x: partial_max(partial_min(self.x, bounds.x2() - width), bounds.x),
+            y: partial_max(partial_min(self.y, bounds.y2() - height), bounds.y),
+            width,
+            height,
+        }
+    }
+
     pub fn union(r1: &_Rect<T>, r2: &_Rect<T>) -> _Rect<T> {
         let x = if r1.x.lt(&r2.x) { r1.x } else { r2.x };
         let y = if r1.y.lt(&r2.y) { r1.y } else { r2.y };
@@ -339,7 +398,7 @@ pub type ISize = _Size<i32>;
 
 #[cfg(test)]
 mod tests {
-    use super::IPoint;
+    use super::{IPoint, Rect};
 
     #[test]
     fn test_distance() {
@@ -355,4 +414,35 @@ mod tests {
         let p2 = IPoint::xy(600000, 0);
         assert_eq!(p1.distance(&p2), 1200000.0);
     }
+
+    #[test]
+    fn test_rect() {
+        let rect = Rect::xywh(0.0, 0.0, 100.0, 100.0);
+        assert_eq!(rect.inset(10.0, 20.0), Rect::xywh(10.0, 20.0, 80.0, 60.0));
+        assert!(rect.contains(&rect.inset(10.0, 20.0)));
+        assert_eq!(
+            rect.intersect(&Rect::xywh(50.0, 50.0, 100.0, 100.0)),
+            Some(Rect::xywh(50.0, 50.0, 50.0, 50.0))
+        );
+        assert_eq!(rect.intersect(&Rect::xywh(100.0, 0.0, 10.0, 10.0)), None);
+    }
+
+    #[test]
+    fn test_clamp() {
+        let bounds = Rect::xywh(0.0, 0.0, 100.0, 100.0);
+        assert_eq!(
+            Rect::xywh(10.0, 10.0, 20.0, 20.0).clamp(&bounds),
+            Rect::xywh(10.0, 10.0, 20.0, 20.0)
+        );
+        // Larger than bounds
+        assert_eq!(
+            Rect::xywh(-50.0, 20.0, 200.0, 150.0).clamp(&bounds),
+            Rect::xywh(0.0, 0.0, 100.0, 100.0)
+        );
+        // Fully outside of bounds
+        assert_eq!(
+            Rect::xywh(150.0, -80.0, 30.0, 40.0).clamp(&bounds),
+            Rect::xywh(70.0, 0.0, 30.0, 40.0)
+        );
+    }
 }
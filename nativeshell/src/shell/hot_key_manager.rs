@@ -48,15 +48,23 @@ impl HotKeyManager {
         &mut self,
         request: HotKeyCreateRequest,
         engine: EngineHandle,
-    ) -> Result<HotKeyHandle> {
+        reply: MethodCallReply<Value>,
+    ) {
         let handle = self.next_handle;
         self.next_handle.0 += 1;
 
-        self.platform_manager
-            .create_hot_key(request.accelerator, request.platform_key, handle, engine)
-            .map_err(Error::from)?;
-
-        Ok(handle)
+        // Registration may need to go through a system service, in which case
+        // the result is only known asynchronously
+        self.platform_manager.create_hot_key(
+            request.accelerator,
+            request.platform_key,
+            handle,
+            engine,
+            move |res| {
+                let res = res.map(|_| handle).map_err(Error::from);
+                reply.send(Self::map_result(res));
+            },
+        );
     }
 
     fn map_result<T>(result: Result<T>) -> MethodCallResult<Value>
@@ -90,8 +98,7 @@ impl MethodCallHandler for HotKeyManager {
         match call.method.as_str() {
             method::hot_key::CREATE => {
                 let request: HotKeyCreateRequest = from_value(&call.args).unwrap();
-                let res = self.on_create(request, engine);
-                reply.send(Self::map_result(res));
+                self.on_create(request, engine, reply);
             }
             method::hot_key::DESTROY => {
                 let request: HotKeyDestroyRequest = from_value(&call.args).unwrap();
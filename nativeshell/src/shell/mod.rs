@@ -1,8 +1,17 @@
 mod api_constants;
+// Only used by macOS for SessionEndReason
+#[cfg_attr(
+    all(target_os = "macos", not(feature = "null_platform")),
+    allow(dead_code)
+)]
+mod app_delegate;
+mod application_manager;
 mod async_method_call_handler;
 mod binary_messenger;
 mod bundle;
+mod clipboard_manager;
 mod context;
+mod dialog_manager;
 mod engine;
 mod engine_manager;
 mod event_channel;
@@ -14,18 +23,25 @@ mod macros;
 mod menu_manager;
 mod message_manager;
 mod method_call_handler;
+mod notification_manager;
 mod observatory;
+mod pointer_manager;
 mod run_loop;
 mod screen_manager;
+mod session_manager;
 mod status_item_manager;
 mod window;
+mod window_controller;
 mod window_manager;
 mod window_method_channel;
+mod window_placement;
 
 pub use async_method_call_handler::*;
 pub use binary_messenger::*;
 pub use bundle::*;
+pub use clipboard_manager::*;
 pub use context::*;
+pub use dialog_manager::*;
 pub use engine::*;
 pub use engine_manager::*;
 pub use event_channel::*;
@@ -36,9 +52,13 @@ pub use keyboard_map_manager::*;
 pub use menu_manager::*;
 pub use message_manager::*;
 pub use method_call_handler::*;
+pub use notification_manager::*;
 pub use observatory::*;
+pub use pointer_manager::*;
 pub use run_loop::*;
+pub use session_manager::*;
 pub use window::*;
+pub use window_controller::*;
 pub use window_manager::*;
 pub use window_method_channel::*;
 
@@ -0,0 +1,217 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    codec::{
+        value::{from_value, to_value},
+        MethodCall, MethodCallReply, MethodCallResult, Value,
+    },
+    util::{Late, OkLog},
+    Error, Result,
+};
+
+use super::{
+    api_constants::{channel, method},
+    api_model::{
+        NotificationActivated, NotificationCloseReason, NotificationCloseRequest,
+        NotificationClosed, NotificationShowRequest,
+    },
+    platform::notification::PlatformNotificationManager,
+    Context, EngineHandle, MethodCallHandler, MethodInvokerProvider, RegisteredMethodCallHandler,
+};
+
+pub struct NotificationManager {
+    context: Context,
+    platform_manager: Late<Rc<PlatformNotificationManager>>,
+    // Engine that posted the notification; Events are delivered to this engine.
+    // Shared with pending show callbacks, which may complete synchronously.
+    notifications: Rc<RefCell<HashMap<NotificationHandle, EngineHandle>>>,
+    next_handle: NotificationHandle,
+    invoker_provider: Late<MethodInvokerProvider>,
+}
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NotificationHandle(pub(crate) i64);
+
+pub trait NotificationManagerDelegate {
+    // action is None when the notification body was clicked
+    fn on_notification_activated(&self, handle: NotificationHandle, action: Option<String>);
+    fn on_notification_closed(&self, handle: NotificationHandle, reason: NotificationCloseReason);
+}
+
+impl NotificationManager {
+    pub(super) fn new(context: Context) -> RegisteredMethodCallHandler<Self> {
+        Self {
+            context: context.clone(),
+            platform_manager: Late::new(),
+            notifications: Rc::new(RefCell::new(HashMap::new())),
+            next_handle: NotificationHandle(1),
+            invoker_provider: Late::new(),
+        }
+        .register(context, channel::NOTIFICATION_MANAGER)
+    }
+
+    pub fn platform_manager(&self) -> Rc<PlatformNotificationManager> {
+        self.platform_manager.clone()
+    }
+
+    fn on_show(
+        &mut self,
+        request: NotificationShowRequest,
+        engine: EngineHandle,
+        reply: MethodCallReply<Value>,
+    ) {
+        // Notification that was already closed is shown as new one
+        let owner = request
+            .replace
+            .and_then(|handle| self.notifications.borrow().get(&handle).cloned());
+        let handle = match (request.replace, owner) {
+            (Some(handle), Some(owner)) if owner == engine => handle,
+            (Some(_), Some(_)) => {
+                reply.send(Self::map_result::<()>(Err(
+                    Error::InvalidNotificationHandle,
+                )));
+                return;
+            }
+            _ => {
+                let handle = self.next_handle;
+                self.next_handle.0 += 1;
+                handle
+            }
+        };
+        self.notifications.borrow_mut().insert(handle, engine);
+        let notifications = self.notifications.clone();
+        self.platform_manager
+            .show_notification(handle, request, move |res| {
+                if res.is_err() {
+                    notifications.borrow_mut().remove(&handle);
+                }
+                let res = res.map(|_| handle).map_err(Error::from);
+                reply.send(Self::map_result(res));
+            });
+    }
+
+    // Closing notification that was already closed is not an error
+    fn on_close(&self, handle: NotificationHandle, engine: EngineHandle) -> Result<()> {
+        let owner = self.notifications.borrow().get(&handle).cloned();
+        match owner {
+            Some(owner) if owner != engine => return Err(Error::InvalidNotificationHandle),
+            Some(_) => {}
+            None => return Ok(()),
+        }
+        self.notifications.borrow_mut().remove(&handle);
+        self.platform_manager.close_notification(handle)?;
+        let invoker = self.invoker_provider.get_method_invoker_for_engine(engine);
+        invoker
+            .call_method(
+                method::notification::ON_CLOSED,
+                to_value(NotificationClosed {
+                    handle,
+                    reason: NotificationCloseReason::Closed,
+                })
+                .unwrap(),
+                |_| {},
+            )
+            .ok_log();
+        Ok(())
+    }
+
+    fn close_notifications_for_engine(&self, engine: EngineHandle) {
+        let handles: Vec<NotificationHandle> = self
+            .notifications
+            .borrow()
+            .iter()
+            .filter_map(|(handle, e)| if *e == engine { Some(*handle) } else { None })
+            .collect();
+        for handle in handles {
+            self.notifications.borrow_mut().remove(&handle);
+            self.platform_manager.close_notification(handle).ok_log();
+        }
+    }
+
+    fn map_result<T>(result: Result<T>) -> MethodCallResult<Value>
+    where
+        T: serde::Serialize,
+    {
+        result.map(|v| to_value(v).unwrap()).map_err(|e| e.into())
+    }
+}
+
+impl NotificationManagerDelegate for NotificationManager {
+    fn on_notification_activated(&self, handle: NotificationHandle, action: Option<String>) {
+        let engine = self.notifications.borrow().get(&handle).cloned();
+        if let Some(engine) = engine {
+            let invoker = self.invoker_provider.get_method_invoker_for_engine(engine);
+            invoker
+                .call_method(
+                    method::notification::ON_ACTIVATED,
+                    to_value(NotificationActivated { handle, action }).unwrap(),
+                    |_| {},
+                )
+                .ok_log();
+        }
+    }
+
+    fn on_notification_closed(&self, handle: NotificationHandle, reason: NotificationCloseReason) {
+        let engine = self.notifications.borrow_mut().remove(&handle);
+        if let Some(engine) = engine {
+            let invoker = self.invoker_provider.get_method_invoker_for_engine(engine);
+            invoker
+                .call_method(
+                    method::notification::ON_CLOSED,
+                    to_value(NotificationClosed { handle, reason }).unwrap(),
+                    |_| {},
+                )
+                .ok_log();
+        }
+    }
+}
+
+impl MethodCallHandler for NotificationManager {
+    fn on_method_call(
+        &mut self,
+        call: MethodCall<Value>,
+        reply: MethodCallReply<Value>,
+        engine: EngineHandle,
+    ) {
+        match call.method.as_str() {
+            method::notification::INIT => {
+                // Remove notifications from previous run (hot restart)
+                self.close_notifications_for_engine(engine);
+                reply.send_ok(Value::Null);
+            }
+            method::notification::SHOW => {
+                let request: NotificationShowRequest = from_value(&call.args).unwrap();
+                self.on_show(request, engine, reply);
+            }
+            method::notification::CLOSE => {
+                let request: NotificationCloseRequest = from_value(&call.args).unwrap();
+                let res = self.on_close(request.handle, engine);
+                reply.send(Self::map_result(res));
+            }
+            _ => {}
+        }
+    }
+
+    fn assign_weak_self(&mut self, weak_self: Weak<RefCell<Self>>) {
+        let delegate: Weak<RefCell<dyn NotificationManagerDelegate>> = weak_self;
+        self.platform_manager
+            .set(Rc::new(PlatformNotificationManager::new(
+                self.context.clone(),
+                delegate,
+            )));
+        self.platform_manager
+            .assign_weak_self(Rc::downgrade(&self.platform_manager));
+    }
+
+    fn assign_invoker_provider(&mut self, provider: MethodInvokerProvider) {
+        self.invoker_provider.set(provider);
+    }
+
+    fn on_engine_destroyed(&mut self, engine: EngineHandle) {
+        self.close_notifications_for_engine(engine);
+    }
+}
@@ -0,0 +1,75 @@
+use std::cell::RefCell;
+
+use glib::Cast;
+use gtk::{
+    prelude::{
+        ContainerExt, DialogExt, GtkWindowExt, MessageDialogExt, ToggleButtonExt, WidgetExt,
+        WidgetExtManual,
+    },
+    ButtonsType, CheckButton, DialogFlags, MessageDialog, MessageType, ResponseType,
+};
+
+use crate::shell::api_model::{AlertRequest, AlertResponse, AlertStyle};
+
+use super::error::PlatformResult;
+
+// Shows message dialog modal to parent window. Buttons use their index as
+// response id; any other response (Escape, closing the dialog) maps to
+// cancel button.
+pub(super) fn show_alert<F>(parent: &gtk::Window, request: AlertRequest, on_done: F)
+where
+    F: FnOnce(PlatformResult<AlertResponse>) + 'static,
+{
+    let message_type = match request.style {
+        AlertStyle::Info => MessageType::Info,
+        AlertStyle::Warning => MessageType::Warning,
+        AlertStyle::Error => MessageType::Error,
+        AlertStyle::Question => MessageType::Question,
+    };
+    let dialog = MessageDialog::new(
+        Some(parent),
+        DialogFlags::MODAL | DialogFlags::DESTROY_WITH_PARENT,
+        message_type,
+        ButtonsType::None,
+        &request.message,
+    );
+    if !request.title.is_empty() {
+        dialog.set_title(&request.title);
+    }
+    dialog.set_secondary_text(request.informative_text.as_deref());
+    for (index, button) in request.buttons.iter().enumerate() {
+        dialog.add_button(button, ResponseType::Other(index as u16));
+    }
+    if let Some(default_button) = request.default_button {
+        dialog.set_default_response(ResponseType::Other(default_button as u16));
+    }
+
+    let suppression_checkbox = request.suppression_text.as_ref().map(|text| {
+        let checkbox = CheckButton::with_label(text);
+        if let Ok(area) = dialog.message_area().downcast::<gtk::Container>() {
+            area.add(&checkbox);
+        }
+        checkbox.show();
+        checkbox
+    });
+
+    let cancel_button = request.cancel_button;
+    let on_done = RefCell::new(Some(on_done));
+    dialog.connect_response(move |dialog, response| {
+        let button = match response {
+            ResponseType::Other(index) => Some(index as usize),
+            _ => cancel_button,
+        };
+        let suppressed = suppression_checkbox
+            .as_ref()
+            .map(|c| c.is_active())
+            .unwrap_or(false);
+        if let Some(on_done) = on_done.take() {
+            on_done(Ok(AlertResponse { button, suppressed }));
+        }
+        unsafe {
+            dialog.destroy();
+        }
+    });
+    dialog.show();
+}
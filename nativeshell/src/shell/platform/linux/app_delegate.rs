@@ -1,15 +1,311 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    path::Path,
+    rc::Rc,
+};
 
-use crate::shell::ContextRef;
+use gio::{
+    prelude::{ApplicationCommandLineExt, ApplicationExt, ApplicationExtManual, FileExt},
+    ApplicationFlags, BusType,
+};
+use glib::{Cast, ObjectExt};
+use gtk::{
+    prelude::{GtkApplicationExt, GtkWindowExt},
+    ApplicationInhibitFlags,
+};
+use log::warn;
+use url::Url;
 
-pub trait ApplicationDelegate {}
+use crate::shell::{app_delegate::DelegateState, Context, ContextRef};
 
-pub struct ApplicationDelegateManager {}
+pub use crate::shell::app_delegate::{
+    AppTermination, ApplicationDelegate, ApplicationTerminateReply, SessionEndReason,
+};
+
+// GtkApplication and session state on top of shared delegate state
+struct ApplicationState {
+    context: Context,
+    delegate_state: Rc<DelegateState>,
+    application: gtk::Application,
+    active: Cell<bool>,
+    // Logout inhibited while waiting for delegate reply or after delegate
+    // cancelled termination
+    inhibit_cookie: Cell<Option<u32>>,
+    // Inhibit taken after delegate cancelled logout is released once the
+    // application is activated again, i.e. user returned to the session
+    logout_cancelled: Cell<bool>,
+    logind_subscription: RefCell<Option<(gio::DBusConnection, gio::SignalSubscriptionId)>>,
+}
+
+impl ApplicationState {
+    fn on_open(&self, files: &[gio::File]) {
+        let urls: Vec<Url> = files
+            .iter()
+            .filter_map(|f| Url::parse(&f.uri()).ok())
+            .collect();
+        self.delegate_state.open_urls(&urls);
+    }
+
+    // Command line forwarded from secondary instance
+    fn on_command_line(&self, command_line: &gio::ApplicationCommandLine) {
+        let arguments: Vec<String> = command_line
+            .arguments()
+            .into_iter()
+            .skip(1)
+            .map(|a| a.to_string_lossy().into())
+            .collect();
+        let working_directory = command_line.cwd();
+        self.delegate_state
+            .secondary_instance_launched(&arguments, working_directory.as_deref());
+    }
+
+    fn update_active(&self) {
+        let active = gtk::Window::list_toplevels().iter().any(|w| {
+            w.downcast_ref::<gtk::Window>()
+                .map(|w| w.is_active())
+                .unwrap_or(false)
+        });
+        if active != self.active.replace(active) {
+            if active && self.logout_cancelled.take() {
+                self.uninhibit();
+            }
+            if active {
+                self.delegate_state
+                    .with_delegate(|d| d.application_did_become_active());
+            } else {
+                self.delegate_state
+                    .with_delegate(|d| d.application_did_resign_active());
+            }
+        }
+    }
+
+    fn uninhibit(&self) {
+        self.logout_cancelled.set(false);
+        if let Some(cookie) = self.inhibit_cookie.take() {
+            self.application.uninhibit(cookie);
+        }
+    }
+
+    fn inhibit(&self, reason: &str) {
+        if self.inhibit_cookie.get().is_none() {
+            let cookie = self.application.inhibit(
+                None::<&gtk::Window>,
+                ApplicationInhibitFlags::LOGOUT,
+                Some(reason),
+            );
+            if cookie != 0 {
+                self.inhibit_cookie.set(Some(cookie));
+            }
+        }
+    }
+
+    fn on_query_end(&self) {
+        self.uninhibit();
+        self.delegate_state
+            .session_will_end(SessionEndReason::Logout);
+        self.request_terminate();
+    }
+
+    fn on_prepare_for_shutdown(&self) {
+        self.delegate_state
+            .session_will_end(SessionEndReason::Shutdown);
+        self.delegate_state.terminate();
+    }
+
+    fn request_terminate(&self) {
+        match self.delegate_state.request_terminate() {
+            ApplicationTerminateReply::DelegateToSuper | ApplicationTerminateReply::Now => {
+                self.uninhibit()
+            }
+            ApplicationTerminateReply::Cancel => {
+                self.inhibit("Application cancelled logout");
+                self.logout_cancelled.set(true);
+            }
+            ApplicationTerminateReply::Later => {
+                self.inhibit("Application is finishing pending work")
+            }
+        }
+    }
+
+    // Opens files and URLs given on command line; Options are ignored.
+    fn open_command_line_arguments(&self) {
+        let files: Vec<gio::File> = std::env::args()
+            .skip(1)
+            .filter(|arg| !arg.starts_with('-'))
+            .filter(|arg| Path::new(arg).exists() || (arg.contains(':') && Url::parse(arg).is_ok()))
+            .map(gio::File::for_commandline_arg)
+            .collect();
+        if !files.is_empty() {
+            self.application.open(&files, "");
+        }
+    }
+}
+
+pub struct ApplicationDelegateManager {
+    state: Rc<ApplicationState>,
+}
 
 impl ApplicationDelegateManager {
-    pub fn new(_context: &ContextRef) -> Self {
-        Self {}
+    pub fn new(context: &ContextRef) -> Self {
+        let application_id = &context.options.app_namespace;
+        let application_id = if gio::Application::id_is_valid(application_id) {
+            Some(application_id.as_str())
+        } else {
+            None
+        };
+        let single_instance = context.options.single_instance;
+        if single_instance && application_id.is_none() {
+            warn!(
+                "Single instance mode requires app_namespace to be a valid application id, got '{}'",
+                context.options.app_namespace
+            );
+        }
+        let flags = if single_instance {
+            ApplicationFlags::HANDLES_OPEN | ApplicationFlags::HANDLES_COMMAND_LINE
+        } else {
+            ApplicationFlags::NON_UNIQUE | ApplicationFlags::HANDLES_OPEN
+        };
+        let application = gtk::Application::new(application_id, flags);
+        // Needed for query-end signal
+        application.set_register_session(true);
+
+        let state = Rc::new(ApplicationState {
+            context: context.weak(),
+            delegate_state: Rc::new(DelegateState::new(context.weak())),
+            application: application.clone(),
+            active: Cell::new(false),
+            inhibit_cookie: Cell::new(None),
+            logout_cancelled: Cell::new(false),
+            logind_subscription: RefCell::new(None),
+        });
+
+        // Inhibit taken for `Later` reply is not needed once delegate decides
+        let weak = Rc::downgrade(&state);
+        state.delegate_state.set_on_termination_resolved(move |_| {
+            if let Some(state) = weak.upgrade() {
+                state.uninhibit();
+            }
+        });
+
+        let weak = Rc::downgrade(&state);
+        application.connect_open(move |_, files, _| {
+            if let Some(state) = weak.upgrade() {
+                state.on_open(files);
+            }
+        });
+        let weak = Rc::downgrade(&state);
+        // query-end is only available since GTK 3.24.8
+        application
+            .connect_local("query-end", false, move |_| {
+                if let Some(state) = weak.upgrade() {
+                    state.on_query_end();
+                }
+                None
+            })
+            .ok();
+        let weak = Rc::downgrade(&state);
+        application.connect_command_line(move |_, command_line| {
+            if let Some(state) = weak.upgrade() {
+                state.on_command_line(command_line);
+            }
+            0
+        });
+        // Application is never run (we use our own run loop), register it
+        // explicitly instead.
+        if let Err(error) = application.register(None::<&gio::Cancellable>) {
+            warn!("Failed to register application: {}", error);
+        }
+        if application.is_remote() {
+            // Another instance owns the application id; Running the application
+            // sends our command line and working directory to it over D-Bus.
+            let args: Vec<String> = std::env::args().collect();
+            let status = application.run_with_args(&args);
+            std::process::exit(status);
+        }
+
+        Self::subscribe_logind(&state);
+
+        // Delegate is usually set after context is created; Wait for run loop
+        // to start.
+        let weak = Rc::downgrade(&state);
+        context
+            .run_loop
+            .borrow()
+            .schedule_now(move || {
+                if let Some(state) = weak.upgrade() {
+                    state.open_command_line_arguments();
+                }
+            })
+            .detach();
+
+        Self { state }
+    }
+
+    fn subscribe_logind(state: &Rc<ApplicationState>) {
+        let connection = match gio::bus_get_sync(BusType::System, None::<&gio::Cancellable>) {
+            Ok(connection) => connection,
+            Err(_) => return,
+        };
+        let weak = Rc::downgrade(state);
+        let subscription = connection.signal_subscribe(
+            Some("org.freedesktop.login1"),
+            Some("org.freedesktop.login1.Manager"),
+            Some("PrepareForShutdown"),
+            Some("/org/freedesktop/login1"),
+            None,
+            gio::DBusSignalFlags::NONE,
+            move |_, _, _, _, _, parameters| {
+                let starting = parameters.get::<(bool,)>().map(|p| p.0).unwrap_or(false);
+                if let (true, Some(state)) = (starting, weak.upgrade()) {
+                    state.on_prepare_for_shutdown();
+                }
+            },
+        );
+        state
+            .logind_subscription
+            .replace(Some((connection, subscription)));
+    }
+
+    pub fn set_delegate<D: ApplicationDelegate + 'static>(&self, delegate: D) {
+        self.state
+            .delegate_state
+            .set_delegate(Rc::new(RefCell::new(delegate)));
+    }
+
+    pub fn set_delegate_ref<D: ApplicationDelegate + 'static>(&self, delegate: Rc<RefCell<D>>) {
+        self.state.delegate_state.set_delegate(delegate);
+    }
+
+    // Asks delegate whether application should terminate and if so, shuts
+    // down engines and stops the run loop.
+    pub fn request_terminate(&self) {
+        self.state.request_terminate();
+    }
+
+    // Called by windows when focus changes; Activation state is updated once
+    // all focus events have been processed so that moving focus between
+    // application windows doesn't resign the application.
+    pub(super) fn window_focus_changed(&self) {
+        let weak = Rc::downgrade(&self.state);
+        if let Some(context) = self.state.context.get() {
+            context
+                .run_loop
+                .borrow()
+                .schedule_now(move || {
+                    if let Some(state) = weak.upgrade() {
+                        state.update_active();
+                    }
+                })
+                .detach();
+        }
+    }
+}
+
+impl Drop for ApplicationDelegateManager {
+    fn drop(&mut self) {
+        if let Some((connection, subscription)) = self.state.logind_subscription.take() {
+            connection.signal_unsubscribe(subscription);
+        }
+        self.state.uninhibit();
     }
-    pub fn set_delegate<D: ApplicationDelegate + 'static>(&self, _delegate: D) {}
-    pub fn set_delegate_ref<D: ApplicationDelegate + 'static>(&self, _delegate: Rc<RefCell<D>>) {}
 }
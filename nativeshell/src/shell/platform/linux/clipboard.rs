@@ -0,0 +1,178 @@
+use std::rc::Rc;
+
+use gdk::Atom;
+use glib::translate::ToGlibPtr;
+use gtk::{SelectionData, TargetEntry, TargetFlags};
+
+use crate::{
+    shell::{
+        api_model::{ClipboardSelection, DragData},
+        Context, ContextRef,
+    },
+    util::{CompletableFuture, FutureCompleter},
+};
+
+use super::{
+    drag_data::{
+        DragDataAdapter, FallThroughDragDataAdapter, HtmlDataAdapter, ImageDataAdapter,
+        TextDataAdapter, UriListDataAdapter,
+    },
+    error::{PlatformError, PlatformResult},
+};
+
+pub struct PlatformClipboard {
+    context: Context,
+    data_adapters: Vec<Box<dyn DragDataAdapter>>,
+}
+
+impl PlatformClipboard {
+    pub fn new(context: Context) -> Self {
+        let mut data_adapters: Vec<Box<dyn DragDataAdapter>> = vec![
+            Box::new(TextDataAdapter::new()),
+            Box::new(HtmlDataAdapter::new()),
+            Box::new(ImageDataAdapter::new()),
+            // Text is handled by TextDataAdapter
+            Box::new(UriListDataAdapter::new_uri_only()),
+        ];
+        if let Some(context) = context.get() {
+            data_adapters.push(Box::new(FallThroughDragDataAdapter::new(&context.options)));
+        }
+        Self {
+            context,
+            data_adapters,
+        }
+    }
+
+    fn data_adapters<'a>(&'a self, context: &'a ContextRef) -> Vec<&'a dyn DragDataAdapter> {
+        context
+            .options
+            .custom_drag_data_adapters
+            .iter()
+            .chain(self.data_adapters.iter())
+            .map(|a| a.as_ref())
+            .collect()
+    }
+
+    fn clipboard(selection: ClipboardSelection) -> gtk::Clipboard {
+        let selection = match selection {
+            ClipboardSelection::Clipboard => "CLIPBOARD",
+            ClipboardSelection::Primary => "PRIMARY",
+        };
+        gtk::Clipboard::get(&Atom::intern(selection))
+    }
+
+    fn request_contents(
+        clipboard: &gtk::Clipboard,
+        target: &Atom,
+    ) -> CompletableFuture<SelectionData> {
+        let (future, completer) = FutureCompleter::new();
+        clipboard.request_contents(target, move |_, data| {
+            completer.complete(data.clone());
+        });
+        future
+    }
+
+    pub fn read_data<F>(self: &Rc<Self>, selection: ClipboardSelection, on_done: F)
+    where
+        F: FnOnce(PlatformResult<DragData>) + 'static,
+    {
+        let context = match self.context.get() {
+            Some(context) => context,
+            None => return on_done(Err(PlatformError::UnknownError)),
+        };
+        let clipboard = Self::clipboard(selection);
+        let this = self.clone();
+        context.run_loop.borrow().spawn(async move {
+            let targets = Self::request_contents(&clipboard, &Atom::intern("TARGETS"))
+                .await
+                .targets()
+                .unwrap_or_default();
+
+            // Each adapter gets data in the first of its formats that is
+            // available; Data is requested sequentially so that it can be
+            // processed in order of adapters.
+            let formats: Vec<Option<Atom>> = match this.context.get() {
+                Some(context) => this
+                    .data_adapters(&context)
+                    .iter()
+                    .map(|a| a.data_formats().into_iter().find(|f| targets.contains(f)))
+                    .collect(),
+                None => return on_done(Err(PlatformError::UnknownError)),
+            };
+
+            let mut data = Vec::<Option<SelectionData>>::new();
+            for format in formats {
+                match format {
+                    Some(format) => {
+                        let contents = Self::request_contents(&clipboard, &format).await;
+                        data.push(Some(contents).filter(|d| d.length() >= 0));
+                    }
+                    None => data.push(None),
+                }
+            }
+
+            let mut res = DragData::default();
+            if let Some(context) = this.context.get() {
+                for (adapter, data) in this.data_adapters(&context).iter().zip(data.iter()) {
+                    if let Some(data) = data {
+                        adapter.retrieve_drag_data(data, &mut res.properties);
+                    }
+                }
+            }
+            on_done(Ok(res));
+        });
+    }
+
+    pub fn write_data(&self, selection: ClipboardSelection, data: DragData) -> PlatformResult<()> {
+        let context = self.context.get().ok_or(PlatformError::UnknownError)?;
+        let mut properties = data.properties;
+        let mut setters = Vec::new();
+        for adapter in self.data_adapters(&context) {
+            setters.append(&mut adapter.prepare_drag_data(&mut properties));
+        }
+
+        // Target info is index of the setter; If more setters provide same
+        // format, the first one wins.
+        let mut targets = Vec::<TargetEntry>::new();
+        for (index, setter) in setters.iter().enumerate() {
+            for format in setter.data_formats() {
+                targets.push(TargetEntry::new(
+                    &format.name(),
+                    TargetFlags::empty(),
+                    index as u32,
+                ));
+            }
+        }
+
+        let clipboard = Self::clipboard(selection);
+        if targets.is_empty() {
+            clipboard.clear();
+            return Ok(());
+        }
+        let res = clipboard.set_with_data(&targets, move |_, selection_data, info| {
+            if let Some(setter) = setters.get(info as usize) {
+                setter.set(selection_data);
+            }
+        });
+        if res {
+            // Let clipboard manager keep the data after application exits
+            unsafe {
+                gtk_sys::gtk_clipboard_set_can_store(
+                    clipboard.to_glib_none().0,
+                    std::ptr::null(),
+                    0,
+                );
+            }
+            Ok(())
+        } else {
+            Err(PlatformError::OtherError {
+                error: "Failed to take clipboard ownership".into(),
+            })
+        }
+    }
+
+    pub fn clear(&self, selection: ClipboardSelection) -> PlatformResult<()> {
+        Self::clipboard(selection).clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,613 @@
+// Exports GMenuModel over com.canonical.dbusmenu
+// https://github.com/AyatanaIndicators/libdbusmenu/blob/master/libdbusmenu-glib/dbus-menu.xml
+//
+// This is the format expected by global menu registrars
+// (com.canonical.AppMenu.Registrar, KDE appmenu) and by StatusNotifierItem
+// hosts. Layout is generated from the model on demand and item ids are
+// assigned in traversal order, so any change to the model or actions bumps
+// the layout revision and hosts re-read the whole layout.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::{Rc, Weak},
+    time::Duration,
+};
+
+use gdk::ModifierType;
+use gio::{
+    prelude::{ActionGroupExt, MenuModelExt},
+    DBusConnection, DBusInterfaceInfo, DBusMethodInvocation, DBusNodeInfo, RegistrationId,
+    SimpleActionGroup,
+};
+use glib::{ObjectExt, SignalHandlerId, ToVariant, Variant, VariantTy};
+
+use crate::util::OkLog;
+
+use super::error::{PlatformError, PlatformResult};
+
+const DBUS_MENU_INTERFACE: &str = "com.canonical.dbusmenu";
+
+// How long can reply to AboutToShow be deferred; Hosts wait for the reply
+// before showing the menu
+const ABOUT_TO_SHOW_TIMEOUT: Duration = Duration::from_millis(1000);
+
+const INTERFACE_XML: &str = r#"
+<node>
+  <interface name="com.canonical.dbusmenu">
+    <property name="Version" type="u" access="read"/>
+    <property name="TextDirection" type="s" access="read"/>
+    <property name="Status" type="s" access="read"/>
+    <property name="IconThemePath" type="as" access="read"/>
+    <method name="GetLayout">
+      <arg name="parentId" type="i" direction="in"/>
+      <arg name="recursionDepth" type="i" direction="in"/>
+      <arg name="propertyNames" type="as" direction="in"/>
+      <arg name="revision" type="u" direction="out"/>
+      <arg name="layout" type="(ia{sv}av)" direction="out"/>
+    </method>
+    <method name="GetGroupProperties">
+      <arg name="ids" type="ai" direction="in"/>
+      <arg name="propertyNames" type="as" direction="in"/>
+      <arg name="properties" type="a(ia{sv})" direction="out"/>
+    </method>
+    <method name="GetProperty">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="name" type="s" direction="in"/>
+      <arg name="value" type="v" direction="out"/>
+    </method>
+    <method name="Event">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="eventId" type="s" direction="in"/>
+      <arg name="data" type="v" direction="in"/>
+      <arg name="timestamp" type="u" direction="in"/>
+    </method>
+    <method name="EventGroup">
+      <arg name="events" type="a(isvu)" direction="in"/>
+      <arg name="idErrors" type="ai" direction="out"/>
+    </method>
+    <method name="AboutToShow">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="needUpdate" type="b" direction="out"/>
+    </method>
+    <method name="AboutToShowGroup">
+      <arg name="ids" type="ai" direction="in"/>
+      <arg name="updatesNeeded" type="ai" direction="out"/>
+      <arg name="idErrors" type="ai" direction="out"/>
+    </method>
+    <signal name="ItemsPropertiesUpdated">
+      <arg name="updatedProps" type="a(ia{sv})"/>
+      <arg name="removedProps" type="a(ias)"/>
+    </signal>
+    <signal name="LayoutUpdated">
+      <arg name="revision" type="u"/>
+      <arg name="parent" type="i"/>
+    </signal>
+    <signal name="ItemActivationRequested">
+      <arg name="id" type="i"/>
+      <arg name="timestamp" type="u"/>
+    </signal>
+  </interface>
+</node>
+"#;
+
+struct Item {
+    id: i32,
+    properties: HashMap<String, Variant>,
+    children: Vec<Item>,
+}
+
+impl Item {
+    fn find(&self, id: i32) -> Option<&Item> {
+        if self.id == id {
+            Some(self)
+        } else {
+            self.children.iter().find_map(|c| c.find(id))
+        }
+    }
+
+    // (ia{sv}av); Negative depth means all descendants
+    fn to_variant(&self, depth: i32) -> Variant {
+        let children: Vec<Variant> = if depth != 0 {
+            self.children
+                .iter()
+                .map(|c| c.to_variant(depth - 1))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Variant::from_tuple(&[
+            self.id.to_variant(),
+            self.properties.to_variant(),
+            children.to_variant(),
+        ])
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) enum DBusMenuEvent {
+    // Host is about to show the menu; Reply is deferred until
+    // about_to_show_done is called so that the menu can be updated first
+    AboutToShow,
+    // Host has closed the menu
+    Closed,
+}
+
+// Action name (without "app." prefix) and target of menu item
+type ItemAction = (String, Option<Variant>);
+
+struct Layout {
+    root: Item,
+    actions: HashMap<i32, ItemAction>,
+}
+
+pub(super) struct DBusMenu {
+    id: i64,
+    connection: DBusConnection,
+    object_path: String,
+    actions: SimpleActionGroup,
+    model: RefCell<Option<gio::MenuModel>>,
+    layout: RefCell<Option<Layout>>,
+    revision: Cell<u32>,
+    update_pending: Cell<bool>,
+    model_handlers: RefCell<Vec<(gio::MenuModel, SignalHandlerId)>>,
+    action_handlers: RefCell<Vec<SignalHandlerId>>,
+    registration_id: Cell<Option<RegistrationId>>,
+    on_event: RefCell<Option<Box<dyn Fn(DBusMenuEvent)>>>,
+    pending_about_to_show: RefCell<Vec<DBusMethodInvocation>>,
+}
+
+// D-Bus callbacks need to be Send + Sync, so menus are looked up by id
+// (same as in status_notifier_item)
+thread_local! {
+    static MENUS: RefCell<HashMap<i64, Weak<DBusMenu>>> = RefCell::new(HashMap::new());
+    static NEXT_ID: Cell<i64> = const { Cell::new(1) };
+}
+
+fn menu_for_id(id: i64) -> Option<Rc<DBusMenu>> {
+    MENUS.with(|menus| menus.borrow().get(&id).and_then(|m| m.upgrade()))
+}
+
+impl DBusMenu {
+    // Menu items refer to actions in given group through "app." prefix
+    pub fn new(
+        connection: &DBusConnection,
+        object_path: &str,
+        actions: &SimpleActionGroup,
+    ) -> PlatformResult<Rc<Self>> {
+        let id = NEXT_ID.with(|next| next.replace(next.get() + 1));
+        let res = Rc::new(Self {
+            id,
+            connection: connection.clone(),
+            object_path: object_path.into(),
+            actions: actions.clone(),
+            model: RefCell::new(None),
+            layout: RefCell::new(None),
+            revision: Cell::new(1),
+            update_pending: Cell::new(false),
+            model_handlers: RefCell::new(Vec::new()),
+            action_handlers: RefCell::new(Vec::new()),
+            registration_id: Cell::new(None),
+            on_event: RefCell::new(None),
+            pending_about_to_show: RefCell::new(Vec::new()),
+        });
+        MENUS.with(|menus| menus.borrow_mut().insert(id, Rc::downgrade(&res)));
+
+        let registration_id = connection.register_object(
+            object_path,
+            &Self::interface_info()?,
+            move |_, _, _, _, method, parameters, invocation| {
+                if let Some(menu) = menu_for_id(id) {
+                    menu.on_method_call(method, parameters, invocation);
+                }
+            },
+            |_, _, _, _, property| match property {
+                "Version" => 3u32.to_variant(),
+                "TextDirection" => "ltr".to_variant(),
+                "Status" => "normal".to_variant(),
+                "IconThemePath" => Vec::<String>::new().to_variant(),
+                _ => "".to_variant(),
+            },
+            |_, _, _, _, _, _| false,
+        )?;
+        res.registration_id.set(Some(registration_id));
+
+        let mut handlers = res.action_handlers.borrow_mut();
+        handlers.push(actions.connect_action_added(None, move |_, _| Self::invalidate_id(id)));
+        handlers.push(actions.connect_action_removed(None, move |_, _| Self::invalidate_id(id)));
+        handlers.push(
+            actions.connect_action_enabled_changed(None, move |_, _, _| Self::invalidate_id(id)),
+        );
+        handlers.push(
+            actions.connect_action_state_changed(None, move |_, _, _| Self::invalidate_id(id)),
+        );
+        drop(handlers);
+
+        Ok(res)
+    }
+
+    fn interface_info() -> PlatformResult<DBusInterfaceInfo> {
+        DBusNodeInfo::for_xml(INTERFACE_XML)?
+            .lookup_interface(DBUS_MENU_INTERFACE)
+            .ok_or(PlatformError::UnknownError)
+    }
+
+    pub fn object_path(&self) -> &str {
+        &self.object_path
+    }
+
+    pub fn set_model(&self, model: Option<&gio::MenuModel>) {
+        self.model.replace(model.cloned());
+        self.invalidate();
+    }
+
+    pub fn set_on_event<F>(&self, on_event: F)
+    where
+        F: Fn(DBusMenuEvent) + 'static,
+    {
+        self.on_event.replace(Some(Box::new(on_event)));
+    }
+
+    // Returns true if host is waiting for the menu to be shown
+    pub fn is_about_to_show(&self) -> bool {
+        !self.pending_about_to_show.borrow().is_empty()
+    }
+
+    // Replies to deferred AboutToShow calls; Host will then fetch the layout
+    pub fn about_to_show_done(&self) {
+        for invocation in self.pending_about_to_show.take() {
+            invocation.return_value(Some(&(true,).to_variant()));
+        }
+    }
+
+    fn emit_event(&self, event: DBusMenuEvent) {
+        if let Some(on_event) = self.on_event.borrow().as_ref() {
+            on_event(event);
+        }
+    }
+
+    fn invalidate_id(id: i64) {
+        if let Some(menu) = menu_for_id(id) {
+            menu.invalidate();
+        }
+    }
+
+    // Models are usually rebuilt item by item, so the update is coalesced
+    fn invalidate(&self) {
+        self.layout.replace(None);
+        if self.update_pending.replace(true) {
+            return;
+        }
+        let id = self.id;
+        glib::idle_add_local(move || {
+            if let Some(menu) = menu_for_id(id) {
+                menu.update_pending.set(false);
+                menu.watch_models();
+                menu.revision.set(menu.revision.get() + 1);
+                menu.connection
+                    .emit_signal(
+                        None,
+                        &menu.object_path,
+                        DBUS_MENU_INTERFACE,
+                        "LayoutUpdated",
+                        Some(&(menu.revision.get(), 0i32).to_variant()),
+                    )
+                    .ok_log();
+            }
+            glib::Continue(false)
+        });
+    }
+
+    // Submenus are linked models, so items-changed needs to be observed on
+    // every model in the tree
+    fn watch_models(&self) {
+        for (model, handler) in self.model_handlers.take() {
+            model.disconnect(handler);
+        }
+        let mut models = Vec::new();
+        if let Some(model) = self.model.borrow().as_ref() {
+            Self::collect_models(model, &mut models);
+        }
+        let id = self.id;
+        let handlers = models
+            .into_iter()
+            .map(|model| {
+                let handler = model.connect_items_changed(move |_, _, _, _| {
+                    Self::invalidate_id(id);
+                });
+                (model, handler)
+            })
+            .collect();
+        self.model_handlers.replace(handlers);
+    }
+
+    fn collect_models(model: &gio::MenuModel, models: &mut Vec<gio::MenuModel>) {
+        models.push(model.clone());
+        for i in 0..model.n_items() {
+            for link in ["section", "submenu"] {
+                if let Some(child) = model.item_link(i, link) {
+                    Self::collect_models(&child, models);
+                }
+            }
+        }
+    }
+
+    fn with_layout<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&Layout) -> R,
+    {
+        let mut layout = self.layout.borrow_mut();
+        let layout = layout.get_or_insert_with(|| self.build_layout());
+        f(layout)
+    }
+
+    fn build_layout(&self) -> Layout {
+        let mut actions = HashMap::new();
+        let mut next_id = 1;
+        let mut root = Item {
+            id: 0,
+            properties: HashMap::new(),
+            children: Vec::new(),
+        };
+        root.properties
+            .insert("children-display".into(), "submenu".to_variant());
+        if let Some(model) = self.model.borrow().as_ref() {
+            self.append_items(model, &mut root.children, &mut next_id, &mut actions);
+        }
+        Layout { root, actions }
+    }
+
+    // Sections are flattened and separated by separator items
+    fn append_items(
+        &self,
+        model: &gio::MenuModel,
+        items: &mut Vec<Item>,
+        next_id: &mut i32,
+        actions: &mut HashMap<i32, ItemAction>,
+    ) {
+        for i in 0..model.n_items() {
+            if let Some(section) = model.item_link(i, "section") {
+                let mut section_items = Vec::new();
+                self.append_items(&section, &mut section_items, next_id, actions);
+                if !section_items.is_empty() {
+                    if !items.is_empty() {
+                        let mut separator = Item {
+                            id: *next_id,
+                            properties: HashMap::new(),
+                            children: Vec::new(),
+                        };
+                        *next_id += 1;
+                        separator
+                            .properties
+                            .insert("type".into(), "separator".to_variant());
+                        items.push(separator);
+                    }
+                    items.append(&mut section_items);
+                }
+                continue;
+            }
+            let id = *next_id;
+            *next_id += 1;
+            let mut item = Item {
+                id,
+                properties: HashMap::new(),
+                children: Vec::new(),
+            };
+            let string = |name: &str| {
+                model
+                    .item_attribute_value(i, name, Some(VariantTy::new("s").unwrap()))
+                    .and_then(|v| v.str().map(|s| s.to_owned()))
+            };
+            if let Some(label) = string("label") {
+                item.properties.insert("label".into(), label.to_variant());
+            }
+            if let Some(submenu) = model.item_link(i, "submenu") {
+                item.properties
+                    .insert("children-display".into(), "submenu".to_variant());
+                self.append_items(&submenu, &mut item.children, next_id, actions);
+            }
+            if let Some(accel) = string("accel") {
+                if let Some(shortcut) = Self::shortcut(&accel) {
+                    item.properties
+                        .insert("shortcut".into(), vec![shortcut].to_variant());
+                }
+            }
+            let action = string("action").and_then(|a| a.strip_prefix("app.").map(String::from));
+            if let Some(action) = action {
+                let target = model.item_attribute_value(i, "target", None);
+                self.append_action_properties(&mut item, &action, target.as_ref());
+                actions.insert(id, (action, target));
+            }
+            items.push(item);
+        }
+    }
+
+    fn append_action_properties(&self, item: &mut Item, action: &str, target: Option<&Variant>) {
+        let properties = &mut item.properties;
+        properties.insert(
+            "enabled".into(),
+            self.actions.is_action_enabled(action).to_variant(),
+        );
+        let state = self.actions.action_state(action);
+        match (state, target) {
+            (Some(state), None) => {
+                if let Some(checked) = state.get::<bool>() {
+                    properties.insert("toggle-type".into(), "checkmark".to_variant());
+                    properties.insert("toggle-state".into(), (checked as i32).to_variant());
+                }
+            }
+            (Some(state), Some(target)) => {
+                properties.insert("toggle-type".into(), "radio".to_variant());
+                properties.insert(
+                    "toggle-state".into(),
+                    ((&state == target) as i32).to_variant(),
+                );
+            }
+            (None, _) => {}
+        }
+    }
+
+    // GTK accelerator to dbusmenu shortcut (i.e. ["Control", "Shift", "s"])
+    fn shortcut(accel: &str) -> Option<Vec<String>> {
+        let (key, modifiers) = gtk::accelerator_parse(accel);
+        if key == 0 {
+            return None;
+        }
+        let mut res: Vec<String> = [
+            (ModifierType::CONTROL_MASK, "Control"),
+            (ModifierType::MOD1_MASK, "Alt"),
+            (ModifierType::SHIFT_MASK, "Shift"),
+            (ModifierType::SUPER_MASK, "Super"),
+        ]
+        .iter()
+        .filter(|(mask, _)| modifiers.contains(*mask))
+        .map(|(_, name)| name.to_string())
+        .collect();
+        res.push(gdk::keys::Key::from(key).name()?.to_string());
+        Some(res)
+    }
+
+    fn activate(&self, id: i32) -> bool {
+        let action = self.with_layout(|layout| layout.actions.get(&id).cloned());
+        match action {
+            Some((action, target)) => {
+                if self.actions.is_action_enabled(&action) {
+                    self.actions.activate_action(&action, target.as_ref());
+                }
+                true
+            }
+            None => self.with_layout(|layout| layout.root.find(id).is_some()),
+        }
+    }
+
+    fn on_method_call(&self, method: &str, parameters: Variant, invocation: DBusMethodInvocation) {
+        match method {
+            "GetLayout" => {
+                let (parent, depth, _) =
+                    parameters
+                        .get::<(i32, i32, Vec<String>)>()
+                        .unwrap_or((0, -1, Vec::new()));
+                let layout = self.with_layout(|layout| {
+                    layout.root.find(parent).map(|item| item.to_variant(depth))
+                });
+                match layout {
+                    Some(layout) => {
+                        let revision = self.revision.get().to_variant();
+                        invocation.return_value(Some(&Variant::from_tuple(&[revision, layout])));
+                    }
+                    None => Self::return_invalid_id(invocation, parent),
+                }
+            }
+            "GetGroupProperties" => {
+                let (ids, _) = parameters
+                    .get::<(Vec<i32>, Vec<String>)>()
+                    .unwrap_or_default();
+                let properties: Vec<Variant> = self.with_layout(|layout| {
+                    ids.iter()
+                        .filter_map(|id| layout.root.find(*id))
+                        .map(|item| {
+                            Variant::from_tuple(&[
+                                item.id.to_variant(),
+                                item.properties.to_variant(),
+                            ])
+                        })
+                        .collect()
+                });
+                let properties =
+                    Variant::from_array::<(i32, HashMap<String, Variant>)>(&properties);
+                invocation.return_value(Some(&Variant::from_tuple(&[properties])));
+            }
+            "GetProperty" => {
+                let (id, name) = parameters.get::<(i32, String)>().unwrap_or_default();
+                let value = self.with_layout(|layout| {
+                    layout
+                        .root
+                        .find(id)
+                        .and_then(|item| item.properties.get(&name).cloned())
+                });
+                match value {
+                    Some(value) => {
+                        invocation.return_value(Some(&Variant::from_tuple(&[value.to_variant()])))
+                    }
+                    None => Self::return_invalid_id(invocation, id),
+                }
+            }
+            "Event" => {
+                let id = parameters.child_value(0).get::<i32>().unwrap_or(-1);
+                let event = parameters.child_value(1);
+                let known = if event.str() == Some("clicked") {
+                    self.activate(id)
+                } else {
+                    self.with_layout(|layout| layout.root.find(id).is_some())
+                };
+                if known {
+                    invocation.return_value(None);
+                } else {
+                    Self::return_invalid_id(invocation, id);
+                }
+                if id == 0 && event.str() == Some("closed") {
+                    self.emit_event(DBusMenuEvent::Closed);
+                }
+            }
+            "EventGroup" => {
+                let events = parameters.child_value(0);
+                let mut errors = Vec::<i32>::new();
+                for event in events.iter() {
+                    let id = event.child_value(0).get::<i32>().unwrap_or(-1);
+                    let known = if event.child_value(1).str() == Some("clicked") {
+                        self.activate(id)
+                    } else {
+                        self.with_layout(|layout| layout.root.find(id).is_some())
+                    };
+                    if !known {
+                        errors.push(id);
+                    }
+                }
+                invocation.return_value(Some(&(errors,).to_variant()));
+            }
+            "AboutToShow" => {
+                let id = parameters.get::<(i32,)>().map(|p| p.0);
+                if id != Some(0) || self.on_event.borrow().is_none() {
+                    invocation.return_value(Some(&(false,).to_variant()));
+                    return;
+                }
+                self.pending_about_to_show.borrow_mut().push(invocation);
+                let menu_id = self.id;
+                glib::timeout_add_local(ABOUT_TO_SHOW_TIMEOUT, move || {
+                    if let Some(menu) = menu_for_id(menu_id) {
+                        menu.about_to_show_done();
+                    }
+                    glib::Continue(false)
+                });
+                self.emit_event(DBusMenuEvent::AboutToShow);
+            }
+            "AboutToShowGroup" => {
+                invocation.return_value(Some(&(Vec::<i32>::new(), Vec::<i32>::new()).to_variant()));
+            }
+            _ => invocation.return_value(None),
+        }
+    }
+
+    fn return_invalid_id(invocation: DBusMethodInvocation, id: i32) {
+        invocation.return_dbus_error(
+            "org.freedesktop.DBus.Error.InvalidArgs",
+            &format!("Unknown menu item id {}", id),
+        );
+    }
+}
+
+impl Drop for DBusMenu {
+    fn drop(&mut self) {
+        MENUS.with(|menus| menus.borrow_mut().remove(&self.id));
+        self.about_to_show_done();
+        for (model, handler) in self.model_handlers.take() {
+            model.disconnect(handler);
+        }
+        for handler in self.action_handlers.take() {
+            self.actions.disconnect(handler);
+        }
+        if let Some(registration_id) = self.registration_id.take() {
+            self.connection.unregister_object(registration_id).ok_log();
+        }
+    }
+}
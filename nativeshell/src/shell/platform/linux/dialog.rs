@@ -0,0 +1,100 @@
+use std::cell::RefCell;
+
+use gtk::{
+    prelude::{FileChooserExt, NativeDialogExt},
+    FileChooserAction, FileChooserNative, FileFilter, ResponseType,
+};
+
+use crate::{
+    shell::api_model::{FileDialogRequest, FileDialogType},
+    Context,
+};
+
+use super::{error::PlatformResult, window::PlatformWindowType};
+
+pub struct PlatformDialogManager {}
+
+impl PlatformDialogManager {
+    pub fn new(_context: Context) -> Self {
+        Self {}
+    }
+
+    // GtkFileChooserNative uses the file chooser portal when running
+    // sandboxed or when GTK_USE_PORTAL is set.
+    pub fn show_file_dialog<F>(
+        &self,
+        dialog_type: FileDialogType,
+        request: FileDialogRequest,
+        parent: Option<PlatformWindowType>,
+        on_done: F,
+    ) where
+        F: FnOnce(PlatformResult<Option<Vec<String>>>) + 'static,
+    {
+        let action = match dialog_type {
+            FileDialogType::Open => FileChooserAction::Open,
+            FileDialogType::Save => FileChooserAction::Save,
+            FileDialogType::Folder => FileChooserAction::SelectFolder,
+        };
+        let dialog = FileChooserNative::new(
+            request.title.as_deref(),
+            parent.as_ref(),
+            action,
+            None,
+            None,
+        );
+        // Same as PlatformWindow::show_modal
+        dialog.set_modal(parent.is_some());
+
+        if let Some(directory) = &request.default_directory {
+            dialog.set_current_folder(directory);
+        }
+        match dialog_type {
+            FileDialogType::Save => {
+                dialog.set_do_overwrite_confirmation(true);
+                if let Some(name) = &request.default_name {
+                    dialog.set_current_name(name);
+                }
+            }
+            FileDialogType::Open | FileDialogType::Folder => {
+                dialog.set_select_multiple(request.multi_select);
+            }
+        }
+        if dialog_type != FileDialogType::Folder {
+            for filter in &request.filters {
+                let file_filter = FileFilter::new();
+                file_filter.set_name(Some(&filter.name));
+                for extension in &filter.extensions {
+                    if extension == "*" {
+                        file_filter.add_pattern("*");
+                    } else {
+                        file_filter.add_pattern(&format!("*.{}", extension));
+                    }
+                }
+                dialog.add_filter(&file_filter);
+            }
+        }
+
+        // Native dialog must be kept alive while shown; The reference is
+        // released when response arrives.
+        let dialog_ref = RefCell::new(Some(dialog.clone()));
+        let on_done = RefCell::new(Some(on_done));
+        dialog.connect_response(move |dialog, response| {
+            let res = if response == ResponseType::Accept {
+                let paths = dialog
+                    .filenames()
+                    .iter()
+                    .map(|p| p.to_string_lossy().into())
+                    .collect();
+                Some(paths)
+            } else {
+                None
+            };
+            if let Some(on_done) = on_done.take() {
+                on_done(Ok(res));
+            }
+            dialog.destroy();
+            dialog_ref.borrow_mut().take();
+        });
+        dialog.show();
+    }
+}
@@ -1,16 +1,21 @@
 use std::{collections::HashMap, mem::take};
 
-use gdk::Atom;
+use gdk::{gdk_pixbuf::Pixbuf, Atom};
 use gtk::SelectionData;
 use log::warn;
 use percent_encoding::percent_decode_str;
 use url::Url;
 
 use crate::{
-    codec::{MessageCodec, StandardMethodCodec, Value},
-    shell::{api_constants::drag_data, ContextOptions},
+    codec::{
+        value::{from_value, to_value},
+        MessageCodec, StandardMethodCodec, Value,
+    },
+    shell::{api_constants::drag_data, api_model::ImageData, ContextOptions},
 };
 
+use super::utils::pixbuf_from_image_data;
+
 pub trait DragDataSetter {
     fn set(&self, selection_data: &SelectionData);
     fn data_formats(&self) -> Vec<Atom>;
@@ -26,11 +31,20 @@ pub trait DragDataAdapter {
     ) -> Vec<Box<dyn DragDataSetter>>;
 }
 
-pub(super) struct UriListDataAdapter {}
+pub(super) struct UriListDataAdapter {
+    // Whether plain text should be parsed as list of URIs; Useful when dropping
+    // from applications that only provide text, but not desired for clipboard
+    // where text is handled by TextDataAdapter.
+    parse_text: bool,
+}
 
 impl UriListDataAdapter {
     pub fn new() -> Self {
-        Self {}
+        Self { parse_text: true }
+    }
+
+    pub fn new_uri_only() -> Self {
+        Self { parse_text: false }
     }
 }
 
@@ -51,7 +65,7 @@ impl DragDataAdapter for UriListDataAdapter {
                 uris.push(uri);
             }
         }
-        if let Some(string) = data.text() {
+        if let Some(string) = data.text().filter(|_| self.parse_text) {
             let parts = string.split('\n');
             for part in parts {
                 let part = part.trim().to_string();
@@ -90,15 +104,11 @@ impl DragDataAdapter for UriListDataAdapter {
     }
 
     fn data_formats(&self) -> Vec<Atom> {
-        vec![
-            Atom::intern("text/uri-list"),
-            Atom::intern("UTF8_STRING"),
-            Atom::intern("COMPOUND_TEXT"),
-            Atom::intern("TEXT"),
-            Atom::intern("STRING"),
-            Atom::intern("text/plain;charset=utf-8"),
-            Atom::intern("text/plain"),
-        ]
+        let mut res = vec![Atom::intern("text/uri-list")];
+        if self.parse_text {
+            res.append(&mut text_formats());
+        }
+        res
     }
 
     fn prepare_drag_data(
@@ -125,6 +135,10 @@ impl DragDataAdapter for UriListDataAdapter {
             }
         }
 
+        if uris.is_empty() {
+            return Vec::new();
+        }
+
         vec![
             Box::new(UriDragData {
                 uris: uris.clone(),
@@ -134,19 +148,23 @@ impl DragDataAdapter for UriListDataAdapter {
             Box::new(UriDragData {
                 uris,
                 set_as_uris: false,
-                formats: vec![
-                    Atom::intern("UTF8_STRING"),
-                    Atom::intern("COMPOUND_TEXT"),
-                    Atom::intern("TEXT"),
-                    Atom::intern("STRING"),
-                    Atom::intern("text/plain;charset=utf-8"),
-                    Atom::intern("text/plain"),
-                ],
+                formats: text_formats(),
             }),
         ]
     }
 }
 
+fn text_formats() -> Vec<Atom> {
+    vec![
+        Atom::intern("UTF8_STRING"),
+        Atom::intern("COMPOUND_TEXT"),
+        Atom::intern("TEXT"),
+        Atom::intern("STRING"),
+        Atom::intern("text/plain;charset=utf-8"),
+        Atom::intern("text/plain"),
+    ]
+}
+
 fn extract_string_list(value: Option<Value>) -> Option<Vec<String>> {
     match value {
         Some(value) => {
@@ -197,6 +215,207 @@ impl DragDataSetter for UriDragData {
 //
 //
 
+pub(super) struct TextDataAdapter {}
+
+impl TextDataAdapter {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl DragDataAdapter for TextDataAdapter {
+    fn retrieve_drag_data(&self, data: &SelectionData, data_out: &mut HashMap<String, Value>) {
+        if let Some(text) = data.text() {
+            data_out.insert(drag_data::key::TEXT.into(), Value::String(text.into()));
+        }
+    }
+
+    fn data_formats(&self) -> Vec<Atom> {
+        text_formats()
+    }
+
+    fn prepare_drag_data(
+        &self,
+        data_in: &mut HashMap<String, Value>,
+    ) -> Vec<Box<dyn DragDataSetter>> {
+        match data_in.remove(drag_data::key::TEXT) {
+            Some(Value::String(text)) => vec![Box::new(TextDragData { text })],
+            Some(value) => panic!("Invalid value: {:?}, expected string", value),
+            None => Vec::new(),
+        }
+    }
+}
+
+struct TextDragData {
+    text: String,
+}
+
+impl DragDataSetter for TextDragData {
+    fn set(&self, selection_data: &SelectionData) {
+        selection_data.set_text(&self.text);
+    }
+
+    fn data_formats(&self) -> Vec<Atom> {
+        text_formats()
+    }
+}
+
+//
+//
+//
+
+pub(super) struct HtmlDataAdapter {
+    format: Atom,
+}
+
+impl HtmlDataAdapter {
+    pub fn new() -> Self {
+        Self {
+            format: Atom::intern("text/html"),
+        }
+    }
+}
+
+impl DragDataAdapter for HtmlDataAdapter {
+    fn retrieve_drag_data(&self, data: &SelectionData, data_out: &mut HashMap<String, Value>) {
+        let data = data.data();
+        // Firefox and Chromium provide text/html as UTF-16 with byte order mark
+        let html = if data.starts_with(&[0xFF, 0xFE]) {
+            let data: Vec<u16> = data[2..]
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .collect();
+            String::from_utf16_lossy(&data)
+        } else {
+            String::from_utf8_lossy(&data).into()
+        };
+        let html = html.trim_end_matches('\0');
+        if !html.is_empty() {
+            data_out.insert(drag_data::key::HTML.into(), Value::String(html.into()));
+        }
+    }
+
+    fn data_formats(&self) -> Vec<Atom> {
+        vec![self.format]
+    }
+
+    fn prepare_drag_data(
+        &self,
+        data_in: &mut HashMap<String, Value>,
+    ) -> Vec<Box<dyn DragDataSetter>> {
+        match data_in.remove(drag_data::key::HTML) {
+            Some(Value::String(html)) => vec![Box::new(HtmlDragData {
+                html,
+                format: self.format,
+            })],
+            Some(value) => panic!("Invalid value: {:?}, expected string", value),
+            None => Vec::new(),
+        }
+    }
+}
+
+struct HtmlDragData {
+    html: String,
+    format: Atom,
+}
+
+impl DragDataSetter for HtmlDragData {
+    fn set(&self, selection_data: &SelectionData) {
+        selection_data.set(&self.format, 8, self.html.as_bytes());
+    }
+
+    fn data_formats(&self) -> Vec<Atom> {
+        vec![self.format]
+    }
+}
+
+//
+//
+//
+
+pub(super) struct ImageDataAdapter {}
+
+impl ImageDataAdapter {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn image_data_from_pixbuf(pixbuf: &Pixbuf) -> Option<ImageData> {
+        let pixbuf = if pixbuf.has_alpha() {
+            pixbuf.clone()
+        } else {
+            pixbuf.add_alpha(false, 0, 0, 0)?
+        };
+        let bytes = pixbuf.read_pixel_bytes()?;
+        Some(ImageData {
+            width: pixbuf.width(),
+            height: pixbuf.height(),
+            bytes_per_row: pixbuf.rowstride(),
+            data: bytes.to_vec(),
+        })
+    }
+}
+
+impl DragDataAdapter for ImageDataAdapter {
+    fn retrieve_drag_data(&self, data: &SelectionData, data_out: &mut HashMap<String, Value>) {
+        let image = data
+            .pixbuf()
+            .and_then(|pixbuf| Self::image_data_from_pixbuf(&pixbuf));
+        if let Some(image) = image {
+            data_out.insert(drag_data::key::IMAGE.into(), to_value(image).unwrap());
+        }
+    }
+
+    fn data_formats(&self) -> Vec<Atom> {
+        vec![
+            Atom::intern("image/png"),
+            Atom::intern("image/bmp"),
+            Atom::intern("image/jpeg"),
+            Atom::intern("image/tiff"),
+        ]
+    }
+
+    fn prepare_drag_data(
+        &self,
+        data_in: &mut HashMap<String, Value>,
+    ) -> Vec<Box<dyn DragDataSetter>> {
+        match data_in.remove(drag_data::key::IMAGE) {
+            Some(value) => {
+                let pixbuf = from_value::<ImageData>(&value)
+                    .map_err(|e| e.to_string())
+                    .and_then(|image| pixbuf_from_image_data(image).map_err(|e| e.to_string()));
+                match pixbuf {
+                    Ok(pixbuf) => vec![Box::new(ImageDragData { pixbuf })],
+                    Err(error) => {
+                        warn!("Invalid image drag data: {}", error);
+                        Vec::new()
+                    }
+                }
+            }
+            None => Vec::new(),
+        }
+    }
+}
+
+struct ImageDragData {
+    pixbuf: Pixbuf,
+}
+
+impl DragDataSetter for ImageDragData {
+    fn set(&self, selection_data: &SelectionData) {
+        selection_data.set_pixbuf(&self.pixbuf);
+    }
+
+    fn data_formats(&self) -> Vec<Atom> {
+        // Encoded on demand, offer only lossless formats
+        vec![Atom::intern("image/png"), Atom::intern("image/bmp")]
+    }
+}
+
+//
+//
+//
+
 pub struct FallThroughDragDataAdapter {
     format: Atom,
 }
@@ -30,3 +30,27 @@ impl Display for PlatformError {
 }
 
 impl std::error::Error for PlatformError {}
+
+impl From<glib::Error> for PlatformError {
+    fn from(error: glib::Error) -> Self {
+        PlatformError::GLibError {
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<cairo::Error> for PlatformError {
+    fn from(error: cairo::Error) -> Self {
+        PlatformError::OtherError {
+            error: format!("Cairo error: {error}"),
+        }
+    }
+}
+
+impl From<cairo::BorrowError> for PlatformError {
+    fn from(error: cairo::BorrowError) -> Self {
+        PlatformError::OtherError {
+            error: format!("Cairo error: {error}"),
+        }
+    }
+}
@@ -0,0 +1,223 @@
+// Exports application menu over D-Bus for desktops with global menu bar.
+//
+// Menu is exported twice - as GMenuModel / GActionGroup, advertised through
+// _GTK_* properties on X11 windows (same as GtkApplicationWindow does), and
+// as com.canonical.dbusmenu (see dbus_menu.rs). The dbusmenu object is
+// registered with com.canonical.AppMenu.Registrar on X11 and associated with
+// window surface through KDE appmenu protocol on Wayland.
+
+use std::{cell::Cell, cell::RefCell, rc::Rc};
+
+use gio::{
+    ActionGroupExportId, BusType, DBusCallFlags, DBusConnection, MenuModelExportId,
+    SimpleActionGroup,
+};
+use glib::{translate::ToGlibPtr, Cast, ObjectExt, ToVariant, Variant};
+use log::warn;
+
+use crate::util::OkLog;
+
+use super::{
+    dbus_menu::DBusMenu,
+    error::PlatformResult,
+    utils::{get_session_type, object_path_variant, SessionType},
+    wayland_sys::{self, wl_argument, wl_proxy, Wayland},
+    x11_sys,
+};
+
+const REGISTRAR_BUS_NAME: &str = "com.canonical.AppMenu.Registrar";
+const REGISTRAR_OBJECT_PATH: &str = "/com/canonical/AppMenu/Registrar";
+const APPLICATION_OBJECT_PATH: &str = "/dev/nativeshell/application";
+const MENUBAR_OBJECT_PATH: &str = "/dev/nativeshell/application/menus/menubar";
+const DBUS_MENU_OBJECT_PATH: &str = "/dev/nativeshell/application/menus/dbusmenu";
+
+pub(super) struct GlobalMenu {
+    connection: DBusConnection,
+    actions_export: Cell<Option<ActionGroupExportId>>,
+    menu_export: Cell<Option<MenuModelExportId>>,
+    dbus_menu: Rc<DBusMenu>,
+    // KDE appmenu objects for window surfaces (Wayland only)
+    appmenus: RefCell<Vec<(glib::WeakRef<gdk::Window>, *mut wl_proxy)>>,
+}
+
+impl GlobalMenu {
+    // Returns None if there is no global menu registrar on session bus (X11)
+    // or compositor doesn't support KDE appmenu protocol (Wayland).
+    pub fn new(actions: &SimpleActionGroup) -> Option<Self> {
+        let connection = gio::bus_get_sync(BusType::Session, None::<&gio::Cancellable>).ok()?;
+        let available = match get_session_type() {
+            SessionType::X11 => Self::has_registrar(&connection),
+            SessionType::Wayland => !Self::appmenu_manager().is_null(),
+        };
+        if !available {
+            return None;
+        }
+        let actions_export = connection
+            .export_action_group(APPLICATION_OBJECT_PATH, actions)
+            .ok_log()?;
+        let dbus_menu = DBusMenu::new(&connection, DBUS_MENU_OBJECT_PATH, actions).ok_log()?;
+        Some(Self {
+            connection,
+            actions_export: Cell::new(Some(actions_export)),
+            menu_export: Cell::new(None),
+            dbus_menu,
+            appmenus: RefCell::new(Vec::new()),
+        })
+    }
+
+    fn has_registrar(connection: &DBusConnection) -> bool {
+        connection
+            .call_sync(
+                Some("org.freedesktop.DBus"),
+                "/org/freedesktop/DBus",
+                "org.freedesktop.DBus",
+                "NameHasOwner",
+                Some(&(REGISTRAR_BUS_NAME,).to_variant()),
+                None,
+                DBusCallFlags::NONE,
+                -1,
+                None::<&gio::Cancellable>,
+            )
+            .ok()
+            .and_then(|r| r.get::<(bool,)>())
+            .map(|r| r.0)
+            .unwrap_or(false)
+    }
+
+    fn appmenu_manager() -> *mut wl_proxy {
+        match (Wayland::get(), gdk::Display::default()) {
+            (Some(wayland), Some(display)) => wayland.globals(&display).appmenu_manager,
+            _ => std::ptr::null_mut(),
+        }
+    }
+
+    pub fn set_menu(&self, model: Option<&gio::Menu>) -> PlatformResult<()> {
+        if let Some(export) = self.menu_export.take() {
+            self.connection.unexport_menu_model(export);
+        }
+        if let Some(model) = model {
+            let export = self
+                .connection
+                .export_menu_model(MENUBAR_OBJECT_PATH, model)?;
+            self.menu_export.set(Some(export));
+        }
+        self.dbus_menu.set_model(model.map(|m| m.upcast_ref()));
+        Ok(())
+    }
+
+    // On X11 window must be realized; On Wayland window must be mapped and
+    // registered again every time it is shown (GDK recreates the surface).
+    pub fn register_window(&self, window: &gdk::Window) {
+        match get_session_type() {
+            SessionType::X11 => self.register_x11_window(window),
+            SessionType::Wayland => self.register_wayland_window(window),
+        }
+    }
+
+    fn bus_name(&self) -> String {
+        self.connection
+            .unique_name()
+            .map(|n| n.to_string())
+            .unwrap_or_default()
+    }
+
+    fn register_x11_window(&self, window: &gdk::Window) {
+        let bus_name = self.bus_name();
+        let properties = [
+            ("_GTK_UNIQUE_BUS_NAME", bus_name.as_str()),
+            ("_GTK_APPLICATION_OBJECT_PATH", APPLICATION_OBJECT_PATH),
+            ("_GTK_MENUBAR_OBJECT_PATH", MENUBAR_OBJECT_PATH),
+        ];
+        for (name, value) in properties {
+            unsafe {
+                x11_sys::gdk_x11_window_set_utf8_property(
+                    window.to_glib_none().0,
+                    name.to_glib_none().0,
+                    value.to_glib_none().0,
+                );
+            }
+        }
+        let xid = unsafe { x11_sys::gdk_x11_window_get_xid(window.to_glib_none().0) };
+        let object_path = object_path_variant(self.dbus_menu.object_path());
+        self.connection.call(
+            Some(REGISTRAR_BUS_NAME),
+            REGISTRAR_OBJECT_PATH,
+            REGISTRAR_BUS_NAME,
+            "RegisterWindow",
+            Some(&Variant::from_tuple(&[
+                (xid as u32).to_variant(),
+                object_path,
+            ])),
+            None,
+            DBusCallFlags::NONE,
+            -1,
+            None::<&gio::Cancellable>,
+            |res| {
+                if let Err(error) = res {
+                    warn!("Failed to register window menu: {}", error);
+                }
+            },
+        );
+    }
+
+    fn register_wayland_window(&self, window: &gdk::Window) {
+        let wayland = match Wayland::get() {
+            Some(wayland) => wayland,
+            None => return,
+        };
+        let manager = Self::appmenu_manager();
+        let mut appmenus = self.appmenus.borrow_mut();
+        // Release objects of closed windows and previous surface of this window
+        appmenus.retain(|(w, appmenu)| match w.upgrade() {
+            Some(w) if &w != window => true,
+            _ => {
+                unsafe { wayland.destroy_with_request(*appmenu, wayland_sys::APPMENU_RELEASE) };
+                false
+            }
+        });
+        unsafe {
+            let surface = (wayland.gdk_wayland_window_get_wl_surface)(window.to_glib_none().0);
+            if surface.is_null() || manager.is_null() {
+                return;
+            }
+            let appmenu = (wayland.wl_proxy_marshal_array_constructor)(
+                manager,
+                wayland_sys::APPMENU_MANAGER_CREATE,
+                [wl_argument { n: 0 }, wl_argument { o: surface }].as_mut_ptr(),
+                wayland.org_kde_kwin_appmenu_interface,
+            );
+            let bus_name = std::ffi::CString::new(self.bus_name()).unwrap_or_default();
+            let object_path = std::ffi::CString::new(self.dbus_menu.object_path()).unwrap();
+            (wayland.wl_proxy_marshal_array)(
+                appmenu,
+                wayland_sys::APPMENU_SET_ADDRESS,
+                [
+                    wl_argument {
+                        s: bus_name.as_ptr(),
+                    },
+                    wl_argument {
+                        s: object_path.as_ptr(),
+                    },
+                ]
+                .as_mut_ptr(),
+            );
+            appmenus.push((window.downgrade(), appmenu));
+        }
+    }
+}
+
+impl Drop for GlobalMenu {
+    fn drop(&mut self) {
+        if let Some(wayland) = Wayland::get() {
+            for (_, appmenu) in self.appmenus.take() {
+                unsafe { wayland.destroy_with_request(appmenu, wayland_sys::APPMENU_RELEASE) };
+            }
+        }
+        if let Some(export) = self.menu_export.take() {
+            self.connection.unexport_menu_model(export);
+        }
+        if let Some(export) = self.actions_export.take() {
+            self.connection.unexport_action_group(export);
+        }
+    }
+}
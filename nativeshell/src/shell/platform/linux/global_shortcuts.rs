@@ -0,0 +1,265 @@
+// Client for org.freedesktop.portal.GlobalShortcuts
+// https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.GlobalShortcuts.html
+//
+// All shortcuts of the application live in single portal session. Whenever
+// the set of shortcuts changes the whole set is bound again.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+
+use gio::{BusType, DBusCallFlags, DBusConnection, DBusSignalFlags, SignalSubscriptionId};
+use glib::{ToVariant, Variant};
+
+use crate::util::FutureCompleter;
+
+use super::{
+    error::{PlatformError, PlatformResult},
+    utils::object_path_variant,
+};
+
+const PORTAL_BUS_NAME: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_OBJECT_PATH: &str = "/org/freedesktop/portal/desktop";
+const REQUEST_INTERFACE: &str = "org.freedesktop.portal.Request";
+const GLOBAL_SHORTCUTS_INTERFACE: &str = "org.freedesktop.portal.GlobalShortcuts";
+
+#[derive(Clone)]
+pub(super) struct Shortcut {
+    pub id: String,
+    pub description: String,
+    // Shortcut in format described by XDG shortcuts specification, i.e.
+    // "CTRL+SHIFT+a"
+    pub preferred_trigger: String,
+}
+
+enum SessionState {
+    None,
+    Creating(Vec<FutureCompleter<PlatformResult<String>>>),
+    Created(String),
+}
+
+pub(super) struct GlobalShortcuts {
+    connection: DBusConnection,
+    session: RefCell<SessionState>,
+    next_token: Cell<u32>,
+    activated_subscription: Cell<Option<SignalSubscriptionId>>,
+}
+
+impl GlobalShortcuts {
+    // Returns error if the portal doesn't provide GlobalShortcuts interface.
+    pub fn new<F>(on_activated: F) -> PlatformResult<Self>
+    where
+        F: Fn(&str) + 'static,
+    {
+        let connection = gio::bus_get_sync(BusType::Session, None::<&gio::Cancellable>)?;
+        let version = connection
+            .call_sync(
+                Some(PORTAL_BUS_NAME),
+                PORTAL_OBJECT_PATH,
+                "org.freedesktop.DBus.Properties",
+                "Get",
+                Some(&(GLOBAL_SHORTCUTS_INTERFACE, "version").to_variant()),
+                None,
+                DBusCallFlags::NONE,
+                -1,
+                None::<&gio::Cancellable>,
+            )
+            .map_err(|_| PlatformError::NotAvailable)?;
+        if version.child_value(0).as_variant().is_none() {
+            return Err(PlatformError::NotAvailable);
+        }
+
+        let subscription = connection.signal_subscribe(
+            Some(PORTAL_BUS_NAME),
+            Some(GLOBAL_SHORTCUTS_INTERFACE),
+            Some("Activated"),
+            Some(PORTAL_OBJECT_PATH),
+            None,
+            DBusSignalFlags::NONE,
+            move |_, _, _, _, _, parameters| {
+                if let Some(id) = parameters.try_child_value(1) {
+                    if let Some(id) = id.str() {
+                        on_activated(id);
+                    }
+                }
+            },
+        );
+
+        Ok(Self {
+            connection,
+            session: RefCell::new(SessionState::None),
+            next_token: Cell::new(1),
+            activated_subscription: Cell::new(Some(subscription)),
+        })
+    }
+
+    // Replaces all shortcuts bound by this application.
+    pub async fn bind_shortcuts(self: Rc<Self>, shortcuts: Vec<Shortcut>) -> PlatformResult<()> {
+        let session = self.clone().session_handle().await?;
+        let shortcuts: Vec<(String, HashMap<String, Variant>)> = shortcuts
+            .into_iter()
+            .map(|s| {
+                let mut options = HashMap::new();
+                options.insert("description".into(), s.description.to_variant());
+                options.insert("preferred_trigger".into(), s.preferred_trigger.to_variant());
+                (s.id, options)
+            })
+            .collect();
+        let token = self.next_token();
+        let parameters = Variant::from_tuple(&[
+            object_path_variant(&session),
+            shortcuts.to_variant(),
+            "".to_variant(),
+            Self::request_options(&token).to_variant(),
+        ]);
+        self.request("BindShortcuts", &parameters, &token).await?;
+        Ok(())
+    }
+
+    async fn session_handle(self: Rc<Self>) -> PlatformResult<String> {
+        let waiting = {
+            let mut session = self.session.borrow_mut();
+            match &mut *session {
+                SessionState::Created(handle) => return Ok(handle.clone()),
+                SessionState::Creating(waiters) => {
+                    let (future, completer) = FutureCompleter::new();
+                    waiters.push(completer);
+                    Some(future)
+                }
+                SessionState::None => {
+                    *session = SessionState::Creating(Vec::new());
+                    None
+                }
+            }
+        };
+        if let Some(waiting) = waiting {
+            return waiting.await;
+        }
+
+        let res = self.create_session().await;
+        let state = match &res {
+            Ok(handle) => SessionState::Created(handle.clone()),
+            Err(_) => SessionState::None,
+        };
+        if let SessionState::Creating(waiters) = self.session.replace(state) {
+            for waiter in waiters {
+                waiter.complete(res.clone());
+            }
+        }
+        res
+    }
+
+    async fn create_session(&self) -> PlatformResult<String> {
+        let token = self.next_token();
+        let mut options = Self::request_options(&token);
+        options.insert("session_handle_token".into(), token.to_variant());
+        let results = self
+            .request("CreateSession", &(options,).to_variant(), &token)
+            .await?;
+        results
+            .get("session_handle")
+            .and_then(|h| h.str().map(|s| s.to_owned()))
+            .ok_or_else(|| PlatformError::OtherError {
+                error: "Portal did not return session handle".into(),
+            })
+    }
+
+    fn next_token(&self) -> String {
+        let token = self.next_token.get();
+        self.next_token.set(token + 1);
+        format!("nativeshell{}", token)
+    }
+
+    fn request_options(token: &str) -> HashMap<String, Variant> {
+        let mut options = HashMap::new();
+        options.insert("handle_token".into(), token.to_variant());
+        options
+    }
+
+    // Invokes portal method and waits for the Response signal on resulting
+    // request object.
+    async fn request(
+        &self,
+        method: &str,
+        parameters: &Variant,
+        token: &str,
+    ) -> PlatformResult<HashMap<String, Variant>> {
+        // Request object path is predictable; Subscribe before making the call
+        // so that the response can't be missed.
+        let sender = self
+            .connection
+            .unique_name()
+            .map(|n| n.trim_start_matches(':').replace('.', "_"))
+            .unwrap_or_default();
+        let request_path = format!("{}/request/{}/{}", PORTAL_OBJECT_PATH, sender, token);
+
+        let (future, completer) = FutureCompleter::new();
+        let completer = RefCell::new(Some(completer));
+        let subscription = self.connection.signal_subscribe(
+            Some(PORTAL_BUS_NAME),
+            Some(REQUEST_INTERFACE),
+            Some("Response"),
+            Some(&request_path),
+            None,
+            DBusSignalFlags::NONE,
+            move |_, _, _, _, _, parameters| {
+                if let Some(completer) = completer.take() {
+                    completer.complete(parameters.get::<(u32, HashMap<String, Variant>)>());
+                }
+            },
+        );
+
+        let call = self
+            .connection
+            .call_future(
+                Some(PORTAL_BUS_NAME),
+                PORTAL_OBJECT_PATH,
+                GLOBAL_SHORTCUTS_INTERFACE,
+                method,
+                Some(parameters),
+                None,
+                DBusCallFlags::NONE,
+                -1,
+            )
+            .await;
+        let response = match call {
+            Ok(_) => Ok(future.await),
+            Err(error) => Err(PlatformError::from(error)),
+        };
+        self.connection.signal_unsubscribe(subscription);
+
+        match response? {
+            Some((0, results)) => Ok(results),
+            Some((1, _)) => Err(PlatformError::OtherError {
+                error: format!("{} was cancelled by user", method),
+            }),
+            _ => Err(PlatformError::OtherError {
+                error: format!("{} failed", method),
+            }),
+        }
+    }
+}
+
+impl Drop for GlobalShortcuts {
+    fn drop(&mut self) {
+        if let Some(subscription) = self.activated_subscription.take() {
+            self.connection.signal_unsubscribe(subscription);
+        }
+        if let SessionState::Created(session) = &*self.session.borrow() {
+            self.connection.call(
+                Some(PORTAL_BUS_NAME),
+                session,
+                "org.freedesktop.portal.Session",
+                "Close",
+                None,
+                None,
+                DBusCallFlags::NONE,
+                -1,
+                None::<&gio::Cancellable>,
+                |_| {},
+            );
+        }
+    }
+}
@@ -1,35 +1,362 @@
-use std::{cell::RefCell, rc::Weak};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    os::raw::{c_int, c_uint},
+    rc::{Rc, Weak},
+};
+
+use gdk::Display;
+use glib::translate::ToGlibPtr;
+use log::warn;
 
-use crate::shell::{
-    api_model::Accelerator, Context, EngineHandle, HotKeyHandle, HotKeyManagerDelegate,
+use crate::{
+    shell::{api_model::Accelerator, Context, EngineHandle, HotKeyHandle, HotKeyManagerDelegate},
+    util::LateRefCell,
 };
 
-use super::error::{PlatformError, PlatformResult};
+use super::{
+    error::{PlatformError, PlatformResult},
+    global_shortcuts::{GlobalShortcuts, Shortcut},
+    keyboard_map::{keycode_for_platform_key, keysym_name_for_platform_key},
+    utils::{get_session_type, SessionType},
+    x11_sys,
+};
 
-pub(crate) struct PlatformHotKeyManager {}
+struct HotKey {
+    engine: EngineHandle,
+    accelerator: Accelerator,
+    platform_key: i64,
+}
+
+enum Backend {
+    X11(X11Grabs),
+    Portal(Rc<GlobalShortcuts>),
+    None,
+}
+
+pub(crate) struct PlatformHotKeyManager {
+    context: Context,
+    delegate: Weak<RefCell<dyn HotKeyManagerDelegate>>,
+    hot_keys: RefCell<HashMap<HotKeyHandle, HotKey>>,
+    backend: LateRefCell<Backend>,
+    weak_self: LateRefCell<Weak<PlatformHotKeyManager>>,
+}
 
 impl PlatformHotKeyManager {
-    pub fn new(_context: Context, _delegate: Weak<RefCell<dyn HotKeyManagerDelegate>>) -> Self {
-        Self {}
+    pub fn new(context: Context, delegate: Weak<RefCell<dyn HotKeyManagerDelegate>>) -> Self {
+        Self {
+            context,
+            delegate,
+            hot_keys: RefCell::new(HashMap::new()),
+            backend: LateRefCell::new(),
+            weak_self: LateRefCell::new(),
+        }
+    }
+
+    pub fn assign_weak_self(&self, weak: Weak<PlatformHotKeyManager>) {
+        self.weak_self.set(weak.clone());
+        let backend = match get_session_type() {
+            SessionType::X11 => X11Grabs::new(weak).map(Backend::X11),
+            SessionType::Wayland => GlobalShortcuts::new(move |id| {
+                if let Some(manager) = weak.upgrade() {
+                    if let Some(handle) = id
+                        .strip_prefix("hotkey")
+                        .and_then(|h| h.parse::<i64>().ok())
+                    {
+                        manager.on_hot_key_pressed(HotKeyHandle(handle));
+                    }
+                }
+            })
+            .map(|s| Backend::Portal(Rc::new(s)))
+            .ok(),
+        };
+        self.backend.set(backend.unwrap_or(Backend::None));
     }
 
-    pub fn assign_weak_self(&self, _weak: Weak<PlatformHotKeyManager>) {}
+    fn on_hot_key_pressed(&self, handle: HotKeyHandle) {
+        let engine = self.hot_keys.borrow().get(&handle).map(|k| k.engine);
+        if let (Some(engine), Some(delegate)) = (engine, self.delegate.upgrade()) {
+            delegate.borrow().on_hot_key_pressed(handle, engine);
+        }
+    }
 
-    pub fn create_hot_key(
+    pub fn create_hot_key<F>(
         &self,
-        _accelerator: Accelerator,
-        _virtual_key: i64,
-        _handle: HotKeyHandle,
-        _engine: EngineHandle,
-    ) -> PlatformResult<()> {
-        Err(PlatformError::NotAvailable)
+        accelerator: Accelerator,
+        virtual_key: i64,
+        handle: HotKeyHandle,
+        engine: EngineHandle,
+        on_done: F,
+    ) where
+        F: FnOnce(PlatformResult<()>) + 'static,
+    {
+        let hot_key = HotKey {
+            engine,
+            accelerator,
+            platform_key: virtual_key,
+        };
+        match &*self.backend.borrow() {
+            Backend::X11(grabs) => {
+                let res = grabs.grab(&hot_key);
+                if res.is_ok() {
+                    self.hot_keys.borrow_mut().insert(handle, hot_key);
+                }
+                on_done(res);
+            }
+            Backend::Portal(portal) => {
+                if let Err(error) = Self::shortcut_for_hot_key(handle, &hot_key) {
+                    on_done(Err(error));
+                    return;
+                }
+                self.hot_keys.borrow_mut().insert(handle, hot_key);
+                let bind = portal.clone().bind_shortcuts(self.shortcuts());
+                let weak = self.weak_self.clone_value();
+                match self.context.get() {
+                    Some(context) => {
+                        context.run_loop.borrow().spawn(async move {
+                            let res = bind.await;
+                            if res.is_err() {
+                                if let Some(manager) = weak.upgrade() {
+                                    manager.hot_keys.borrow_mut().remove(&handle);
+                                }
+                            }
+                            on_done(res);
+                        });
+                    }
+                    None => on_done(Err(PlatformError::UnknownError)),
+                }
+            }
+            Backend::None => on_done(Err(PlatformError::NotAvailable)),
+        }
     }
 
-    pub fn destroy_hot_key(&self, _handle: HotKeyHandle) -> PlatformResult<()> {
-        Err(PlatformError::NotAvailable)
+    pub fn destroy_hot_key(&self, handle: HotKeyHandle) -> PlatformResult<()> {
+        let hot_key = self.hot_keys.borrow_mut().remove(&handle);
+        if let Some(hot_key) = hot_key {
+            match &*self.backend.borrow() {
+                Backend::X11(grabs) => grabs.ungrab(&hot_key),
+                Backend::Portal(portal) => self.rebind_shortcuts(portal),
+                Backend::None => {}
+            }
+        }
+        Ok(())
     }
 
-    pub fn engine_destroyed(&self, _engine: EngineHandle) -> PlatformResult<()> {
+    pub fn engine_destroyed(&self, engine: EngineHandle) -> PlatformResult<()> {
+        let hot_keys: Vec<HotKeyHandle> = self
+            .hot_keys
+            .borrow()
+            .iter()
+            .filter_map(|(key, k)| if k.engine == engine { Some(*key) } else { None })
+            .collect();
+        for key in hot_keys {
+            self.destroy_hot_key(key)?;
+        }
         Ok(())
     }
+
+    fn shortcuts(&self) -> Vec<Shortcut> {
+        self.hot_keys
+            .borrow()
+            .iter()
+            .filter_map(|(handle, hot_key)| Self::shortcut_for_hot_key(*handle, hot_key).ok())
+            .collect()
+    }
+
+    fn rebind_shortcuts(&self, portal: &Rc<GlobalShortcuts>) {
+        let bind = portal.clone().bind_shortcuts(self.shortcuts());
+        if let Some(context) = self.context.get() {
+            context.run_loop.borrow().spawn(async move {
+                if let Err(error) = bind.await {
+                    warn!("Failed to update global shortcuts: {}", error);
+                }
+            });
+        }
+    }
+
+    fn shortcut_for_hot_key(handle: HotKeyHandle, hot_key: &HotKey) -> PlatformResult<Shortcut> {
+        let key = keysym_name_for_platform_key(hot_key.platform_key).ok_or_else(|| {
+            PlatformError::OtherError {
+                error: format!("Unknown platform key {}", hot_key.platform_key),
+            }
+        })?;
+        let accelerator = &hot_key.accelerator;
+        let mut trigger = String::new();
+        if accelerator.control {
+            trigger.push_str("CTRL+");
+        }
+        if accelerator.alt {
+            trigger.push_str("ALT+");
+        }
+        if accelerator.shift {
+            trigger.push_str("SHIFT+");
+        }
+        if accelerator.meta {
+            trigger.push_str("LOGO+");
+        }
+        trigger.push_str(&key);
+        Ok(Shortcut {
+            id: format!("hotkey{}", handle.0),
+            description: if accelerator.label.is_empty() {
+                trigger.clone()
+            } else {
+                accelerator.label.clone()
+            },
+            preferred_trigger: trigger,
+        })
+    }
+}
+
+// Key grabs on X11 root window
+struct X11Grabs {
+    display: Display,
+    filter_data: *mut Weak<PlatformHotKeyManager>,
+}
+
+// Lock modifiers must not prevent hot key from firing, so each key is grabbed
+// with every combination of these.
+const IGNORED_MODIFIERS: [c_uint; 4] = [
+    0,
+    x11_sys::LockMask,
+    x11_sys::Mod2Mask,
+    x11_sys::LockMask | x11_sys::Mod2Mask,
+];
+
+const RELEVANT_MODIFIERS: c_uint =
+    x11_sys::ShiftMask | x11_sys::ControlMask | x11_sys::Mod1Mask | x11_sys::Mod4Mask;
+
+impl X11Grabs {
+    fn new(manager: Weak<PlatformHotKeyManager>) -> Option<Self> {
+        let display = Display::default()?;
+        let filter_data = Box::into_raw(Box::new(manager));
+        unsafe {
+            gdk_sys::gdk_window_add_filter(
+                std::ptr::null_mut(),
+                Some(Self::filter),
+                filter_data as *mut _,
+            );
+        }
+        Some(Self {
+            display,
+            filter_data,
+        })
+    }
+
+    fn modifiers(accelerator: &Accelerator) -> c_uint {
+        let mut modifiers = 0;
+        if accelerator.shift {
+            modifiers |= x11_sys::ShiftMask;
+        }
+        if accelerator.control {
+            modifiers |= x11_sys::ControlMask;
+        }
+        if accelerator.alt {
+            modifiers |= x11_sys::Mod1Mask;
+        }
+        if accelerator.meta {
+            modifiers |= x11_sys::Mod4Mask;
+        }
+        modifiers
+    }
+
+    fn grab(&self, hot_key: &HotKey) -> PlatformResult<()> {
+        let keycode = keycode_for_platform_key(hot_key.platform_key).ok_or_else(|| {
+            PlatformError::OtherError {
+                error: format!("Unknown platform key {}", hot_key.platform_key),
+            }
+        })?;
+        let modifiers = Self::modifiers(&hot_key.accelerator);
+        let display: *mut gdk_sys::GdkDisplay = self.display.to_glib_none().0;
+        let error = unsafe {
+            let xdisplay = x11_sys::gdk_x11_display_get_xdisplay(display);
+            let root = x11_sys::XDefaultRootWindow(xdisplay);
+            // Grab failure is reported asynchronously as X error
+            x11_sys::gdk_x11_display_error_trap_push(display);
+            for ignored in IGNORED_MODIFIERS {
+                x11_sys::XGrabKey(
+                    xdisplay,
+                    keycode as c_int,
+                    modifiers | ignored,
+                    root,
+                    0,
+                    x11_sys::GrabModeAsync,
+                    x11_sys::GrabModeAsync,
+                );
+            }
+            x11_sys::gdk_x11_display_error_trap_pop(display)
+        };
+        if error != 0 {
+            self.ungrab(hot_key);
+            Err(PlatformError::OtherError {
+                error: "Hot key is already registered by another application".into(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn ungrab(&self, hot_key: &HotKey) {
+        let keycode = match keycode_for_platform_key(hot_key.platform_key) {
+            Some(keycode) => keycode,
+            None => return,
+        };
+        let modifiers = Self::modifiers(&hot_key.accelerator);
+        let display: *mut gdk_sys::GdkDisplay = self.display.to_glib_none().0;
+        unsafe {
+            let xdisplay = x11_sys::gdk_x11_display_get_xdisplay(display);
+            let root = x11_sys::XDefaultRootWindow(xdisplay);
+            x11_sys::gdk_x11_display_error_trap_push(display);
+            for ignored in IGNORED_MODIFIERS {
+                x11_sys::XUngrabKey(xdisplay, keycode as c_int, modifiers | ignored, root);
+            }
+            x11_sys::gdk_x11_display_error_trap_pop(display);
+        }
+    }
+
+    unsafe extern "C" fn filter(
+        xevent: *mut gdk_sys::GdkXEvent,
+        _event: *mut gdk_sys::GdkEvent,
+        data: glib_sys::gpointer,
+    ) -> gdk_sys::GdkFilterReturn {
+        let event = xevent as *const x11_sys::XKeyEvent;
+        if (*event).type_ != x11_sys::KeyPress {
+            return gdk_sys::GDK_FILTER_CONTINUE;
+        }
+        let manager = &*(data as *const Weak<PlatformHotKeyManager>);
+        let manager = match manager.upgrade() {
+            Some(manager) => manager,
+            None => return gdk_sys::GDK_FILTER_CONTINUE,
+        };
+        let keycode = (*event).keycode as i64;
+        let modifiers = (*event).state & RELEVANT_MODIFIERS;
+        let handle = manager
+            .hot_keys
+            .borrow()
+            .iter()
+            .find(|(_, k)| {
+                keycode_for_platform_key(k.platform_key) == Some(keycode as u32)
+                    && Self::modifiers(&k.accelerator) == modifiers
+            })
+            .map(|(handle, _)| *handle);
+        match handle {
+            Some(handle) => {
+                manager.on_hot_key_pressed(handle);
+                gdk_sys::GDK_FILTER_REMOVE
+            }
+            None => gdk_sys::GDK_FILTER_CONTINUE,
+        }
+    }
+}
+
+impl Drop for X11Grabs {
+    fn drop(&mut self) {
+        unsafe {
+            gdk_sys::gdk_window_remove_filter(
+                std::ptr::null_mut(),
+                Some(Self::filter),
+                self.filter_data as *mut _,
+            );
+            drop(Box::from_raw(self.filter_data));
+        }
+    }
 }
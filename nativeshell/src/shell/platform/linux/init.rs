@@ -3,15 +3,24 @@ use std::{ptr, rc::Weak};
 use gdk::Event;
 use glib::ObjectExt;
 
-use crate::shell::platform::window::PlatformWindow;
+use crate::shell::{platform::window::PlatformWindow, ContextOptions};
 
 use super::error::{PlatformError, PlatformResult};
 
-pub fn init_platform() -> PlatformResult<()> {
+pub fn init_platform(options: &ContextOptions) -> PlatformResult<()> {
+    // Program name is used for WM_CLASS on X11 and app_id on Wayland
+    if let Some(application_id) = &options.application_id {
+        glib::set_prgname(Some(application_id));
+    }
+
     gtk::init().map_err(|e| PlatformError::GLibError {
         message: e.message.into(),
     })?;
 
+    if let Some(application_id) = &options.application_id {
+        gdk::set_program_class(application_id);
+    }
+
     Event::set_handler(Some(|e: &mut Event| {
         let win = e.window().map(|w| w.toplevel());
 
@@ -22,7 +31,9 @@ pub fn init_platform() -> PlatformResult<()> {
             if let Some(platform_window) =
                 platform_window.and_then(|w| unsafe { w.as_ref() }.upgrade())
             {
-                platform_window.on_event(e);
+                if platform_window.on_event(e) {
+                    return;
+                }
             }
         }
 
@@ -256,3 +256,26 @@ impl PlatformKeyboardMap {
         }
     }
 }
+
+// Returns X11 keycode for platform key if it is part of the keyboard map.
+pub(super) fn keycode_for_platform_key(platform_key: i64) -> Option<u32> {
+    get_key_map()
+        .iter()
+        .find(|e| e.platform == platform_key)
+        .map(|e| e.platform as u32)
+}
+
+// Returns keysym name (i.e. "a", "F1", "Return") produced by the key in first
+// layout group without modifiers.
+pub(super) fn keysym_name_for_platform_key(platform_key: i64) -> Option<String> {
+    let keycode = keycode_for_platform_key(platform_key)?;
+    let keymap = Keymap::for_display(&Display::default()?)?;
+    keymap
+        .lookup_key(&gdk::KeymapKey {
+            keycode,
+            group: 0,
+            level: 0,
+        })?
+        .name()
+        .map(|n| n.to_string())
+}
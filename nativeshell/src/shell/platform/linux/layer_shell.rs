@@ -0,0 +1,165 @@
+// Bindings for gtk-layer-shell (zwlr_layer_shell_v1).
+//
+// The library is loaded at runtime so that it remains an optional dependency;
+// When it is missing (or compositor doesn't support the protocol) windows
+// fall back to regular toplevels.
+
+use std::{
+    ffi::CString,
+    os::raw::{c_char, c_int, c_void},
+};
+
+use glib::translate::ToGlibPtr;
+use gtk_sys::GtkWindow;
+use once_cell::sync::OnceCell;
+
+use crate::shell::api_model::{
+    LayerShellKeyboardInteractivity, LayerShellLayer, LayerShellOptions,
+};
+
+// GtkLayerShellEdge
+const EDGE_LEFT: c_int = 0;
+const EDGE_RIGHT: c_int = 1;
+const EDGE_TOP: c_int = 2;
+const EDGE_BOTTOM: c_int = 3;
+
+type Gboolean = c_int;
+
+struct LayerShellLib {
+    is_supported: Option<unsafe extern "C" fn() -> Gboolean>,
+    init_for_window: unsafe extern "C" fn(*mut GtkWindow),
+    is_layer_window: unsafe extern "C" fn(*mut GtkWindow) -> Gboolean,
+    set_namespace: unsafe extern "C" fn(*mut GtkWindow, *const c_char),
+    set_layer: unsafe extern "C" fn(*mut GtkWindow, c_int),
+    set_anchor: unsafe extern "C" fn(*mut GtkWindow, c_int, Gboolean),
+    set_margin: unsafe extern "C" fn(*mut GtkWindow, c_int, c_int),
+    set_exclusive_zone: unsafe extern "C" fn(*mut GtkWindow, c_int),
+    auto_exclusive_zone_enable: unsafe extern "C" fn(*mut GtkWindow),
+    set_keyboard_interactivity: unsafe extern "C" fn(*mut GtkWindow, Gboolean),
+    // Only available in gtk-layer-shell 0.6 and newer
+    set_keyboard_mode: Option<unsafe extern "C" fn(*mut GtkWindow, c_int)>,
+}
+
+impl LayerShellLib {
+    fn load() -> Option<Self> {
+        unsafe {
+            let name = CString::new("libgtk-layer-shell.so.0").unwrap();
+            let handle = libc::dlopen(name.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL);
+            if handle.is_null() {
+                return None;
+            }
+            let symbol = |name: &str| -> Option<*mut c_void> {
+                let name = CString::new(name).unwrap();
+                let symbol = libc::dlsym(handle, name.as_ptr());
+                if symbol.is_null() {
+                    None
+                } else {
+                    Some(symbol)
+                }
+            };
+            macro_rules! function {
+                ($name:expr) => {
+                    symbol($name).map(|s| std::mem::transmute(s))
+                };
+            }
+            Some(Self {
+                is_supported: function!("gtk_layer_is_supported"),
+                init_for_window: function!("gtk_layer_init_for_window")?,
+                is_layer_window: function!("gtk_layer_is_layer_window")?,
+                set_namespace: function!("gtk_layer_set_namespace")?,
+                set_layer: function!("gtk_layer_set_layer")?,
+                set_anchor: function!("gtk_layer_set_anchor")?,
+                set_margin: function!("gtk_layer_set_margin")?,
+                set_exclusive_zone: function!("gtk_layer_set_exclusive_zone")?,
+                auto_exclusive_zone_enable: function!("gtk_layer_auto_exclusive_zone_enable")?,
+                set_keyboard_interactivity: function!("gtk_layer_set_keyboard_interactivity")?,
+                set_keyboard_mode: function!("gtk_layer_set_keyboard_mode"),
+            })
+        }
+    }
+}
+
+fn layer_shell() -> Option<&'static LayerShellLib> {
+    static LIB: OnceCell<Option<LayerShellLib>> = OnceCell::new();
+    LIB.get_or_init(|| {
+        LayerShellLib::load().filter(|lib| match lib.is_supported {
+            Some(is_supported) => unsafe { is_supported() != 0 },
+            None => true,
+        })
+    })
+    .as_ref()
+}
+
+pub(super) fn layer_shell_available() -> bool {
+    layer_shell().is_some()
+}
+
+pub(super) fn is_layer_window(window: &gtk::Window) -> bool {
+    match layer_shell() {
+        Some(lib) => unsafe { (lib.is_layer_window)(window.to_glib_none().0) != 0 },
+        None => false,
+    }
+}
+
+// Must be called before window is realized.
+pub(super) fn init_for_window(window: &gtk::Window) {
+    if let Some(lib) = layer_shell() {
+        unsafe { (lib.init_for_window)(window.to_glib_none().0) }
+    }
+}
+
+pub(super) fn configure_window(window: &gtk::Window, options: &LayerShellOptions) {
+    let lib = match layer_shell() {
+        Some(lib) => lib,
+        None => return,
+    };
+    let window: *mut GtkWindow = window.to_glib_none().0;
+    unsafe {
+        if let Some(namespace) = &options.namespace {
+            let namespace = CString::new(namespace.as_str()).unwrap_or_default();
+            (lib.set_namespace)(window, namespace.as_ptr());
+        }
+        let layer = match options.layer {
+            LayerShellLayer::Background => 0,
+            LayerShellLayer::Bottom => 1,
+            LayerShellLayer::Top => 2,
+            LayerShellLayer::Overlay => 3,
+        };
+        (lib.set_layer)(window, layer);
+
+        let anchor = &options.anchor;
+        let margin = &options.margin;
+        for (edge, anchored, margin) in [
+            (EDGE_LEFT, anchor.left, margin.left),
+            (EDGE_RIGHT, anchor.right, margin.right),
+            (EDGE_TOP, anchor.top, margin.top),
+            (EDGE_BOTTOM, anchor.bottom, margin.bottom),
+        ] {
+            (lib.set_anchor)(window, edge, anchored as Gboolean);
+            (lib.set_margin)(window, edge, margin);
+        }
+
+        if options.auto_exclusive_zone {
+            (lib.auto_exclusive_zone_enable)(window);
+        } else {
+            (lib.set_exclusive_zone)(window, options.exclusive_zone);
+        }
+
+        match lib.set_keyboard_mode {
+            Some(set_keyboard_mode) => {
+                let mode = match options.keyboard_interactivity {
+                    LayerShellKeyboardInteractivity::None => 0,
+                    LayerShellKeyboardInteractivity::Exclusive => 1,
+                    LayerShellKeyboardInteractivity::OnDemand => 2,
+                };
+                set_keyboard_mode(window, mode);
+            }
+            None => {
+                // On demand mode requires newer protocol version
+                let interactive =
+                    options.keyboard_interactivity != LayerShellKeyboardInteractivity::None;
+                (lib.set_keyboard_interactivity)(window, interactive as Gboolean);
+            }
+        }
+    }
+}
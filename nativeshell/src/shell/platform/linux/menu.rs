@@ -8,10 +8,15 @@ use std::{
 };
 
 use gdk::ModifierType;
-use glib::{Cast, ObjectExt};
+use gio::{
+    prelude::{ActionGroupExt, ActionMapExt, MenuModelExt},
+    SimpleAction, SimpleActionGroup,
+};
+use glib::{Cast, ObjectExt, ToVariant, VariantTy};
 use gtk::{
     prelude::{
-        AccelLabelExt, BinExt, ContainerExt, GtkMenuExt, GtkMenuItemExt, MenuShellExt, WidgetExt,
+        AccelGroupExt, AccelGroupExtManual, AccelLabelExt, BinExt, ContainerExt, GtkMenuExt,
+        GtkMenuItemExt, GtkWindowExt, MenuShellExt, WidgetExt,
     },
     AccelLabel, MenuDirectionType,
 };
@@ -25,11 +30,13 @@ use crate::{
 };
 
 use super::{
-    error::{PlatformError, PlatformResult},
+    error::PlatformResult,
+    global_menu::GlobalMenu,
     menu_item::{
         check_menu_item_set_checked, create_check_menu_item, create_radio_menu_item,
         radio_menu_item_set_checked,
     },
+    window::PlatformWindow,
 };
 
 pub struct PlatformMenu {
@@ -44,6 +51,11 @@ pub struct PlatformMenu {
     ignore_activate: Cell<bool>,
     pending_selection_done: Cell<bool>,
     delegate: Weak<RefCell<dyn MenuDelegate>>,
+    // Mirror of this menu for the application menu bar; Submenu items link
+    // models of child menus, so the whole tree updates in place.
+    pub(super) model: gio::Menu,
+    actions: RefCell<Option<SimpleActionGroup>>,
+    action_names: RefCell<Vec<String>>,
 }
 
 #[allow(unused_variables)]
@@ -67,6 +79,9 @@ impl PlatformMenu {
             ignore_activate: Cell::new(false),
             pending_selection_done: Cell::new(false),
             delegate,
+            model: gio::Menu::new(),
+            actions: RefCell::new(None),
+            action_names: RefCell::new(Vec::new()),
         }
     }
 
@@ -148,6 +163,8 @@ impl PlatformMenu {
     }
 
     pub fn update_from_menu(&self, menu: Menu, manager: &MenuManager) -> PlatformResult<()> {
+        self.update_model(&menu, manager);
+
         let mut previous_menu = self.previous_menu.borrow_mut();
 
         let diff = update_diff(&previous_menu.items, &menu.items, |a, b| {
@@ -214,6 +231,98 @@ impl PlatformMenu {
         Ok(())
     }
 
+    fn action_name(&self, item: &MenuItem) -> String {
+        format!("menu{}-item{}", self.handle.0, item.id)
+    }
+
+    fn update_model(&self, menu: &Menu, manager: &MenuManager) {
+        let actions = self
+            .actions
+            .borrow_mut()
+            .get_or_insert_with(|| manager.get_platform_menu_manager().actions.clone())
+            .clone();
+        for name in self.action_names.take() {
+            actions.remove_action(&name);
+        }
+
+        // Separators split GMenuModel into sections
+        self.model.remove_all();
+        let mut section = gio::Menu::new();
+        for item in &menu.items {
+            if item.separator {
+                if section.n_items() > 0 {
+                    self.model.append_section(None, &section);
+                    section = gio::Menu::new();
+                }
+                continue;
+            }
+            let label = Self::convert_mnemonics(&item.title);
+            let submenu = item.submenu.and_then(|s| manager.get_platform_menu(s).ok());
+            if let Some(submenu) = submenu {
+                section.append_submenu(Some(&label), &submenu.model);
+                continue;
+            }
+
+            let name = self.action_name(item);
+            let model_item = gio::MenuItem::new(Some(&label), None);
+            let action = match item.check_status {
+                CheckStatus::None => {
+                    model_item.set_detailed_action(&format!("app.{}", name));
+                    SimpleAction::new(&name, None)
+                }
+                CheckStatus::CheckOn | CheckStatus::CheckOff => {
+                    model_item.set_detailed_action(&format!("app.{}", name));
+                    let checked = item.check_status == CheckStatus::CheckOn;
+                    SimpleAction::new_stateful(&name, None, &checked.to_variant())
+                }
+                // Radio item is rendered for actions with target; It is
+                // selected when action state matches the target.
+                CheckStatus::RadioOn | CheckStatus::RadioOff => {
+                    model_item.set_action_and_target_value(
+                        Some(&format!("app.{}", name)),
+                        Some(&"on".to_variant()),
+                    );
+                    let state = if item.check_status == CheckStatus::RadioOn {
+                        "on"
+                    } else {
+                        "off"
+                    };
+                    SimpleAction::new_stateful(
+                        &name,
+                        Some(VariantTy::new("s").unwrap()),
+                        &state.to_variant(),
+                    )
+                }
+            };
+            if let Some(accelerator) = &item.accelerator {
+                let accel = gtk::accelerator_name(
+                    Self::accelerator_label_code(accelerator) as u32,
+                    Self::accelerator_modifier_type(accelerator),
+                );
+                if let Some(accel) = accel {
+                    model_item.set_attribute_value("accel", Some(&accel.to_variant()));
+                }
+            }
+            action.set_enabled(item.enabled);
+            let weak = self.weak_self.borrow().clone();
+            let id = item.id;
+            action.connect_activate(move |_, _| {
+                if let Some(s) = weak.upgrade() {
+                    if let Some(delegate) = s.delegate.upgrade() {
+                        delegate.borrow().on_menu_action(s.handle, id);
+                    }
+                }
+            });
+            actions.add_action(&action);
+            self.action_names.borrow_mut().push(name);
+            section.append_item(&model_item);
+        }
+        if section.n_items() > 0 {
+            self.model.append_section(None, &section);
+        }
+        manager.get_platform_menu_manager().update_accelerators();
+    }
+
     fn resize_menu_if_needed(&self) {
         let top_level = self.menu.toplevel();
         let win = top_level.as_ref().and_then(|w| w.window());
@@ -497,16 +606,149 @@ impl PlatformMenu {
     }
 }
 
-pub struct PlatformMenuManager {}
+impl Drop for PlatformMenu {
+    fn drop(&mut self) {
+        if let Some(actions) = self.actions.borrow().as_ref() {
+            for name in self.action_names.borrow().iter() {
+                actions.remove_action(name);
+            }
+        }
+    }
+}
+
+pub struct PlatformMenuManager {
+    // Actions of all menu items, prefixed with "app." in menu models
+    pub(super) actions: SimpleActionGroup,
+    app_menu: RefCell<Option<Rc<PlatformMenu>>>,
+    windows: RefCell<Vec<Weak<PlatformWindow>>>,
+    // Set on first use; None if there is no global menu on this desktop
+    global_menu: LateRefCell<Option<GlobalMenu>>,
+    // "accel" attribute in menu model is only displayed; Accelerators of app
+    // menu are activated through accel group added to every window.
+    accel_group: gtk::AccelGroup,
+    accelerators: RefCell<Vec<(u32, ModifierType)>>,
+}
 
 impl PlatformMenuManager {
     pub fn new(_context: Context) -> Self {
-        Self {}
+        Self {
+            actions: SimpleActionGroup::new(),
+            app_menu: RefCell::new(None),
+            windows: RefCell::new(Vec::new()),
+            global_menu: LateRefCell::new(),
+            accel_group: gtk::AccelGroup::new(),
+            accelerators: RefCell::new(Vec::new()),
+        }
     }
 
     pub(crate) fn assign_weak_self(&self, _weak_self: Weak<PlatformMenuManager>) {}
 
-    pub fn set_app_menu(&self, _menu: Option<Rc<PlatformMenu>>) -> PlatformResult<()> {
-        Err(PlatformError::NotAvailable)
+    pub fn set_app_menu(&self, menu: Option<Rc<PlatformMenu>>) -> PlatformResult<()> {
+        if !self.global_menu.is_set() {
+            self.global_menu.set(GlobalMenu::new(&self.actions));
+        }
+        let model = menu.as_ref().map(|m| &m.model);
+        if let Some(global_menu) = &*self.global_menu.borrow() {
+            global_menu.set_menu(model)?;
+        }
+        self.app_menu.replace(menu.clone());
+        self.update_accelerators();
+
+        self.windows.borrow_mut().retain(|w| w.strong_count() > 0);
+        let windows: Vec<_> = self
+            .windows
+            .borrow()
+            .iter()
+            .filter_map(|w| w.upgrade())
+            .collect();
+        for window in windows {
+            self.update_window(&window);
+        }
+        Ok(())
+    }
+
+    // Called for every window after it has been realized
+    pub(super) fn register_window(&self, window: Weak<PlatformWindow>) {
+        if let Some(window) = window.upgrade() {
+            window.window.add_accel_group(&self.accel_group);
+            if self.app_menu.borrow().is_some() {
+                self.update_window(&window);
+            }
+        }
+        self.windows.borrow_mut().push(window);
+    }
+
+    // Wayland surface is recreated every time window is shown, so it must be
+    // associated with global menu again
+    pub(super) fn window_mapped(&self, window: &PlatformWindow) {
+        if self.app_menu.borrow().is_some() && self.global_menu.borrow().is_some() {
+            self.update_window(window);
+        }
+    }
+
+    fn update_accelerators(&self) {
+        for (key, modifiers) in self.accelerators.take() {
+            self.accel_group.disconnect_key(key, modifiers);
+        }
+        let mut accelerators = Vec::new();
+        if let Some(app_menu) = &*self.app_menu.borrow() {
+            Self::collect_accelerators(app_menu.model.upcast_ref(), &mut accelerators);
+        }
+        for (key, modifiers, action, target) in accelerators {
+            let actions = self.actions.clone();
+            self.accel_group.connect_accel_group(
+                key,
+                modifiers,
+                gtk::AccelFlags::VISIBLE,
+                move |_, _, _, _| {
+                    if actions.is_action_enabled(&action) {
+                        actions.activate_action(&action, target.as_ref());
+                    }
+                    true
+                },
+            );
+            self.accelerators.borrow_mut().push((key, modifiers));
+        }
+    }
+
+    fn collect_accelerators(
+        model: &gio::MenuModel,
+        accelerators: &mut Vec<(u32, ModifierType, String, Option<glib::Variant>)>,
+    ) {
+        for i in 0..model.n_items() {
+            for link in ["section", "submenu"] {
+                if let Some(model) = model.item_link(i, link) {
+                    Self::collect_accelerators(&model, accelerators);
+                }
+            }
+            let accel = model
+                .item_attribute_value(i, "accel", Some(VariantTy::new("s").unwrap()))
+                .and_then(|v| v.str().map(|s| s.to_owned()));
+            let action = model
+                .item_attribute_value(i, "action", Some(VariantTy::new("s").unwrap()))
+                .and_then(|v| v.str().map(|s| s.to_owned()));
+            if let (Some(accel), Some(action)) = (accel, action) {
+                let (key, modifiers) = gtk::accelerator_parse(&accel);
+                let action = action.trim_start_matches("app.").to_owned();
+                if key != 0 {
+                    let target = model.item_attribute_value(i, "target", None);
+                    accelerators.push((key, modifiers, action, target));
+                }
+            }
+        }
+    }
+
+    fn update_window(&self, window: &PlatformWindow) {
+        let app_menu = self.app_menu.borrow();
+        match &*self.global_menu.borrow() {
+            Some(global_menu) => {
+                if let Some(gdk_window) = window.window.window() {
+                    global_menu.register_window(&gdk_window);
+                }
+            }
+            None => {
+                window.set_app_menu_bar(app_menu.as_ref().map(|m| &m.model), &self.actions);
+            }
+        }
     }
 }
@@ -1,20 +1,33 @@
+mod alert;
 pub mod app_delegate;
 pub mod binary_messenger;
+pub mod clipboard;
+mod dbus_menu;
+pub mod dialog;
 pub mod drag_context;
 pub mod drag_data;
 pub mod engine;
 pub mod error;
 pub mod flutter;
 pub mod flutter_sys;
+pub mod global_menu;
+pub mod global_shortcuts;
 pub mod hot_key;
 pub mod init;
 pub mod keyboard_map;
+mod layer_shell;
 pub mod menu;
 pub mod menu_item;
+pub mod notification;
+mod pointer_lock;
+pub mod pointer_manager;
 pub mod run_loop;
 pub mod screen_manager;
 pub mod size_widget;
 pub mod status_item;
+pub mod status_notifier_item;
 pub mod utils;
+mod wayland_sys;
 pub mod window;
 pub mod window_menu;
+pub mod x11_sys;
@@ -0,0 +1,244 @@
+// Client for org.freedesktop.Notifications
+// https://specifications.freedesktop.org/notification-spec/latest/
+
+use std::{cell::RefCell, collections::HashMap, rc::Weak};
+
+use gio::{BusType, DBusCallFlags, DBusConnection, DBusSignalFlags, SignalSubscriptionId};
+use glib::{ToVariant, Variant};
+
+use crate::{
+    shell::{
+        api_model::{ImageData, NotificationCloseReason, NotificationShowRequest},
+        Context, NotificationHandle, NotificationManagerDelegate,
+    },
+    util::LateRefCell,
+};
+
+use super::error::{PlatformError, PlatformResult};
+
+const NOTIFICATIONS_BUS_NAME: &str = "org.freedesktop.Notifications";
+const NOTIFICATIONS_OBJECT_PATH: &str = "/org/freedesktop/Notifications";
+const NOTIFICATIONS_INTERFACE: &str = "org.freedesktop.Notifications";
+
+// Action invoked when the notification itself is clicked
+const DEFAULT_ACTION: &str = "default";
+
+pub struct PlatformNotificationManager {
+    context: Context,
+    delegate: Weak<RefCell<dyn NotificationManagerDelegate>>,
+    connection: LateRefCell<Option<DBusConnection>>,
+    // Maps handles to ids assigned by notification server
+    notifications: RefCell<HashMap<NotificationHandle, u32>>,
+    subscriptions: RefCell<Vec<SignalSubscriptionId>>,
+    weak_self: LateRefCell<Weak<PlatformNotificationManager>>,
+}
+
+impl PlatformNotificationManager {
+    pub fn new(context: Context, delegate: Weak<RefCell<dyn NotificationManagerDelegate>>) -> Self {
+        Self {
+            context,
+            delegate,
+            connection: LateRefCell::new(),
+            notifications: RefCell::new(HashMap::new()),
+            subscriptions: RefCell::new(Vec::new()),
+            weak_self: LateRefCell::new(),
+        }
+    }
+
+    pub fn assign_weak_self(&self, weak: Weak<PlatformNotificationManager>) {
+        self.weak_self.set(weak.clone());
+        let connection = gio::bus_get_sync(BusType::Session, None::<&gio::Cancellable>).ok();
+        if let Some(connection) = &connection {
+            let weak_closed = weak.clone();
+            let closed = connection.signal_subscribe(
+                Some(NOTIFICATIONS_BUS_NAME),
+                Some(NOTIFICATIONS_INTERFACE),
+                Some("NotificationClosed"),
+                Some(NOTIFICATIONS_OBJECT_PATH),
+                None,
+                DBusSignalFlags::NONE,
+                move |_, _, _, _, _, parameters| {
+                    if let (Some(manager), Some((id, reason))) =
+                        (weak_closed.upgrade(), parameters.get::<(u32, u32)>())
+                    {
+                        manager.on_closed(id, reason);
+                    }
+                },
+            );
+            let action_invoked = connection.signal_subscribe(
+                Some(NOTIFICATIONS_BUS_NAME),
+                Some(NOTIFICATIONS_INTERFACE),
+                Some("ActionInvoked"),
+                Some(NOTIFICATIONS_OBJECT_PATH),
+                None,
+                DBusSignalFlags::NONE,
+                move |_, _, _, _, _, parameters| {
+                    if let (Some(manager), Some((id, action))) =
+                        (weak.upgrade(), parameters.get::<(u32, String)>())
+                    {
+                        manager.on_action_invoked(id, action);
+                    }
+                },
+            );
+            self.subscriptions
+                .borrow_mut()
+                .extend([closed, action_invoked]);
+        }
+        self.connection.set(connection);
+    }
+
+    fn handle_for_id(&self, id: u32) -> Option<NotificationHandle> {
+        self.notifications
+            .borrow()
+            .iter()
+            .find_map(|(handle, i)| if *i == id { Some(*handle) } else { None })
+    }
+
+    fn on_closed(&self, id: u32, reason: u32) {
+        if let Some(handle) = self.handle_for_id(id) {
+            self.notifications.borrow_mut().remove(&handle);
+            let reason = match reason {
+                1 => NotificationCloseReason::Expired,
+                2 => NotificationCloseReason::Dismissed,
+                3 => NotificationCloseReason::Closed,
+                _ => NotificationCloseReason::Unknown,
+            };
+            if let Some(delegate) = self.delegate.upgrade() {
+                delegate.borrow().on_notification_closed(handle, reason);
+            }
+        }
+    }
+
+    fn on_action_invoked(&self, id: u32, action: String) {
+        if let Some(handle) = self.handle_for_id(id) {
+            let action = if action == DEFAULT_ACTION {
+                None
+            } else {
+                Some(action)
+            };
+            if let Some(delegate) = self.delegate.upgrade() {
+                delegate.borrow().on_notification_activated(handle, action);
+            }
+        }
+    }
+
+    pub fn show_notification<F>(
+        &self,
+        handle: NotificationHandle,
+        request: NotificationShowRequest,
+        on_done: F,
+    ) where
+        F: FnOnce(PlatformResult<()>) + 'static,
+    {
+        let connection = match &*self.connection.borrow() {
+            Some(connection) => connection.clone(),
+            None => return on_done(Err(PlatformError::NotAvailable)),
+        };
+        let context = match self.context.get() {
+            Some(context) => context,
+            None => return on_done(Err(PlatformError::UnknownError)),
+        };
+
+        let replaces_id = self
+            .notifications
+            .borrow()
+            .get(&handle)
+            .cloned()
+            .unwrap_or(0);
+        let mut actions = vec![DEFAULT_ACTION.to_string(), String::new()];
+        for action in request.actions {
+            actions.push(action.id);
+            actions.push(action.label);
+        }
+        let mut hints = HashMap::<String, Variant>::new();
+        if let Some(icon) = request.icon {
+            hints.insert("image-data".into(), Self::image_data_variant(icon));
+        }
+        let app_name = glib::application_name()
+            .map(|s| s.to_string())
+            .or_else(glib::prgname)
+            .unwrap_or_default();
+        let parameters = Variant::from_tuple(&[
+            app_name.to_variant(),
+            replaces_id.to_variant(),
+            "".to_variant(),
+            request.title.to_variant(),
+            request.body.to_variant(),
+            actions.to_variant(),
+            hints.to_variant(),
+            (-1i32).to_variant(),
+        ]);
+
+        let call = connection.call_future(
+            Some(NOTIFICATIONS_BUS_NAME),
+            NOTIFICATIONS_OBJECT_PATH,
+            NOTIFICATIONS_INTERFACE,
+            "Notify",
+            Some(&parameters),
+            None,
+            DBusCallFlags::NONE,
+            -1,
+        );
+        let weak = self.weak_self.clone_value();
+        context.run_loop.borrow().spawn(async move {
+            let res = match call.await {
+                Ok(result) => match result.get::<(u32,)>() {
+                    Some((id,)) => {
+                        if let Some(manager) = weak.upgrade() {
+                            manager.notifications.borrow_mut().insert(handle, id);
+                        }
+                        Ok(())
+                    }
+                    None => Err(PlatformError::OtherError {
+                        error: "Unexpected reply from notification server".into(),
+                    }),
+                },
+                Err(error) => Err(PlatformError::from(error)),
+            };
+            on_done(res);
+        });
+    }
+
+    pub fn close_notification(&self, handle: NotificationHandle) -> PlatformResult<()> {
+        let id = self.notifications.borrow_mut().remove(&handle);
+        if let (Some(id), Some(connection)) = (id, &*self.connection.borrow()) {
+            connection.call(
+                Some(NOTIFICATIONS_BUS_NAME),
+                NOTIFICATIONS_OBJECT_PATH,
+                NOTIFICATIONS_INTERFACE,
+                "CloseNotification",
+                Some(&(id,).to_variant()),
+                None,
+                DBusCallFlags::NONE,
+                -1,
+                None::<&gio::Cancellable>,
+                |_| {},
+            );
+        }
+        Ok(())
+    }
+
+    // (iiibiiay) - width, height, rowstride, has alpha, bits per sample,
+    // channels, RGBA data
+    fn image_data_variant(image: ImageData) -> Variant {
+        Variant::from_tuple(&[
+            image.width.to_variant(),
+            image.height.to_variant(),
+            image.bytes_per_row.to_variant(),
+            true.to_variant(),
+            8i32.to_variant(),
+            4i32.to_variant(),
+            image.data.to_variant(),
+        ])
+    }
+}
+
+impl Drop for PlatformNotificationManager {
+    fn drop(&mut self) {
+        if let Some(connection) = &*self.connection.borrow() {
+            for subscription in self.subscriptions.take() {
+                connection.signal_unsubscribe(subscription);
+            }
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use std::{os::raw::c_void, ptr::null_mut, rc::Weak};
+
+use gdk::{Cursor, CursorType, GrabStatus, Seat, SeatCapabilities};
+use glib::translate::ToGlibPtr;
+
+use crate::shell::{PlatformWindowDelegate, Point, Rect};
+
+use super::{
+    error::{PlatformError, PlatformResult},
+    utils::{get_session_type, SessionType},
+    wayland_sys::{self, wl_argument, wl_proxy, Wayland},
+};
+
+#[derive(Clone, Debug)]
+pub(super) enum PointerConstraint {
+    // Pointer stays in place and is hidden; Movement is reported as relative
+    // motion
+    Lock,
+    Confine,
+}
+
+enum Backend {
+    // X11 has no pointer constraints; Pointer is grabbed and warped back
+    // into region on motion
+    X11 {
+        seat: Seat,
+    },
+    Wayland {
+        wayland: &'static Wayland,
+        constraint: *mut wl_proxy,
+        relative_pointer: *mut wl_proxy,
+        listener_data: *mut Weak<dyn PlatformWindowDelegate>,
+    },
+}
+
+pub(super) struct PointerLock {
+    constraint: PointerConstraint,
+    window: gdk::Window,
+    // Region in window coordinates
+    region: Rect,
+    // Window with hidden cursor and its original cursor
+    hidden_cursor: Option<(gdk::Window, Option<Cursor>)>,
+    backend: Backend,
+}
+
+impl PointerLock {
+    // Region is in coordinates of the toplevel window; Cursor of cursor_window
+    // is hidden while pointer is locked.
+    pub fn new(
+        constraint: PointerConstraint,
+        window: &gdk::Window,
+        cursor_window: &gdk::Window,
+        region: Rect,
+        delegate: Weak<dyn PlatformWindowDelegate>,
+    ) -> PlatformResult<Self> {
+        let display = window.display();
+        let seat = display.default_seat().ok_or(PlatformError::NotAvailable)?;
+        let blank_cursor = Cursor::for_display(&display, CursorType::BlankCursor);
+        let backend = match get_session_type() {
+            SessionType::X11 => {
+                let cursor = match constraint {
+                    PointerConstraint::Lock => Some(&blank_cursor),
+                    PointerConstraint::Confine => None,
+                };
+                let status = seat.grab(
+                    window,
+                    SeatCapabilities::ALL_POINTING,
+                    true,
+                    cursor,
+                    None,
+                    None,
+                );
+                if status != GrabStatus::Success {
+                    return Err(PlatformError::OtherError {
+                        error: format!("Failed to grab pointer: {:?}", status),
+                    });
+                }
+                Backend::X11 { seat }
+            }
+            SessionType::Wayland => {
+                Self::create_wayland_constraint(&constraint, window, &seat, &region, delegate)?
+            }
+        };
+        let hidden_cursor = match constraint {
+            PointerConstraint::Lock => {
+                let cursor = cursor_window.cursor();
+                cursor_window.set_cursor(Some(&blank_cursor));
+                Some((cursor_window.clone(), cursor))
+            }
+            PointerConstraint::Confine => None,
+        };
+        let res = Self {
+            constraint,
+            window: window.clone(),
+            region,
+            hidden_cursor,
+            backend,
+        };
+        if let (PointerConstraint::Lock, Backend::X11 { .. }) = (&res.constraint, &res.backend) {
+            res.warp_to(res.lock_position());
+        }
+        Ok(res)
+    }
+
+    // Locked pointer on X11 is warped back after every move, so the motion
+    // must not be delivered to flutter.
+    pub fn swallows_motion(&self) -> bool {
+        matches!(
+            (&self.constraint, &self.backend),
+            (PointerConstraint::Lock, Backend::X11 { .. })
+        )
+    }
+
+    fn root_region(&self) -> Rect {
+        let (_, x, y) = self.window.origin();
+        self.region.translated(&Point::xy(x as f64, y as f64))
+    }
+
+    fn lock_position(&self) -> (i32, i32) {
+        let center = self.root_region().center();
+        (center.x.round() as i32, center.y.round() as i32)
+    }
+
+    fn warp_to(&self, (x, y): (i32, i32)) {
+        if let Backend::X11 { seat } = &self.backend {
+            if let Some(pointer) = seat.pointer() {
+                pointer.warp(&self.window.screen(), x, y);
+            }
+        }
+    }
+
+    // Handles pointer motion on X11; Returns relative motion for locked
+    // pointer. Wayland compositor enforces the constraint itself.
+    pub fn handle_motion(&self, root_coords: (f64, f64)) -> Option<Point> {
+        if !matches!(self.backend, Backend::X11 { .. }) {
+            return None;
+        }
+        match self.constraint {
+            PointerConstraint::Lock => {
+                let (x, y) = self.lock_position();
+                let delta = Point::xy(root_coords.0 - x as f64, root_coords.1 - y as f64);
+                if delta.x == 0.0 && delta.y == 0.0 {
+                    // Motion caused by warping
+                    return None;
+                }
+                self.warp_to((x, y));
+                Some(delta)
+            }
+            PointerConstraint::Confine => {
+                let region = self.root_region();
+                let point = Point::xy(root_coords.0, root_coords.1);
+                if !region.is_inside(&point) {
+                    let x = point.x.max(region.x).min(region.x2() - 1.0);
+                    let y = point.y.max(region.y).min(region.y2() - 1.0);
+                    self.warp_to((x as i32, y as i32));
+                }
+                None
+            }
+        }
+    }
+
+    fn create_wayland_constraint(
+        constraint: &PointerConstraint,
+        window: &gdk::Window,
+        seat: &Seat,
+        region: &Rect,
+        delegate: Weak<dyn PlatformWindowDelegate>,
+    ) -> PlatformResult<Backend> {
+        let wayland = Wayland::get().ok_or(PlatformError::NotAvailable)?;
+        let display = window.display();
+        let globals = wayland.globals(&display);
+        let (pointer_constraints, relative_pointer_manager) = (
+            globals.pointer_constraints,
+            globals.relative_pointer_manager,
+        );
+        if pointer_constraints.is_null() {
+            return Err(PlatformError::NotAvailable);
+        }
+        let pointer = seat.pointer().ok_or(PlatformError::NotAvailable)?;
+        unsafe {
+            let surface = (wayland.gdk_wayland_window_get_wl_surface)(window.to_glib_none().0);
+            let pointer = (wayland.gdk_wayland_device_get_wl_pointer)(pointer.to_glib_none().0);
+            let compositor =
+                (wayland.gdk_wayland_display_get_wl_compositor)(display.to_glib_none().0);
+            if surface.is_null() || pointer.is_null() || compositor.is_null() {
+                return Err(PlatformError::NotAvailable);
+            }
+
+            let wl_region = (wayland.wl_proxy_marshal_array_constructor)(
+                compositor,
+                wayland_sys::WL_COMPOSITOR_CREATE_REGION,
+                [wl_argument { n: 0 }].as_mut_ptr(),
+                wayland.wl_region_interface,
+            );
+            (wayland.wl_proxy_marshal_array)(
+                wl_region,
+                wayland_sys::WL_REGION_ADD,
+                [
+                    wl_argument { i: region.x as i32 },
+                    wl_argument { i: region.y as i32 },
+                    wl_argument {
+                        i: region.width as i32,
+                    },
+                    wl_argument {
+                        i: region.height as i32,
+                    },
+                ]
+                .as_mut_ptr(),
+            );
+
+            let (opcode, interface) = match constraint {
+                PointerConstraint::Lock => (
+                    wayland_sys::POINTER_CONSTRAINTS_LOCK_POINTER,
+                    wayland.zwp_locked_pointer_v1_interface,
+                ),
+                PointerConstraint::Confine => (
+                    wayland_sys::POINTER_CONSTRAINTS_CONFINE_POINTER,
+                    wayland.zwp_confined_pointer_v1_interface,
+                ),
+            };
+            // Persistent constraint is reactivated when window regains focus
+            let constraint_proxy = (wayland.wl_proxy_marshal_array_constructor)(
+                pointer_constraints,
+                opcode,
+                [
+                    wl_argument { n: 0 },
+                    wl_argument { o: surface },
+                    wl_argument { o: pointer },
+                    wl_argument { o: wl_region },
+                    wl_argument {
+                        u: wayland_sys::LIFETIME_PERSISTENT,
+                    },
+                ]
+                .as_mut_ptr(),
+                interface,
+            );
+            // Region is copied by compositor
+            wayland.destroy(wl_region);
+
+            let mut relative_pointer = null_mut();
+            let mut listener_data = null_mut();
+            if let (PointerConstraint::Lock, false) =
+                (constraint, relative_pointer_manager.is_null())
+            {
+                relative_pointer = (wayland.wl_proxy_marshal_array_constructor)(
+                    relative_pointer_manager,
+                    wayland_sys::RELATIVE_POINTER_MANAGER_GET_RELATIVE_POINTER,
+                    [wl_argument { n: 0 }, wl_argument { o: pointer }].as_mut_ptr(),
+                    wayland.zwp_relative_pointer_v1_interface,
+                );
+                listener_data = Box::into_raw(Box::new(delegate));
+                (wayland.wl_proxy_add_listener)(
+                    relative_pointer,
+                    &RELATIVE_POINTER_LISTENER as *const _ as *const c_void,
+                    listener_data as *mut c_void,
+                );
+            }
+            Ok(Backend::Wayland {
+                wayland,
+                constraint: constraint_proxy,
+                relative_pointer,
+                listener_data,
+            })
+        }
+    }
+}
+
+impl Drop for PointerLock {
+    fn drop(&mut self) {
+        match &self.backend {
+            Backend::X11 { seat } => seat.ungrab(),
+            Backend::Wayland {
+                wayland,
+                constraint,
+                relative_pointer,
+                listener_data,
+            } => unsafe {
+                wayland.destroy(*relative_pointer);
+                wayland.destroy(*constraint);
+                if !listener_data.is_null() {
+                    drop(Box::from_raw(*listener_data));
+                }
+            },
+        }
+        if let Some((window, cursor)) = self.hidden_cursor.take() {
+            window.set_cursor(cursor.as_ref());
+        }
+    }
+}
+
+static RELATIVE_POINTER_LISTENER: wayland_sys::zwp_relative_pointer_v1_listener =
+    wayland_sys::zwp_relative_pointer_v1_listener {
+        relative_motion: on_relative_motion,
+    };
+
+#[allow(clippy::too_many_arguments)]
+unsafe extern "C" fn on_relative_motion(
+    data: *mut c_void,
+    _relative_pointer: *mut wl_proxy,
+    _utime_hi: u32,
+    _utime_lo: u32,
+    dx: wayland_sys::wl_fixed_t,
+    dy: wayland_sys::wl_fixed_t,
+    _dx_unaccel: wayland_sys::wl_fixed_t,
+    _dy_unaccel: wayland_sys::wl_fixed_t,
+) {
+    let delegate = &*(data as *const Weak<dyn PlatformWindowDelegate>);
+    // Delegate may release the pointer, which frees data
+    if let Some(delegate) = delegate.upgrade() {
+        delegate.relative_pointer_motion(Point::xy(
+            wayland_sys::wl_fixed_to_double(dx),
+            wayland_sys::wl_fixed_to_double(dy),
+        ));
+    }
+}
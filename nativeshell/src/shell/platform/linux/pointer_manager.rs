@@ -0,0 +1,411 @@
+use std::{
+    cell::{Cell, RefCell},
+    ffi::CString,
+    os::raw::c_int,
+    rc::{Rc, Weak},
+};
+
+use gdk::{Display, Keymap, ModifierType};
+use glib::translate::ToGlibPtr;
+
+use crate::shell::{
+    api_model::{
+        GlobalPointerEvent, GlobalPointerEventType, PointerModifiers, PointerState,
+        POINTER_BUTTON_BACK, POINTER_BUTTON_FORWARD, POINTER_BUTTON_MIDDLE, POINTER_BUTTON_PRIMARY,
+        POINTER_BUTTON_SECONDARY,
+    },
+    pointer_manager::PointerManagerDelegate,
+    Point,
+};
+
+use super::{
+    error::{PlatformError, PlatformResult},
+    utils::{get_session_type, SessionType},
+    x11_sys,
+};
+
+pub struct PlatformPointerManager {
+    delegate: Weak<RefCell<dyn PointerManagerDelegate>>,
+    monitor: RefCell<Option<RawEventMonitor>>,
+}
+
+impl PlatformPointerManager {
+    pub fn new(delegate: Weak<RefCell<dyn PointerManagerDelegate>>) -> Self {
+        Self {
+            delegate,
+            monitor: RefCell::new(None),
+        }
+    }
+
+    // Wayland doesn't expose pointer position outside of surfaces owned by
+    // the client.
+    pub fn get_pointer_state(&self) -> PlatformResult<PointerState> {
+        match get_session_type() {
+            SessionType::X11 => query_pointer(),
+            SessionType::Wayland => Err(PlatformError::NotAvailable),
+        }
+    }
+
+    pub fn start_monitoring(&self) -> PlatformResult<()> {
+        if get_session_type() == SessionType::Wayland {
+            return Err(PlatformError::NotAvailable);
+        }
+        if self.monitor.borrow().is_none() {
+            let monitor = RawEventMonitor::new(self.delegate.clone())?;
+            self.monitor.replace(Some(monitor));
+        }
+        Ok(())
+    }
+
+    pub fn stop_monitoring(&self) {
+        self.monitor.replace(None);
+    }
+}
+
+fn query_pointer() -> PlatformResult<PointerState> {
+    let display = Display::default().ok_or(PlatformError::NotAvailable)?;
+    let device = display
+        .default_seat()
+        .and_then(|s| s.pointer())
+        .ok_or(PlatformError::NotAvailable)?;
+    let root = display
+        .default_screen()
+        .root_window()
+        .ok_or(PlatformError::NotAvailable)?;
+    let (_, x, y, mask) = root.device_position_double(&device);
+    Ok(PointerState {
+        position: Point::xy(x, y),
+        screen_id: None,
+        buttons: buttons_from_mask(mask),
+        modifiers: modifiers_from_mask(mask),
+    })
+}
+
+fn buttons_from_mask(mask: ModifierType) -> i64 {
+    let mut buttons = 0;
+    if mask.contains(ModifierType::BUTTON1_MASK) {
+        buttons |= POINTER_BUTTON_PRIMARY;
+    }
+    if mask.contains(ModifierType::BUTTON2_MASK) {
+        buttons |= POINTER_BUTTON_MIDDLE;
+    }
+    if mask.contains(ModifierType::BUTTON3_MASK) {
+        buttons |= POINTER_BUTTON_SECONDARY;
+    }
+    buttons
+}
+
+fn modifiers_from_mask(mask: ModifierType) -> PointerModifiers {
+    PointerModifiers {
+        shift: mask.contains(ModifierType::SHIFT_MASK),
+        control: mask.contains(ModifierType::CONTROL_MASK),
+        alt: mask.contains(ModifierType::MOD1_MASK),
+        // Root window query reports raw X modifiers, where super is Mod4
+        meta: mask.intersects(
+            ModifierType::MOD4_MASK | ModifierType::SUPER_MASK | ModifierType::META_MASK,
+        ),
+    }
+}
+
+// X button numbers; 4-7 are scroll wheel
+fn button_for_detail(detail: c_int) -> Option<i64> {
+    match detail {
+        1 => Some(POINTER_BUTTON_PRIMARY),
+        2 => Some(POINTER_BUTTON_MIDDLE),
+        3 => Some(POINTER_BUTTON_SECONDARY),
+        8 => Some(POINTER_BUTTON_BACK),
+        9 => Some(POINTER_BUTTON_FORWARD),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ModifierKey {
+    Shift,
+    Control,
+    Alt,
+    Meta,
+}
+
+impl ModifierKey {
+    fn for_keycode(keymap: &Keymap, keycode: c_int) -> Option<Self> {
+        keymap
+            .entries_for_keycode(keycode as u32)
+            .iter()
+            .find_map(|(_, keyval)| match *keyval as c_int {
+                gdk_sys::GDK_KEY_Shift_L | gdk_sys::GDK_KEY_Shift_R => Some(Self::Shift),
+                gdk_sys::GDK_KEY_Control_L | gdk_sys::GDK_KEY_Control_R => Some(Self::Control),
+                gdk_sys::GDK_KEY_Alt_L | gdk_sys::GDK_KEY_Alt_R => Some(Self::Alt),
+                gdk_sys::GDK_KEY_Super_L
+                | gdk_sys::GDK_KEY_Super_R
+                | gdk_sys::GDK_KEY_Meta_L
+                | gdk_sys::GDK_KEY_Meta_R => Some(Self::Meta),
+                _ => None,
+            })
+    }
+
+    fn set(&self, modifiers: &mut PointerModifiers, value: bool) {
+        match self {
+            Self::Shift => modifiers.shift = value,
+            Self::Control => modifiers.control = value,
+            Self::Alt => modifiers.alt = value,
+            Self::Meta => modifiers.meta = value,
+        }
+    }
+}
+
+struct FilterData {
+    opcode: c_int,
+    delegate: Weak<RefCell<dyn PointerManagerDelegate>>,
+    weak_self: Weak<FilterData>,
+    keymap: Keymap,
+    // Buttons and modifiers are updated from raw events; Position is only
+    // queried once per main loop iteration while pointer is moving
+    state: RefCell<PointerState>,
+    motion_pending: Cell<bool>,
+    // Modifier keys currently held down (keycode, modifier)
+    pressed_keys: RefCell<Vec<(c_int, ModifierKey)>>,
+}
+
+impl FilterData {
+    fn emit(&self, event_type: GlobalPointerEventType, button: i64) {
+        let state = self.state.borrow().clone();
+        if let Some(delegate) = self.delegate.upgrade() {
+            delegate.borrow().on_pointer_event(GlobalPointerEvent {
+                event_type,
+                position: state.position,
+                screen_id: None,
+                button,
+                buttons: state.buttons,
+                modifiers: state.modifiers,
+            });
+        }
+    }
+
+    fn on_raw_motion(&self) {
+        if self.motion_pending.replace(true) {
+            return;
+        }
+        let weak = self.weak_self.clone();
+        glib::idle_add_local(move || {
+            if let Some(data) = weak.upgrade() {
+                data.flush_motion();
+            }
+            glib::Continue(false)
+        });
+    }
+
+    fn flush_motion(&self) {
+        if !self.motion_pending.replace(false) {
+            return;
+        }
+        let state = match query_pointer() {
+            Ok(state) => state,
+            Err(_) => return,
+        };
+        let previous = self.state.replace(state.clone());
+        // Raw motion is also reported when pointer is stuck at screen edge
+        if previous.position != state.position {
+            self.emit(GlobalPointerEventType::Move, 0);
+        } else if previous.modifiers != state.modifiers {
+            self.emit(GlobalPointerEventType::ModifiersChanged, 0);
+        }
+    }
+
+    fn on_raw_button(&self, pressed: bool, button: i64) {
+        // Report pending motion first so that button event has current position
+        self.flush_motion();
+        let event_type = {
+            let mut state = self.state.borrow_mut();
+            if pressed {
+                state.buttons |= button;
+                GlobalPointerEventType::ButtonDown
+            } else {
+                state.buttons &= !button;
+                GlobalPointerEventType::ButtonUp
+            }
+        };
+        self.emit(event_type, button);
+    }
+
+    fn on_raw_key(&self, pressed: bool, keycode: c_int) {
+        let modifier = match ModifierKey::for_keycode(&self.keymap, keycode) {
+            Some(modifier) => modifier,
+            None => return,
+        };
+        let value = {
+            let mut pressed_keys = self.pressed_keys.borrow_mut();
+            pressed_keys.retain(|(k, _)| *k != keycode);
+            if pressed {
+                pressed_keys.push((keycode, modifier));
+            }
+            pressed_keys.iter().any(|(_, m)| *m == modifier)
+        };
+        let changed = {
+            let mut state = self.state.borrow_mut();
+            let previous = state.modifiers.clone();
+            modifier.set(&mut state.modifiers, value);
+            previous != state.modifiers
+        };
+        if changed {
+            self.emit(GlobalPointerEventType::ModifiersChanged, 0);
+        }
+    }
+}
+
+// XInput2 raw events selected on root window. Raw events are delivered
+// regardless of which window is under pointer or has a grab, but they don't
+// carry pointer position, so the pointer is queried when motion is reported.
+struct RawEventMonitor {
+    display: Display,
+    select_events: x11_sys::XISelectEvents,
+    filter_data: Rc<FilterData>,
+}
+
+impl RawEventMonitor {
+    fn new(delegate: Weak<RefCell<dyn PointerManagerDelegate>>) -> PlatformResult<Self> {
+        let display = Display::default().ok_or(PlatformError::NotAvailable)?;
+        let keymap = Keymap::for_display(&display).ok_or(PlatformError::NotAvailable)?;
+        let select_events = Self::load_select_events().ok_or(PlatformError::NotAvailable)?;
+        let mut opcode = 0;
+        let mut first_event = 0;
+        let mut first_error = 0;
+        let name = CString::new("XInputExtension").unwrap();
+        let has_extension = unsafe {
+            let xdisplay = x11_sys::gdk_x11_display_get_xdisplay(display.to_glib_none().0);
+            x11_sys::XQueryExtension(
+                xdisplay,
+                name.as_ptr(),
+                &mut opcode as *mut _,
+                &mut first_event as *mut _,
+                &mut first_error as *mut _,
+            )
+        };
+        if has_extension == 0 {
+            return Err(PlatformError::NotAvailable);
+        }
+        let state = query_pointer()?;
+        let filter_data = Rc::new_cyclic(|weak_self| FilterData {
+            opcode,
+            delegate,
+            weak_self: weak_self.clone(),
+            keymap,
+            state: RefCell::new(state),
+            motion_pending: Cell::new(false),
+            pressed_keys: RefCell::new(Vec::new()),
+        });
+        unsafe {
+            // GDK retrieves generic event data before calling filters
+            gdk_sys::gdk_window_add_filter(
+                std::ptr::null_mut(),
+                Some(Self::filter),
+                Rc::as_ptr(&filter_data) as *mut _,
+            );
+        }
+        let res = Self {
+            display,
+            select_events,
+            filter_data,
+        };
+        res.select(&[
+            x11_sys::XI_RawKeyPress,
+            x11_sys::XI_RawKeyRelease,
+            x11_sys::XI_RawMotion,
+            x11_sys::XI_RawButtonPress,
+            x11_sys::XI_RawButtonRelease,
+        ])?;
+        Ok(res)
+    }
+
+    // libXi is already loaded by GDK X11 backend
+    fn load_select_events() -> Option<x11_sys::XISelectEvents> {
+        unsafe {
+            let name = CString::new("libXi.so.6").unwrap();
+            let handle = libc::dlopen(name.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL);
+            if handle.is_null() {
+                return None;
+            }
+            let name = CString::new("XISelectEvents").unwrap();
+            let symbol = libc::dlsym(handle, name.as_ptr());
+            if symbol.is_null() {
+                None
+            } else {
+                Some(std::mem::transmute::<
+                    *mut libc::c_void,
+                    x11_sys::XISelectEvents,
+                >(symbol))
+            }
+        }
+    }
+
+    fn select(&self, events: &[c_int]) -> PlatformResult<()> {
+        // XIMaskLen(XI_LASTEVENT)
+        let mut mask = [0u8; 4];
+        for event in events {
+            mask[(event >> 3) as usize] |= 1 << (event & 7);
+        }
+        let mut event_mask = x11_sys::XIEventMask {
+            deviceid: x11_sys::XIAllMasterDevices,
+            mask_len: mask.len() as c_int,
+            mask: mask.as_mut_ptr(),
+        };
+        let display: *mut gdk_sys::GdkDisplay = self.display.to_glib_none().0;
+        let error = unsafe {
+            let xdisplay = x11_sys::gdk_x11_display_get_xdisplay(display);
+            let root = x11_sys::XDefaultRootWindow(xdisplay);
+            x11_sys::gdk_x11_display_error_trap_push(display);
+            (self.select_events)(xdisplay, root, &mut event_mask as *mut _, 1);
+            x11_sys::gdk_x11_display_error_trap_pop(display)
+        };
+        if error != 0 {
+            Err(PlatformError::NotAvailable)
+        } else {
+            Ok(())
+        }
+    }
+
+    unsafe extern "C" fn filter(
+        xevent: *mut gdk_sys::GdkXEvent,
+        _event: *mut gdk_sys::GdkEvent,
+        data: glib_sys::gpointer,
+    ) -> gdk_sys::GdkFilterReturn {
+        let cookie = &*(xevent as *const x11_sys::XGenericEventCookie);
+        let data = &*(data as *const FilterData);
+        if cookie.type_ != x11_sys::GenericEvent
+            || cookie.extension != data.opcode
+            || cookie.data.is_null()
+        {
+            return gdk_sys::GDK_FILTER_CONTINUE;
+        }
+        let raw_event = &*(cookie.data as *const x11_sys::XIRawEvent);
+        match raw_event.evtype {
+            x11_sys::XI_RawMotion => data.on_raw_motion(),
+            x11_sys::XI_RawButtonPress | x11_sys::XI_RawButtonRelease => {
+                if let Some(button) = button_for_detail(raw_event.detail) {
+                    data.on_raw_button(raw_event.evtype == x11_sys::XI_RawButtonPress, button);
+                }
+            }
+            x11_sys::XI_RawKeyPress | x11_sys::XI_RawKeyRelease => {
+                data.on_raw_key(
+                    raw_event.evtype == x11_sys::XI_RawKeyPress,
+                    raw_event.detail,
+                );
+            }
+            _ => {}
+        }
+        gdk_sys::GDK_FILTER_CONTINUE
+    }
+}
+
+impl Drop for RawEventMonitor {
+    fn drop(&mut self) {
+        self.select(&[]).ok();
+        unsafe {
+            gdk_sys::gdk_window_remove_filter(
+                std::ptr::null_mut(),
+                Some(Self::filter),
+                Rc::as_ptr(&self.filter_data) as *mut _,
+            );
+        }
+    }
+}
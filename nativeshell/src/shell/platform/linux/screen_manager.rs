@@ -4,7 +4,7 @@ use gdk::{Display, Monitor, Rectangle};
 use gdk_sys::GdkMonitor;
 use glib::translate::ToGlibPtr;
 
-use crate::shell::{api_model::Screen, screen_manager::ScreenManagerDelegate, Point, Rect};
+use crate::shell::{api_model::Screen, screen_manager::ScreenManagerDelegate, Point, Rect, Size};
 
 use super::error::PlatformResult;
 
@@ -55,11 +55,33 @@ impl PlatformScreenManager {
     }
 
     fn screen_for_monitor(monitor: &Monitor) -> Screen {
+        let manufacturer = monitor.manufacturer().map(|m| m.to_string());
+        let model = monitor.model().map(|m| m.to_string());
+        let name = match (&manufacturer, &model) {
+            (Some(manufacturer), Some(model)) => Some(format!("{manufacturer} {model}")),
+            _ => model.clone().or(manufacturer),
+        };
+        let (width_mm, height_mm) = (monitor.width_mm(), monitor.height_mm());
         Screen {
             id: Self::get_monitor_id(monitor),
             frame: Self::convert_rect(monitor.geometry()),
             work_area: Self::convert_rect(monitor.workarea()),
             scaling_factor: monitor.scale_factor() as f64,
+            name,
+            model,
+            physical_size: if width_mm > 0 && height_mm > 0 {
+                Some(Size::wh(width_mm as f64, height_mm as f64))
+            } else {
+                None
+            },
+            // GDK reports refresh rate in millihertz
+            refresh_rate: match monitor.refresh_rate() {
+                0 => None,
+                rate => Some(rate as f64 / 1000.0),
+            },
+            // Not exposed by GDK; Monitor geometry is already rotated
+            rotation: 0,
+            is_primary: monitor.is_primary(),
         }
     }
 
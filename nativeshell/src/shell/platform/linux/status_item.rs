@@ -3,71 +3,398 @@ use std::{
     rc::{Rc, Weak},
 };
 
+use gdk::{Display, EventType, Gravity, Rectangle};
+use glib::{
+    translate::{FromGlibPtrFull, ToGlibPtr},
+    ObjectExt, ToValue,
+};
+use gtk::prelude::{GtkMenuExt, GtkWindowExt, WidgetExt};
+
 use crate::{
     shell::{
-        api_model::ImageData,
+        api_model::{ImageData, StatusItemActionType},
         status_item_manager::{StatusItemDelegate, StatusItemHandle},
         EngineHandle, Point, Rect,
     },
+    util::{LateRefCell, OkLog},
     Context,
 };
 
 use super::{
     error::{PlatformError, PlatformResult},
     menu::PlatformMenu,
+    screen_manager::PlatformScreenManager,
+    status_notifier_item::{StatusNotifierHost, StatusNotifierItem, StatusNotifierItemEvent},
+    utils::{get_session_type, pixbuf_from_image_data, SessionType},
 };
 
+enum StatusItemBackend {
+    // Preferred; Works on KDE, GNOME (with AppIndicator extension) and most
+    // panels that support Wayland
+    StatusNotifier(Rc<StatusNotifierItem>),
+    // Legacy XEmbed tray icon, only available on X11
+    XEmbed(StatusIcon),
+}
+
 pub struct PlatformStatusItem {
+    handle: StatusItemHandle,
+    delegate: Weak<RefCell<dyn StatusItemDelegate>>,
     pub(crate) engine: EngineHandle,
+    backend: LateRefCell<StatusItemBackend>,
+    // Position of last activation in logical screen coordinates; StatusNotifier
+    // protocol doesn't provide item geometry so this is the best we have
+    activation_point: RefCell<Option<Point>>,
+    // Only used for StatusNotifier items to position the menu on X11
+    menu_anchor: RefCell<Option<gtk::Window>>,
+    // Completion of menu exported to StatusNotifier host
+    menu_done: RefCell<Option<Box<dyn FnOnce(PlatformResult<()>)>>>,
 }
 
 impl PlatformStatusItem {
+    fn new(
+        handle: StatusItemHandle,
+        delegate: Weak<RefCell<dyn StatusItemDelegate>>,
+        engine: EngineHandle,
+    ) -> Self {
+        Self {
+            handle,
+            delegate,
+            engine,
+            backend: LateRefCell::new(),
+            activation_point: RefCell::new(None),
+            menu_anchor: RefCell::new(None),
+            menu_done: RefCell::new(None),
+        }
+    }
+
     pub fn assign_weak_self(&self, _weak: Weak<PlatformStatusItem>) {}
 
-    pub fn set_image(&self, _image: Vec<ImageData>) -> PlatformResult<()> {
-        Err(PlatformError::NotAvailable)
+    fn fire_action(&self, action: StatusItemActionType, position: Point) {
+        if let Some(delegate) = self.delegate.upgrade() {
+            delegate.borrow().on_action(self.handle, action, position);
+        }
+    }
+
+    fn on_status_notifier_event(&self, event: StatusNotifierItemEvent) {
+        let (x, y, down, up) = match event {
+            StatusNotifierItemEvent::Activate { x, y } => (
+                x,
+                y,
+                StatusItemActionType::LeftMouseDown,
+                StatusItemActionType::LeftMouseUp,
+            ),
+            StatusNotifierItemEvent::ContextMenu { x, y } => (
+                x,
+                y,
+                StatusItemActionType::RightMouseDown,
+                StatusItemActionType::RightMouseUp,
+            ),
+            // Host doesn't provide position when showing exported menu
+            StatusNotifierItemEvent::MenuAboutToShow => {
+                self.fire_action(StatusItemActionType::RightMouseDown, Point::xy(0.0, 0.0));
+                self.fire_action(StatusItemActionType::RightMouseUp, Point::xy(0.0, 0.0));
+                return;
+            }
+            StatusNotifierItemEvent::MenuClosed => {
+                if let StatusItemBackend::StatusNotifier(item) = &*self.backend.borrow() {
+                    item.menu_closed();
+                }
+                if let Some(on_done) = self.menu_done.take() {
+                    on_done(Ok(()));
+                }
+                return;
+            }
+        };
+        // Host only tells us about the click, not individual mouse events
+        let scale = primary_scale_factor();
+        self.activation_point
+            .replace(Some(Point::xy(x as f64 / scale, y as f64 / scale)));
+        self.fire_action(down, Point::xy(0.0, 0.0));
+        self.fire_action(up, Point::xy(0.0, 0.0));
     }
 
-    pub fn set_hint(&self, _hint: String) -> PlatformResult<()> {
-        Err(PlatformError::NotAvailable)
+    fn on_status_icon_event(&self, event: &gdk::Event) {
+        let action = match (event.event_type(), event.button()) {
+            (EventType::ButtonPress, Some(1)) => StatusItemActionType::LeftMouseDown,
+            (EventType::ButtonRelease, Some(1)) => StatusItemActionType::LeftMouseUp,
+            (EventType::ButtonPress, Some(3)) => StatusItemActionType::RightMouseDown,
+            (EventType::ButtonRelease, Some(3)) => StatusItemActionType::RightMouseUp,
+            _ => return,
+        };
+        if let Some((x, y)) = event.root_coords() {
+            self.activation_point.replace(Some(Point::xy(x, y)));
+        }
+        let (x, y) = event.coords().unwrap_or((0.0, 0.0));
+        self.fire_action(action, Point::xy(x, y));
     }
 
-    pub fn show_menu<F>(&self, _menu: Rc<PlatformMenu>, _offset: Point, on_done: F)
+    pub fn set_image(&self, image: Vec<ImageData>) -> PlatformResult<()> {
+        match &*self.backend.borrow() {
+            StatusItemBackend::StatusNotifier(item) => item.set_image(image),
+            StatusItemBackend::XEmbed(icon) => icon.set_image(image),
+        }
+    }
+
+    pub fn set_hint(&self, hint: String) -> PlatformResult<()> {
+        match &*self.backend.borrow() {
+            StatusItemBackend::StatusNotifier(item) => item.set_hint(hint),
+            StatusItemBackend::XEmbed(icon) => {
+                icon.set_hint(&hint);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn show_menu<F>(&self, menu: Rc<PlatformMenu>, offset: Point, on_done: F)
     where
         F: FnOnce(PlatformResult<()>) + 'static,
     {
-        on_done(Err(PlatformError::NotAvailable))
+        match &*self.backend.borrow() {
+            StatusItemBackend::StatusNotifier(item) => {
+                // Preferably the menu is shown by host through dbusmenu, which
+                // is only possible while host is waiting for it
+                if item.show_menu(&menu.model) {
+                    if let Some(previous) = self.menu_done.replace(Some(Box::new(on_done))) {
+                        previous(Ok(()));
+                    }
+                    return;
+                }
+                // Popup window can't be positioned on Wayland
+                if get_session_type() != SessionType::X11 {
+                    on_done(Err(PlatformError::NotAvailable));
+                    return;
+                }
+                // Menu needs to be anchored to a window of this process; Create an
+                // invisible one at the position where item was activated.
+                let position = self.activation_point.borrow().clone().unwrap_or_default();
+                let anchor = self
+                    .menu_anchor
+                    .borrow_mut()
+                    .get_or_insert_with(|| {
+                        let window = gtk::Window::new(gtk::WindowType::Popup);
+                        window.set_default_size(1, 1);
+                        window.set_opacity(0.0);
+                        window
+                    })
+                    .clone();
+                anchor.move_(
+                    (position.x + offset.x) as i32,
+                    (position.y + offset.y) as i32,
+                );
+                anchor.show();
+                menu.menu
+                    .popup_at_widget(&anchor, Gravity::NorthWest, Gravity::NorthWest, None);
+                menu.on_selection_done(move |_| {
+                    anchor.hide();
+                    on_done(Ok(()));
+                });
+            }
+            StatusItemBackend::XEmbed(icon) => {
+                let event = icon.last_button_press.borrow().clone();
+                match event.as_ref().and_then(|e| e.window().map(|w| (e, w))) {
+                    Some((event, window)) => {
+                        menu.menu.popup_at_rect(
+                            &window,
+                            &Rectangle {
+                                x: offset.x as i32,
+                                y: offset.y as i32,
+                                width: 0,
+                                height: 0,
+                            },
+                            Gravity::NorthWest,
+                            Gravity::NorthWest,
+                            Some(event),
+                        );
+                    }
+                    None => menu.menu.popup_at_pointer(None),
+                }
+                menu.on_selection_done(move |_| {
+                    on_done(Ok(()));
+                });
+            }
+        }
     }
 
     pub fn set_highlighted(&self, _highlighted: bool) -> PlatformResult<()> {
-        Err(PlatformError::NotAvailable)
+        // Neither tray implementation lets the application draw highlight
+        Ok(())
     }
 
     pub fn get_geometry(&self) -> PlatformResult<Rect> {
-        Err(PlatformError::NotAvailable)
+        if let StatusItemBackend::XEmbed(icon) = &*self.backend.borrow() {
+            if let Some(geometry) = icon.geometry() {
+                return Ok(geometry);
+            }
+        }
+        self.activation_point
+            .borrow()
+            .clone()
+            .map(|p| Rect::xywh(p.x, p.y, 1.0, 1.0))
+            .ok_or(PlatformError::NotAvailable)
     }
 
     pub fn get_screen_id(&self) -> PlatformResult<i64> {
-        Err(PlatformError::NotAvailable)
+        let geometry = self.get_geometry()?;
+        Display::default()
+            .and_then(|d| d.monitor_at_point(geometry.x as i32, geometry.y as i32))
+            .map(|m| PlatformScreenManager::get_monitor_id(&m))
+            .ok_or(PlatformError::NotAvailable)
     }
 }
 
-pub struct PlatformStatusItemManager {}
+fn primary_scale_factor() -> f64 {
+    Display::default()
+        .and_then(|d| d.primary_monitor().or_else(|| d.monitor(0)))
+        .map(|m| m.scale_factor() as f64)
+        .unwrap_or(1.0)
+}
+
+// Minimal wrapper for GtkStatusIcon, which is not exposed by gtk-rs
+struct StatusIcon {
+    icon: glib::Object,
+    last_button_press: Rc<RefCell<Option<gdk::Event>>>,
+}
+
+impl StatusIcon {
+    fn new(item: Weak<PlatformStatusItem>) -> Self {
+        let icon: glib::Object = unsafe {
+            glib::Object::from_glib_full(gtk_sys::gtk_status_icon_new() as *mut gobject_sys::GObject)
+        };
+        let last_button_press = Rc::new(RefCell::new(None));
+        for signal in ["button-press-event", "button-release-event"] {
+            let item = item.clone();
+            let last_button_press = last_button_press.clone();
+            icon.connect_local(signal, false, move |values| {
+                let event = values.get(1).and_then(|v| v.get::<gdk::Event>().ok());
+                if let (Some(event), Some(item)) = (event, item.upgrade()) {
+                    if event.event_type() == EventType::ButtonPress {
+                        last_button_press.replace(Some(event.clone()));
+                    }
+                    item.on_status_icon_event(&event);
+                }
+                Some(true.to_value())
+            })
+            .ok_log();
+        }
+        Self {
+            icon,
+            last_button_press,
+        }
+    }
+
+    fn raw(&self) -> *mut gtk_sys::GtkStatusIcon {
+        let icon: *mut gobject_sys::GObject = self.icon.to_glib_none().0;
+        icon as *mut _
+    }
+
+    fn set_image(&self, image: Vec<ImageData>) -> PlatformResult<()> {
+        // Tray icons are small, but give the tray a chance to scale down
+        // from the best resolution available
+        let image = image.into_iter().max_by_key(|i| i.width);
+        if let Some(image) = image {
+            let pixbuf = pixbuf_from_image_data(image)?;
+            unsafe {
+                gtk_sys::gtk_status_icon_set_from_pixbuf(self.raw(), pixbuf.to_glib_none().0);
+            }
+        }
+        Ok(())
+    }
+
+    fn set_hint(&self, hint: &str) {
+        unsafe {
+            gtk_sys::gtk_status_icon_set_tooltip_text(self.raw(), hint.to_glib_none().0);
+        }
+    }
+
+    fn geometry(&self) -> Option<Rect> {
+        let mut area = gdk_sys::GdkRectangle {
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+        };
+        let res = unsafe {
+            gtk_sys::gtk_status_icon_get_geometry(
+                self.raw(),
+                std::ptr::null_mut(),
+                &mut area,
+                std::ptr::null_mut(),
+            )
+        };
+        if res != 0 {
+            Some(Rect::xywh(
+                area.x as f64,
+                area.y as f64,
+                area.width as f64,
+                area.height as f64,
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+impl Drop for StatusIcon {
+    fn drop(&mut self) {
+        unsafe {
+            gtk_sys::gtk_status_icon_set_visible(self.raw(), 0);
+        }
+    }
+}
+
+pub struct PlatformStatusItemManager {
+    context: Context,
+    status_notifier_host: Option<StatusNotifierHost>,
+}
 
 impl PlatformStatusItemManager {
-    pub fn new(_context: Context) -> Self {
-        Self {}
+    pub fn new(context: Context) -> Self {
+        Self {
+            context,
+            status_notifier_host: StatusNotifierHost::new().ok(),
+        }
     }
 
     pub fn assign_weak_self(&self, _weak: Weak<PlatformStatusItemManager>) {}
 
     pub fn create_status_item(
         &self,
-        _handle: StatusItemHandle,
-        _delegate: Weak<RefCell<dyn StatusItemDelegate>>,
-        _engine: EngineHandle,
+        handle: StatusItemHandle,
+        delegate: Weak<RefCell<dyn StatusItemDelegate>>,
+        engine: EngineHandle,
     ) -> PlatformResult<Rc<PlatformStatusItem>> {
-        Err(PlatformError::NotAvailable)
+        let item = Rc::new(PlatformStatusItem::new(handle, delegate, engine));
+        let weak = Rc::downgrade(&item);
+        let backend = match &self.status_notifier_host {
+            Some(host) => StatusItemBackend::StatusNotifier(StatusNotifierItem::new(
+                host,
+                handle.0,
+                &self.menu_actions()?,
+                move |event| {
+                    if let Some(item) = weak.upgrade() {
+                        item.on_status_notifier_event(event);
+                    }
+                },
+            )?),
+            None if get_session_type() == SessionType::X11 => {
+                StatusItemBackend::XEmbed(StatusIcon::new(weak))
+            }
+            None => return Err(PlatformError::NotAvailable),
+        };
+        item.backend.set(backend);
+        Ok(item)
+    }
+
+    // Exported status item menus use actions of platform menus
+    fn menu_actions(&self) -> PlatformResult<gio::SimpleActionGroup> {
+        let context = self.context.get().ok_or(PlatformError::UnknownError)?;
+        let menu_manager = context.menu_manager.borrow();
+        let actions = menu_manager
+            .borrow()
+            .get_platform_menu_manager()
+            .actions
+            .clone();
+        Ok(actions)
     }
 
     pub fn unregister_status_item(&self, _item: &Rc<PlatformStatusItem>) {}
@@ -0,0 +1,362 @@
+// StatusNotifierItem (a.k.a. AppIndicator) implementation
+// https://www.freedesktop.org/wiki/Specifications/StatusNotifierItem/
+//
+// Host calls Activate / ContextMenu, which are turned into status item
+// actions. Items also export a com.canonical.dbusmenu object (Menu property),
+// which most hosts show on right click instead of calling ContextMenu. The
+// menu is empty until Dart code decides which menu to show in response to
+// AboutToShow (see dbus_menu.rs).
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+use gio::{
+    BusType, DBusCallFlags, DBusConnection, DBusInterfaceInfo, DBusMethodInvocation, DBusNodeInfo,
+    DBusSignalFlags, RegistrationId, SignalSubscriptionId, SimpleActionGroup,
+};
+use glib::{Cast, ToVariant, Variant};
+use log::warn;
+
+use crate::{shell::api_model::ImageData, util::OkLog};
+
+use super::{
+    dbus_menu::{DBusMenu, DBusMenuEvent},
+    error::{PlatformError, PlatformResult},
+    utils::{object_path_variant, validate_image_data},
+};
+
+const WATCHER_BUS_NAME: &str = "org.kde.StatusNotifierWatcher";
+const WATCHER_OBJECT_PATH: &str = "/StatusNotifierWatcher";
+const ITEM_INTERFACE: &str = "org.kde.StatusNotifierItem";
+
+const INTERFACE_XML: &str = r#"
+<node>
+  <interface name="org.kde.StatusNotifierItem">
+    <property name="Category" type="s" access="read"/>
+    <property name="Id" type="s" access="read"/>
+    <property name="Title" type="s" access="read"/>
+    <property name="Status" type="s" access="read"/>
+    <property name="IconName" type="s" access="read"/>
+    <property name="IconPixmap" type="a(iiay)" access="read"/>
+    <property name="ToolTip" type="(sa(iiay)ss)" access="read"/>
+    <property name="ItemIsMenu" type="b" access="read"/>
+    <property name="Menu" type="o" access="read"/>
+    <method name="ContextMenu">
+      <arg name="x" type="i" direction="in"/>
+      <arg name="y" type="i" direction="in"/>
+    </method>
+    <method name="Activate">
+      <arg name="x" type="i" direction="in"/>
+      <arg name="y" type="i" direction="in"/>
+    </method>
+    <method name="SecondaryActivate">
+      <arg name="x" type="i" direction="in"/>
+      <arg name="y" type="i" direction="in"/>
+    </method>
+    <method name="Scroll">
+      <arg name="delta" type="i" direction="in"/>
+      <arg name="orientation" type="s" direction="in"/>
+    </method>
+    <signal name="NewTitle"/>
+    <signal name="NewIcon"/>
+    <signal name="NewToolTip"/>
+    <signal name="NewStatus">
+      <arg name="status" type="s"/>
+    </signal>
+  </interface>
+</node>
+"#;
+
+#[derive(Debug, Clone, Copy)]
+pub(super) enum StatusNotifierItemEvent {
+    // Primary activation (usually left click); Coordinates are in screen pixels
+    Activate { x: i32, y: i32 },
+    // Request to show context menu (usually right click)
+    ContextMenu { x: i32, y: i32 },
+    // Host is about to show exported menu; It should be populated through
+    // show_menu
+    MenuAboutToShow,
+    MenuClosed,
+}
+
+type Pixmap = (i32, i32, Vec<u8>);
+
+pub(super) struct StatusNotifierItem {
+    id: i64,
+    connection: DBusConnection,
+    registration_id: Cell<Option<RegistrationId>>,
+    icon: RefCell<Vec<Pixmap>>,
+    hint: RefCell<String>,
+    menu: Rc<DBusMenu>,
+    on_event: Box<dyn Fn(StatusNotifierItemEvent)>,
+}
+
+// D-Bus callbacks need to be Send + Sync, so instead of capturing the item
+// they capture its id and look it up here. Callbacks are dispatched on main
+// thread.
+thread_local! {
+    static ITEMS: RefCell<HashMap<i64, Weak<StatusNotifierItem>>> = RefCell::new(HashMap::new());
+}
+
+fn item_for_id(id: i64) -> Option<Rc<StatusNotifierItem>> {
+    ITEMS.with(|items| items.borrow().get(&id).and_then(|i| i.upgrade()))
+}
+
+impl StatusNotifierItem {
+    // Menu items refer to actions in given group
+    pub fn new<F>(
+        host: &StatusNotifierHost,
+        id: i64,
+        actions: &SimpleActionGroup,
+        on_event: F,
+    ) -> PlatformResult<Rc<Self>>
+    where
+        F: Fn(StatusNotifierItemEvent) + 'static,
+    {
+        let menu = DBusMenu::new(&host.connection, &Self::menu_object_path(id), actions)?;
+        menu.set_on_event(move |event| {
+            let event = match event {
+                DBusMenuEvent::AboutToShow => StatusNotifierItemEvent::MenuAboutToShow,
+                DBusMenuEvent::Closed => StatusNotifierItemEvent::MenuClosed,
+            };
+            if let Some(item) = item_for_id(id) {
+                (item.on_event)(event);
+            }
+        });
+        let res = Rc::new(Self {
+            id,
+            connection: host.connection.clone(),
+            registration_id: Cell::new(None),
+            icon: RefCell::new(Vec::new()),
+            hint: RefCell::new(String::new()),
+            menu,
+            on_event: Box::new(on_event),
+        });
+        ITEMS.with(|items| items.borrow_mut().insert(id, Rc::downgrade(&res)));
+
+        let registration_id = res.connection.register_object(
+            &res.object_path(),
+            &host.interface_info,
+            move |_, _, _, _, method, parameters, invocation| {
+                if let Some(item) = item_for_id(id) {
+                    item.on_method_call(method, parameters, invocation);
+                }
+            },
+            move |_, _, _, _, property| match item_for_id(id) {
+                Some(item) => item.get_property(property),
+                None => "".to_variant(),
+            },
+            |_, _, _, _, _, _| false,
+        )?;
+        res.registration_id.set(Some(registration_id));
+        res.register_with_watcher();
+        Ok(res)
+    }
+
+    fn object_path(&self) -> String {
+        format!("/org/nativeshell/StatusNotifierItem{}", self.id)
+    }
+
+    fn menu_object_path(id: i64) -> String {
+        format!("/org/nativeshell/StatusNotifierItem{}/Menu", id)
+    }
+
+    // Returns false if host is not waiting for the menu, in which case there
+    // is no way to show it
+    pub fn show_menu(&self, model: &gio::Menu) -> bool {
+        if !self.menu.is_about_to_show() {
+            return false;
+        }
+        self.menu.set_model(Some(model.upcast_ref()));
+        self.menu.about_to_show_done();
+        true
+    }
+
+    // Clears the menu so that it is not shown again unless requested
+    pub fn menu_closed(&self) {
+        self.menu.set_model(None);
+    }
+
+    // Notifies the watcher about this item. Must be done again if watcher
+    // gets restarted.
+    pub fn register_with_watcher(&self) {
+        self.connection.call(
+            Some(WATCHER_BUS_NAME),
+            WATCHER_OBJECT_PATH,
+            WATCHER_BUS_NAME,
+            "RegisterStatusNotifierItem",
+            Some(&(self.object_path(),).to_variant()),
+            None,
+            DBusCallFlags::NONE,
+            -1,
+            None::<&gio::Cancellable>,
+            |res| {
+                if let Err(error) = res {
+                    warn!("Failed to register status notifier item: {}", error);
+                }
+            },
+        );
+    }
+
+    pub fn set_image(&self, image: Vec<ImageData>) -> PlatformResult<()> {
+        let icon = image
+            .into_iter()
+            .map(|image| {
+                validate_image_data(&image)?;
+                Ok(Self::pixmap_from_image(image))
+            })
+            .collect::<PlatformResult<_>>()?;
+        self.icon.replace(icon);
+        self.emit_signal("NewIcon")
+    }
+
+    pub fn set_hint(&self, hint: String) -> PlatformResult<()> {
+        self.hint.replace(hint);
+        self.emit_signal("NewToolTip")?;
+        self.emit_signal("NewTitle")
+    }
+
+    fn emit_signal(&self, signal: &str) -> PlatformResult<()> {
+        self.connection
+            .emit_signal(None, &self.object_path(), ITEM_INTERFACE, signal, None)
+            .map_err(PlatformError::from)
+    }
+
+    // Converts RGBA image to ARGB32 in network byte order
+    fn pixmap_from_image(image: ImageData) -> Pixmap {
+        let mut data = Vec::with_capacity((image.width * image.height * 4) as usize);
+        for y in 0..image.height as usize {
+            let row = &image.data[y * image.bytes_per_row as usize..];
+            for pixel in row.chunks_exact(4).take(image.width as usize) {
+                data.extend_from_slice(&[pixel[3], pixel[0], pixel[1], pixel[2]]);
+            }
+        }
+        (image.width, image.height, data)
+    }
+
+    fn get_property(&self, property: &str) -> Variant {
+        match property {
+            "Category" => "ApplicationStatus".to_variant(),
+            "Id" => glib::prgname()
+                .map(|s| s.to_string())
+                .unwrap_or_default()
+                .to_variant(),
+            "Title" => self.hint.borrow().to_variant(),
+            "Status" => "Active".to_variant(),
+            "IconName" => "".to_variant(),
+            "IconPixmap" => self.icon.borrow().to_variant(),
+            "ToolTip" => (
+                String::new(),
+                Vec::<Pixmap>::new(),
+                self.hint.borrow().clone(),
+                String::new(),
+            )
+                .to_variant(),
+            "ItemIsMenu" => false.to_variant(),
+            "Menu" => object_path_variant(&Self::menu_object_path(self.id)),
+            _ => "".to_variant(),
+        }
+    }
+
+    fn on_method_call(&self, method: &str, parameters: Variant, invocation: DBusMethodInvocation) {
+        let position = parameters.get::<(i32, i32)>();
+        match (method, position) {
+            ("Activate", Some((x, y))) => {
+                (self.on_event)(StatusNotifierItemEvent::Activate { x, y });
+            }
+            ("ContextMenu", Some((x, y))) => {
+                (self.on_event)(StatusNotifierItemEvent::ContextMenu { x, y });
+            }
+            _ => {}
+        }
+        invocation.return_value(None);
+    }
+}
+
+impl Drop for StatusNotifierItem {
+    fn drop(&mut self) {
+        ITEMS.with(|items| items.borrow_mut().remove(&self.id));
+        if let Some(registration_id) = self.registration_id.take() {
+            self.connection.unregister_object(registration_id).ok_log();
+        }
+    }
+}
+
+// Session bus connection shared by all status notifier items.
+pub(super) struct StatusNotifierHost {
+    connection: DBusConnection,
+    interface_info: DBusInterfaceInfo,
+    subscription_id: Cell<Option<SignalSubscriptionId>>,
+}
+
+impl StatusNotifierHost {
+    // Returns host if there is a StatusNotifierWatcher running on session bus.
+    pub fn new() -> PlatformResult<Self> {
+        let connection = gio::bus_get_sync(BusType::Session, None::<&gio::Cancellable>)?;
+        let has_watcher = connection
+            .call_sync(
+                Some("org.freedesktop.DBus"),
+                "/org/freedesktop/DBus",
+                "org.freedesktop.DBus",
+                "NameHasOwner",
+                Some(&(WATCHER_BUS_NAME,).to_variant()),
+                None,
+                DBusCallFlags::NONE,
+                -1,
+                None::<&gio::Cancellable>,
+            )?
+            .get::<(bool,)>()
+            .map(|r| r.0)
+            .unwrap_or(false);
+        if !has_watcher {
+            return Err(PlatformError::NotAvailable);
+        }
+
+        let interface_info = DBusNodeInfo::for_xml(INTERFACE_XML)?
+            .lookup_interface(ITEM_INTERFACE)
+            .ok_or(PlatformError::UnknownError)?;
+
+        // re-register items when watcher gets restarted (i.e. panel restart)
+        let subscription_id = connection.signal_subscribe(
+            Some("org.freedesktop.DBus"),
+            Some("org.freedesktop.DBus"),
+            Some("NameOwnerChanged"),
+            Some("/org/freedesktop/DBus"),
+            Some(WATCHER_BUS_NAME),
+            DBusSignalFlags::NONE,
+            |_, _, _, _, _, parameters| {
+                let new_owner = parameters.get::<(String, String, String)>().map(|p| p.2);
+                if new_owner.map(|o| o.is_empty()).unwrap_or(true) {
+                    return;
+                }
+                let items: Vec<_> = ITEMS.with(|items| {
+                    items
+                        .borrow()
+                        .values()
+                        .filter_map(|i| i.upgrade())
+                        .collect()
+                });
+                for item in items {
+                    item.register_with_watcher();
+                }
+            },
+        );
+
+        Ok(Self {
+            connection,
+            interface_info,
+            subscription_id: Cell::new(Some(subscription_id)),
+        })
+    }
+}
+
+impl Drop for StatusNotifierHost {
+    fn drop(&mut self) {
+        if let Some(subscription_id) = self.subscription_id.take() {
+            self.connection.signal_unsubscribe(subscription_id);
+        }
+    }
+}
@@ -1,5 +1,10 @@
-use gdk::{Event, EventType, Window};
-use glib::translate::{FromGlibPtrFull, ToGlibPtr, ToGlibPtrMut};
+use gdk::gdk_pixbuf::{Colorspace, Pixbuf};
+use gdk::{Device, Event, EventType, Window};
+use glib::translate::{FromGlibPtrFull, FromGlibPtrNone, ToGlibPtr, ToGlibPtrMut};
+
+use crate::shell::api_model::ImageData;
+
+use super::error::{PlatformError, PlatformResult};
 
 #[derive(PartialEq, Eq)]
 pub(super) enum SessionType {
@@ -45,6 +50,23 @@ pub(super) fn synthetize_leave_event_from_motion(event: &Event) -> Event {
     res
 }
 
+pub(super) fn synthetize_motion_event(window: &Window, device: &Device, root: (f64, f64)) -> Event {
+    let mut res = Event::new(EventType::MotionNotify);
+    let e: *mut gdk_sys::GdkEvent = res.to_glib_none_mut().0;
+    let e = unsafe { &mut *e };
+    let (_, win_x, win_y) = window.origin();
+    e.motion.window = window.to_glib_full();
+    e.motion.send_event = 1;
+    e.motion.time = gtk::current_event_time();
+    e.motion.x = root.0 - win_x as f64;
+    e.motion.y = root.1 - win_y as f64;
+    e.motion.x_root = root.0;
+    e.motion.y_root = root.1;
+
+    res.set_device(Some(device));
+    res
+}
+
 pub(super) fn translate_event_to_window(event: &Event, win: &Window) -> Event {
     let mut event = event.clone();
     let e: *mut gdk_sys::GdkEvent = event.to_glib_none_mut().0;
@@ -66,3 +88,82 @@ pub(super) fn translate_event_to_window(event: &Event, win: &Window) -> Event {
     }
     event
 }
+
+// ImageData comes from Dart; Pixbuf asserts on invalid dimensions or short
+// buffer, which would abort the process.
+pub(super) fn validate_image_data(image: &ImageData) -> PlatformResult<()> {
+    let (width, height, stride) = (
+        image.width as i64,
+        image.height as i64,
+        image.bytes_per_row as i64,
+    );
+    if width <= 0
+        || height <= 0
+        || stride < width * 4
+        || (image.data.len() as i64) < stride * height
+    {
+        Err(PlatformError::OtherError {
+            error: format!(
+                "Invalid image data ({}x{}, {} bytes per row, {} bytes)",
+                image.width,
+                image.height,
+                image.bytes_per_row,
+                image.data.len()
+            ),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+pub(super) fn pixbuf_from_image_data(image: ImageData) -> PlatformResult<Pixbuf> {
+    validate_image_data(&image)?;
+    Ok(Pixbuf::from_mut_slice(
+        image.data,
+        Colorspace::Rgb,
+        true,
+        8,
+        image.width,
+        image.height,
+        image.bytes_per_row,
+    ))
+}
+
+// glib-rs doesn't have object path variant constructor
+pub(super) fn object_path_variant(path: &str) -> glib::Variant {
+    unsafe {
+        glib::Variant::from_glib_none(glib_sys::g_variant_new_object_path(path.to_glib_none().0))
+    }
+}
+
+// Converts Cairo ARGB32 pixels (premultiplied BGRA on little endian) to
+// straight RGBA used by ImageData
+pub(super) fn premultiplied_bgra_to_rgba(data: &mut [u8]) {
+    let unpremultiply = |c: u8, a: u8| {
+        if a == 0 {
+            0
+        } else {
+            ((c as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8
+        }
+    };
+    for pixel in data.chunks_exact_mut(4) {
+        let (b, g, r, a) = (pixel[0], pixel[1], pixel[2], pixel[3]);
+        pixel[0] = unpremultiply(r, a);
+        pixel[1] = unpremultiply(g, a);
+        pixel[2] = unpremultiply(b, a);
+        pixel[3] = a;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::premultiplied_bgra_to_rgba;
+
+    #[test]
+    fn test_premultiplied_bgra_to_rgba() {
+        // Half transparent pixel; Truncating would result in (199, 99, 51)
+        let mut data = [26, 50, 100, 128, 10, 20, 30, 0];
+        premultiplied_bgra_to_rgba(&mut data);
+        assert_eq!(data, [199, 100, 52, 128, 0, 0, 0, 0]);
+    }
+}
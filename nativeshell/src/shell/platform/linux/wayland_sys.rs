@@ -0,0 +1,473 @@
+// Minimal libwayland-client bindings and interface definitions for
+// pointer-constraints-unstable-v1, relative-pointer-unstable-v1 and KDE
+// appmenu protocols.
+// Everything is resolved at runtime, so that nothing is required when running
+// on X11 or with GTK built without Wayland backend.
+
+#![allow(non_camel_case_types, clippy::upper_case_acronyms)]
+
+use std::{
+    cell::Cell,
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_int, c_void},
+    ptr::{null, null_mut},
+};
+
+use glib::translate::ToGlibPtr;
+
+pub type wl_proxy = c_void;
+pub type wl_event_queue = c_void;
+pub type wl_fixed_t = i32;
+
+#[repr(C)]
+pub struct wl_message {
+    pub name: *const c_char,
+    pub signature: *const c_char,
+    pub types: *const *const wl_interface,
+}
+
+#[repr(C)]
+pub struct wl_interface {
+    pub name: *const c_char,
+    pub version: c_int,
+    pub method_count: c_int,
+    pub methods: *const wl_message,
+    pub event_count: c_int,
+    pub events: *const wl_message,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union wl_argument {
+    pub i: i32,
+    pub u: u32,
+    pub f: wl_fixed_t,
+    pub s: *const c_char,
+    pub o: *mut wl_proxy,
+    pub n: u32,
+}
+
+pub fn wl_fixed_to_double(f: wl_fixed_t) -> f64 {
+    f as f64 / 256.0
+}
+
+// zwp_pointer_constraints_v1.lifetime
+pub const LIFETIME_PERSISTENT: u32 = 2;
+
+// Request opcodes
+pub const WL_DISPLAY_GET_REGISTRY: u32 = 1;
+pub const WL_REGISTRY_BIND: u32 = 0;
+pub const WL_COMPOSITOR_CREATE_REGION: u32 = 1;
+pub const WL_REGION_ADD: u32 = 1;
+// All protocol objects below have destroy request with opcode 0
+pub const DESTROY: u32 = 0;
+pub const POINTER_CONSTRAINTS_LOCK_POINTER: u32 = 1;
+pub const POINTER_CONSTRAINTS_CONFINE_POINTER: u32 = 2;
+pub const RELATIVE_POINTER_MANAGER_GET_RELATIVE_POINTER: u32 = 1;
+pub const APPMENU_MANAGER_CREATE: u32 = 0;
+pub const APPMENU_SET_ADDRESS: u32 = 0;
+pub const APPMENU_RELEASE: u32 = 1;
+
+pub type wl_registry_global = unsafe extern "C" fn(
+    data: *mut c_void,
+    registry: *mut wl_proxy,
+    name: u32,
+    interface: *const c_char,
+    version: u32,
+);
+
+pub type wl_registry_global_remove =
+    unsafe extern "C" fn(data: *mut c_void, registry: *mut wl_proxy, name: u32);
+
+#[repr(C)]
+pub struct wl_registry_listener {
+    pub global: wl_registry_global,
+    pub global_remove: wl_registry_global_remove,
+}
+
+#[repr(C)]
+pub struct zwp_relative_pointer_v1_listener {
+    pub relative_motion: unsafe extern "C" fn(
+        data: *mut c_void,
+        relative_pointer: *mut wl_proxy,
+        utime_hi: u32,
+        utime_lo: u32,
+        dx: wl_fixed_t,
+        dy: wl_fixed_t,
+        dx_unaccel: wl_fixed_t,
+        dy_unaccel: wl_fixed_t,
+    ),
+}
+
+pub struct Wayland {
+    pub wl_proxy_marshal_array_constructor: unsafe extern "C" fn(
+        proxy: *mut wl_proxy,
+        opcode: u32,
+        args: *mut wl_argument,
+        interface: *const wl_interface,
+    ) -> *mut wl_proxy,
+    pub wl_proxy_marshal_array_constructor_versioned: unsafe extern "C" fn(
+        proxy: *mut wl_proxy,
+        opcode: u32,
+        args: *mut wl_argument,
+        interface: *const wl_interface,
+        version: u32,
+    ) -> *mut wl_proxy,
+    pub wl_proxy_marshal_array:
+        unsafe extern "C" fn(proxy: *mut wl_proxy, opcode: u32, args: *mut wl_argument),
+    pub wl_proxy_add_listener: unsafe extern "C" fn(
+        proxy: *mut wl_proxy,
+        implementation: *const c_void,
+        data: *mut c_void,
+    ) -> c_int,
+    pub wl_proxy_destroy: unsafe extern "C" fn(proxy: *mut wl_proxy),
+    pub wl_proxy_create_wrapper: unsafe extern "C" fn(proxy: *mut wl_proxy) -> *mut wl_proxy,
+    pub wl_proxy_wrapper_destroy: unsafe extern "C" fn(proxy: *mut wl_proxy),
+    pub wl_proxy_set_queue: unsafe extern "C" fn(proxy: *mut wl_proxy, queue: *mut wl_event_queue),
+    pub wl_display_create_queue:
+        unsafe extern "C" fn(display: *mut wl_proxy) -> *mut wl_event_queue,
+    pub wl_display_roundtrip_queue:
+        unsafe extern "C" fn(display: *mut wl_proxy, queue: *mut wl_event_queue) -> c_int,
+    pub wl_event_queue_destroy: unsafe extern "C" fn(queue: *mut wl_event_queue),
+
+    pub gdk_wayland_display_get_wl_display:
+        unsafe extern "C" fn(display: *mut gdk_sys::GdkDisplay) -> *mut wl_proxy,
+    pub gdk_wayland_display_get_wl_compositor:
+        unsafe extern "C" fn(display: *mut gdk_sys::GdkDisplay) -> *mut wl_proxy,
+    pub gdk_wayland_window_get_wl_surface:
+        unsafe extern "C" fn(window: *mut gdk_sys::GdkWindow) -> *mut wl_proxy,
+    pub gdk_wayland_device_get_wl_pointer:
+        unsafe extern "C" fn(device: *mut gdk_sys::GdkDevice) -> *mut wl_proxy,
+
+    pub wl_registry_interface: *const wl_interface,
+    pub wl_region_interface: *const wl_interface,
+
+    pub zwp_pointer_constraints_v1_interface: *const wl_interface,
+    pub zwp_locked_pointer_v1_interface: *const wl_interface,
+    pub zwp_confined_pointer_v1_interface: *const wl_interface,
+    pub zwp_relative_pointer_manager_v1_interface: *const wl_interface,
+    pub zwp_relative_pointer_v1_interface: *const wl_interface,
+    pub org_kde_kwin_appmenu_manager_interface: *const wl_interface,
+    pub org_kde_kwin_appmenu_interface: *const wl_interface,
+}
+
+// Globals bound through registry; Null if compositor doesn't support the
+// protocol.
+#[derive(Clone, Copy)]
+pub struct WaylandGlobals {
+    pub pointer_constraints: *mut wl_proxy,
+    pub relative_pointer_manager: *mut wl_proxy,
+    pub appmenu_manager: *mut wl_proxy,
+}
+
+thread_local! {
+    static WAYLAND: Option<&'static Wayland> = unsafe { Wayland::load() };
+    static GLOBALS: Cell<Option<WaylandGlobals>> = const { Cell::new(None) };
+}
+
+struct RegistryData {
+    wayland: &'static Wayland,
+    globals: WaylandGlobals,
+}
+
+static REGISTRY_LISTENER: wl_registry_listener = wl_registry_listener {
+    global: on_registry_global,
+    global_remove: on_registry_global_remove,
+};
+
+impl Wayland {
+    pub fn get() -> Option<&'static Wayland> {
+        WAYLAND.with(|w| *w)
+    }
+
+    // Symbols are looked up in already loaded libraries (GDK Wayland backend
+    // links libwayland-client).
+    #[allow(clippy::missing_transmute_annotations)]
+    unsafe fn load() -> Option<&'static Wayland> {
+        let symbol = |name: &str| -> Option<*mut c_void> {
+            let name = CString::new(name).unwrap();
+            let symbol = libc::dlsym(libc::RTLD_DEFAULT, name.as_ptr());
+            if symbol.is_null() {
+                None
+            } else {
+                Some(symbol)
+            }
+        };
+        macro_rules! function {
+            ($name:expr) => {
+                std::mem::transmute(symbol($name)?)
+            };
+        }
+        let wl_surface_interface = symbol("wl_surface_interface")? as *const wl_interface;
+        let wl_pointer_interface = symbol("wl_pointer_interface")? as *const wl_interface;
+        let wl_region_interface = symbol("wl_region_interface")? as *const wl_interface;
+
+        let locked_pointer = Self::interface(
+            "zwp_locked_pointer_v1",
+            &[
+                ("destroy", "", &[]),
+                ("set_cursor_position_hint", "ff", &[null(), null()]),
+                ("set_region", "?o", &[wl_region_interface]),
+            ],
+            &[("locked", "", &[]), ("unlocked", "", &[])],
+        );
+        let confined_pointer = Self::interface(
+            "zwp_confined_pointer_v1",
+            &[
+                ("destroy", "", &[]),
+                ("set_region", "?o", &[wl_region_interface]),
+            ],
+            &[("confined", "", &[]), ("unconfined", "", &[])],
+        );
+        let pointer_constraints = Self::interface(
+            "zwp_pointer_constraints_v1",
+            &[
+                ("destroy", "", &[]),
+                (
+                    "lock_pointer",
+                    "noo?ou",
+                    &[
+                        locked_pointer,
+                        wl_surface_interface,
+                        wl_pointer_interface,
+                        wl_region_interface,
+                        null(),
+                    ],
+                ),
+                (
+                    "confine_pointer",
+                    "noo?ou",
+                    &[
+                        confined_pointer,
+                        wl_surface_interface,
+                        wl_pointer_interface,
+                        wl_region_interface,
+                        null(),
+                    ],
+                ),
+            ],
+            &[],
+        );
+        let relative_pointer = Self::interface(
+            "zwp_relative_pointer_v1",
+            &[("destroy", "", &[])],
+            &[(
+                "relative_motion",
+                "uuffff",
+                &[null(), null(), null(), null(), null(), null()],
+            )],
+        );
+        let relative_pointer_manager = Self::interface(
+            "zwp_relative_pointer_manager_v1",
+            &[
+                ("destroy", "", &[]),
+                (
+                    "get_relative_pointer",
+                    "no",
+                    &[relative_pointer, wl_pointer_interface],
+                ),
+            ],
+            &[],
+        );
+
+        let appmenu = Self::interface(
+            "org_kde_kwin_appmenu",
+            &[
+                ("set_address", "ss", &[null(), null()]),
+                ("release", "", &[]),
+            ],
+            &[],
+        );
+        let appmenu_manager = Self::interface(
+            "org_kde_kwin_appmenu_manager",
+            &[
+                ("create", "no", &[appmenu, wl_surface_interface]),
+                ("release", "", &[]),
+            ],
+            &[],
+        );
+
+        let res = Wayland {
+            wl_proxy_marshal_array_constructor: function!("wl_proxy_marshal_array_constructor"),
+            wl_proxy_marshal_array_constructor_versioned: function!(
+                "wl_proxy_marshal_array_constructor_versioned"
+            ),
+            wl_proxy_marshal_array: function!("wl_proxy_marshal_array"),
+            wl_proxy_add_listener: function!("wl_proxy_add_listener"),
+            wl_proxy_destroy: function!("wl_proxy_destroy"),
+            wl_proxy_create_wrapper: function!("wl_proxy_create_wrapper"),
+            wl_proxy_wrapper_destroy: function!("wl_proxy_wrapper_destroy"),
+            wl_proxy_set_queue: function!("wl_proxy_set_queue"),
+            wl_display_create_queue: function!("wl_display_create_queue"),
+            wl_display_roundtrip_queue: function!("wl_display_roundtrip_queue"),
+            wl_event_queue_destroy: function!("wl_event_queue_destroy"),
+            gdk_wayland_display_get_wl_display: function!("gdk_wayland_display_get_wl_display"),
+            gdk_wayland_display_get_wl_compositor: function!(
+                "gdk_wayland_display_get_wl_compositor"
+            ),
+            gdk_wayland_window_get_wl_surface: function!("gdk_wayland_window_get_wl_surface"),
+            gdk_wayland_device_get_wl_pointer: function!("gdk_wayland_device_get_wl_pointer"),
+            wl_registry_interface: symbol("wl_registry_interface")? as *const _,
+            wl_region_interface,
+            zwp_pointer_constraints_v1_interface: pointer_constraints,
+            zwp_locked_pointer_v1_interface: locked_pointer,
+            zwp_confined_pointer_v1_interface: confined_pointer,
+            zwp_relative_pointer_manager_v1_interface: relative_pointer_manager,
+            zwp_relative_pointer_v1_interface: relative_pointer,
+            org_kde_kwin_appmenu_manager_interface: appmenu_manager,
+            org_kde_kwin_appmenu_interface: appmenu,
+        };
+        Some(Box::leak(Box::new(res)))
+    }
+
+    // Interface definitions are leaked; They are created once per thread
+    fn interface(
+        name: &str,
+        methods: &[(&str, &str, &[*const wl_interface])],
+        events: &[(&str, &str, &[*const wl_interface])],
+    ) -> *const wl_interface {
+        fn string(s: &str) -> *const c_char {
+            CString::new(s).unwrap().into_raw()
+        }
+        fn messages(messages: &[(&str, &str, &[*const wl_interface])]) -> *const wl_message {
+            let messages: Vec<wl_message> = messages
+                .iter()
+                .map(|(name, signature, types)| wl_message {
+                    name: string(name),
+                    signature: string(signature),
+                    types: Box::leak(types.to_vec().into_boxed_slice()).as_ptr(),
+                })
+                .collect();
+            Box::leak(messages.into_boxed_slice()).as_ptr()
+        }
+        Box::leak(Box::new(wl_interface {
+            name: string(name),
+            version: 1,
+            method_count: methods.len() as c_int,
+            methods: messages(methods),
+            event_count: events.len() as c_int,
+            events: messages(events),
+        }))
+    }
+
+    // Binds globals on first call. Registry uses private event queue, so that
+    // GDK events are not dispatched from within the roundtrip.
+    pub fn globals(&'static self, display: &gdk::Display) -> WaylandGlobals {
+        if let Some(globals) = GLOBALS.with(|g| g.get()) {
+            return globals;
+        }
+        let mut data = RegistryData {
+            wayland: self,
+            globals: WaylandGlobals {
+                pointer_constraints: null_mut(),
+                relative_pointer_manager: null_mut(),
+                appmenu_manager: null_mut(),
+            },
+        };
+        unsafe {
+            let wl_display = (self.gdk_wayland_display_get_wl_display)(display.to_glib_none().0);
+            let queue = (self.wl_display_create_queue)(wl_display);
+            let wrapper = (self.wl_proxy_create_wrapper)(wl_display);
+            (self.wl_proxy_set_queue)(wrapper, queue);
+            let registry = (self.wl_proxy_marshal_array_constructor)(
+                wrapper,
+                WL_DISPLAY_GET_REGISTRY,
+                [wl_argument { n: 0 }].as_mut_ptr(),
+                self.wl_registry_interface,
+            );
+            (self.wl_proxy_wrapper_destroy)(wrapper);
+            (self.wl_proxy_add_listener)(
+                registry,
+                &REGISTRY_LISTENER as *const _ as *const c_void,
+                &mut data as *mut _ as *mut c_void,
+            );
+            (self.wl_display_roundtrip_queue)(wl_display, queue);
+            // wl_registry has no destroy request
+            (self.wl_proxy_destroy)(registry);
+            // Objects created from globals must dispatch on GDK queue
+            let globals = &data.globals;
+            for proxy in [
+                globals.pointer_constraints,
+                globals.relative_pointer_manager,
+                globals.appmenu_manager,
+            ] {
+                if !proxy.is_null() {
+                    (self.wl_proxy_set_queue)(proxy, null_mut());
+                }
+            }
+            (self.wl_event_queue_destroy)(queue);
+        }
+        GLOBALS.with(|g| g.set(Some(data.globals)));
+        data.globals
+    }
+
+    // Sends destroy request and destroys the proxy
+    pub unsafe fn destroy(&self, proxy: *mut wl_proxy) {
+        self.destroy_with_request(proxy, DESTROY);
+    }
+
+    // For objects where destructor is not the first request
+    pub unsafe fn destroy_with_request(&self, proxy: *mut wl_proxy, opcode: u32) {
+        if !proxy.is_null() {
+            (self.wl_proxy_marshal_array)(proxy, opcode, std::ptr::null_mut());
+            (self.wl_proxy_destroy)(proxy);
+        }
+    }
+}
+
+// Interface versions implemented by the bindings above
+const POINTER_CONSTRAINTS_VERSION: u32 = 1;
+const RELATIVE_POINTER_MANAGER_VERSION: u32 = 1;
+const APPMENU_MANAGER_VERSION: u32 = 1;
+
+unsafe extern "C" fn on_registry_global(
+    data: *mut c_void,
+    registry: *mut wl_proxy,
+    name: u32,
+    interface: *const c_char,
+    version: u32,
+) {
+    let data = &mut *(data as *mut RegistryData);
+    let wayland = data.wayland;
+    let (target, target_interface, supported_version) = match CStr::from_ptr(interface).to_bytes() {
+        b"zwp_pointer_constraints_v1" => (
+            &mut data.globals.pointer_constraints,
+            wayland.zwp_pointer_constraints_v1_interface,
+            POINTER_CONSTRAINTS_VERSION,
+        ),
+        b"zwp_relative_pointer_manager_v1" => (
+            &mut data.globals.relative_pointer_manager,
+            wayland.zwp_relative_pointer_manager_v1_interface,
+            RELATIVE_POINTER_MANAGER_VERSION,
+        ),
+        b"org_kde_kwin_appmenu_manager" => (
+            &mut data.globals.appmenu_manager,
+            wayland.org_kde_kwin_appmenu_manager_interface,
+            APPMENU_MANAGER_VERSION,
+        ),
+        _ => return,
+    };
+    let version = version.min(supported_version);
+    *target = (wayland.wl_proxy_marshal_array_constructor_versioned)(
+        registry,
+        WL_REGISTRY_BIND,
+        [
+            wl_argument { u: name },
+            wl_argument {
+                s: (*target_interface).name,
+            },
+            wl_argument { u: version },
+            wl_argument { n: 0 },
+        ]
+        .as_mut_ptr(),
+        target_interface,
+        version,
+    );
+}
+
+unsafe extern "C" fn on_registry_global_remove(
+    _data: *mut c_void,
+    _registry: *mut wl_proxy,
+    _name: u32,
+) {
+}
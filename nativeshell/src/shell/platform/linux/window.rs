@@ -1,40 +1,56 @@
 use std::{
     cell::{Cell, RefCell},
     collections::HashMap,
+    ffi::CString,
+    os::raw::c_char,
     rc::{Rc, Weak},
     time::Duration,
 };
 
-use gdk::{Display, Event, EventType, EventWindowState, WMDecoration, WMFunction};
-use glib::{Cast, ObjectExt};
+use cairo::{Format, ImageSurface};
+use gdk::{
+    Cursor, CursorType, Display, Event, EventType, EventWindowState, WMDecoration, WMFunction,
+    WindowTypeHint,
+};
+use glib::{translate::ToGlibPtr, Cast, Continue, ObjectExt};
 use gtk::{
-    prelude::{ContainerExt, GtkWindowExt, OverlayExt, WidgetExt},
-    propagate_event, EventBox, Inhibit, Overlay, Widget,
+    prelude::{BoxExt, ContainerExt, GtkWindowExt, OverlayExt, WidgetExt},
+    propagate_event, EventBox, Inhibit, Orientation, Overlay, Widget,
 };
 
 use crate::{
     codec::Value,
     shell::{
         api_model::{
-            BoolTransition, DragEffect, DragRequest, PopupMenuRequest, PopupMenuResponse,
-            WindowCollectionBehavior, WindowFrame, WindowGeometry, WindowGeometryFlags,
-            WindowGeometryRequest, WindowStateFlags, WindowStyle,
+            AlertRequest, AlertResponse, BoolTransition, Color, DragEffect, DragRequest, ImageData,
+            LayerShellLayer, LayerShellOptions, PopupMenuRequest, PopupMenuResponse,
+            WindowCollectionBehavior, WindowCreateOptions, WindowDragRegions, WindowEdge,
+            WindowFrame, WindowGeometry, WindowGeometryFlags, WindowGeometryRequest,
+            WindowStateFlags, WindowStyle, WindowType,
         },
-        Context, PlatformWindowDelegate, Point, Size,
+        Context, PlatformWindowDelegate, Point, Rect, Size,
     },
     util::{LateRefCell, OkLog},
 };
 
 use super::{
+    alert::show_alert,
     drag_context::{DragContext, DropContext},
     engine::PlatformEngine,
     error::{PlatformError, PlatformResult},
     flutter::View,
+    layer_shell::{configure_window, init_for_window, is_layer_window, layer_shell_available},
     menu::PlatformMenu,
+    pointer_lock::{PointerConstraint, PointerLock},
     screen_manager::PlatformScreenManager,
     size_widget::{create_size_widget, size_widget_set_min_size},
-    utils::{get_session_type, synthetize_button_up, translate_event_to_window, SessionType},
+    utils::{
+        get_session_type, pixbuf_from_image_data, premultiplied_bgra_to_rgba, synthetize_button_up,
+        synthetize_leave_event_from_motion, synthetize_motion_event, translate_event_to_window,
+        SessionType,
+    },
     window_menu::WindowMenu,
+    x11_sys,
 };
 
 pub type PlatformWindowType = gtk::Window;
@@ -71,6 +87,35 @@ pub struct PlatformWindow {
     pub(super) drop_context: LateRefCell<DropContext>,
     drag_context: LateRefCell<DragContext>,
     window_state: RefCell<WindowState>,
+    // Layer shell options emulated on X11 by positioning window manually
+    layer_shell_fallback: RefCell<Option<LayerShellOptions>>,
+    collection_behavior: RefCell<WindowCollectionBehavior>,
+    // Window with overriden cursor and its original cursor while pointer is over
+    // resize border
+    resize_cursor: RefCell<Option<(gdk::Window, Option<gdk::Cursor>)>>,
+    drag_regions: RefCell<WindowDragRegions>,
+    // Button press inside drag region that was not forwarded to flutter
+    drag_region_press: RefCell<Option<DragRegionPress>>,
+    ignore_mouse_events: Cell<bool>,
+    input_region: RefCell<Option<Vec<Rect>>>,
+    // Polls pointer position while mouse events are ignored, see forward_pointer_motion
+    forward_motion_source: RefCell<Option<glib::SourceId>>,
+    // Last synthetized motion event delivered to flutter while forwarding motion
+    forwarded_motion: RefCell<Option<Event>>,
+    content: gtk::Box,
+    // Fallback for application menu when there is no global menu
+    app_menu_bar: RefCell<Option<gtk::MenuBar>>,
+    pointer_lock: RefCell<Option<PointerLock>>,
+    hide_pointer_while_typing: Cell<bool>,
+    // Window with cursor hidden after key press and its original cursor
+    typing_cursor: RefCell<Option<(gdk::Window, Option<gdk::Cursor>)>>,
+    // Per window override of ContextOptions::application_id
+    application_id: RefCell<Option<CString>>,
+    // Window origin and size last reported through geometry_changed
+    last_reported_geometry: Cell<Option<(i32, i32, i32, i32)>>,
+    geometry_change_scheduled: Cell<bool>,
+    // Window origin while not maximized or full screen, see get_normal_frame
+    normal_origin: Cell<Option<(i32, i32)>>,
 }
 
 impl PlatformWindow {
@@ -78,8 +123,9 @@ impl PlatformWindow {
         context: Context,
         delegate: Weak<dyn PlatformWindowDelegate>,
         parent: Option<Rc<PlatformWindow>>,
+        options: &WindowCreateOptions,
     ) -> Self {
-        Self {
+        let res = Self {
             context,
             window: gtk::Window::new(gtk::WindowType::Toplevel),
             weak_self: LateRefCell::new(),
@@ -101,11 +147,47 @@ impl PlatformWindow {
             drop_context: LateRefCell::new(),
             drag_context: LateRefCell::new(),
             window_state: RefCell::new(Default::default()),
+            layer_shell_fallback: RefCell::new(None),
+            collection_behavior: RefCell::new(Default::default()),
+            resize_cursor: RefCell::new(None),
+            drag_regions: RefCell::new(Default::default()),
+            drag_region_press: RefCell::new(None),
+            ignore_mouse_events: Cell::new(false),
+            input_region: RefCell::new(None),
+            forward_motion_source: RefCell::new(None),
+            forwarded_motion: RefCell::new(None),
+            content: gtk::Box::new(Orientation::Vertical, 0),
+            app_menu_bar: RefCell::new(None),
+            pointer_lock: RefCell::new(None),
+            hide_pointer_while_typing: Cell::new(false),
+            typing_cursor: RefCell::new(None),
+            application_id: RefCell::new(None),
+            last_reported_geometry: Cell::new(None),
+            geometry_change_scheduled: Cell::new(false),
+            normal_origin: Cell::new(None),
+        };
+        // Surface role is assigned when the window is realized, so layer shell
+        // must be initialized before that
+        let layer_shell = options.style.as_ref().and_then(|s| s.layer_shell.as_ref());
+        if layer_shell.is_some()
+            && get_session_type() == SessionType::Wayland
+            && layer_shell_available()
+        {
+            init_for_window(&res.window);
         }
+        res
     }
 
     pub fn on_first_frame(&self) {
-        self.window.set_opacity(1.0);
+        self.window.set_opacity(self.style_opacity());
+    }
+
+    fn style_opacity(&self) -> f64 {
+        self.last_window_style
+            .borrow()
+            .as_ref()
+            .and_then(|style| style.opacity)
+            .unwrap_or(1.0)
     }
 
     pub fn engine_launched(&self) {
@@ -118,7 +200,8 @@ impl PlatformWindow {
         self.window_menu.set(WindowMenu::new(weak.clone()));
 
         let overlay = Overlay::new();
-        self.window.add(&overlay);
+        self.window.add(&self.content);
+        self.content.pack_end(&overlay, true, true, 0);
 
         overlay.add(&self.size_widget);
         overlay.add_overlay(&self.window_menu.borrow().menu_bar_container);
@@ -135,6 +218,25 @@ impl PlatformWindow {
             }
         });
 
+        // Wayland toplevel is recreated every time the window is shown
+        let weak_clone = weak.clone();
+        self.window.connect_map(move |_| {
+            if let Some(s) = weak_clone.upgrade() {
+                if get_session_type() == SessionType::Wayland {
+                    s.on_wayland_map();
+                }
+            }
+        });
+
+        // Size allocation is not performed when window is only moved
+        let weak_clone = weak.clone();
+        self.window.connect_configure_event(move |_, _| {
+            if let Some(s) = weak_clone.upgrade() {
+                s.schedule_geometry_changed();
+            }
+            false
+        });
+
         let weak_clone = weak.clone();
         self.window.connect_window_state_event(move |_, state| {
             if let Some(s) = weak_clone.upgrade() {
@@ -143,14 +245,26 @@ impl PlatformWindow {
             Inhibit(false)
         });
 
-        self.window.realize();
-        unsafe {
-            self.window
-                .window()
-                .unwrap()
-                .set_data("nativeshell_platform_window", weak);
+        // Visual can't be changed once the view is realized (it would lose its
+        // GL context), so use RGBA visual if possible and let set_style decide
+        // whether window background is actually transparent.
+        if let Some(visual) = gtk::prelude::WidgetExt::screen(&self.window)
+            .filter(|screen| screen.is_composited())
+            .and_then(|screen| screen.rgba_visual())
+        {
+            self.window.set_visual(Some(&visual));
         }
 
+        let weak_clone = weak.clone();
+        self.window.connect_draw(move |_, cr| {
+            if let Some(s) = weak_clone.upgrade() {
+                s.draw_background(cr);
+            }
+            Inhibit(false)
+        });
+
+        self.realize_window();
+
         // by default make window resizable, non resizable window need size
         // specified
         self.window.set_resizable(true);
@@ -173,17 +287,108 @@ impl PlatformWindow {
         }
         self.connect_drag_drop_events();
 
+        if let Some(context) = self.context.get() {
+            context
+                .menu_manager
+                .borrow()
+                .borrow()
+                .get_platform_menu_manager()
+                .register_window(self.weak_self.clone_value());
+        }
+
         self.schedule_first_frame_notification();
     }
 
+    fn on_wayland_map(&self) {
+        self.apply_application_id().ok_log();
+        if let Some(context) = self.context.get() {
+            context
+                .menu_manager
+                .borrow()
+                .borrow()
+                .get_platform_menu_manager()
+                .window_mapped(self);
+        }
+    }
+
+    pub(super) fn set_app_menu_bar(
+        &self,
+        model: Option<&gio::Menu>,
+        actions: &gio::SimpleActionGroup,
+    ) {
+        if let Some(menu_bar) = self.app_menu_bar.take() {
+            self.content.remove(&menu_bar);
+        }
+        match model {
+            Some(model) => {
+                let menu_bar = gtk::MenuBar::from_model(model);
+                self.content.pack_start(&menu_bar, false, false, 0);
+                menu_bar.show_all();
+                self.window.insert_action_group("app", Some(actions));
+                self.app_menu_bar.replace(Some(menu_bar));
+            }
+            None => {
+                self.window
+                    .insert_action_group("app", None::<&gio::ActionGroup>);
+            }
+        }
+    }
+
+    // Window content (as seen by Flutter) doesn't include the menu bar
+    fn app_menu_bar_height(&self) -> i32 {
+        self.app_menu_bar
+            .borrow()
+            .as_ref()
+            .map(|menu_bar| menu_bar.preferred_height().1)
+            .unwrap_or(0)
+    }
+
+    fn draw_background(&self, cr: &cairo::Context) {
+        let color = match &*self.last_window_style.borrow() {
+            Some(style) => match (&style.background_color, style.transparent) {
+                (Some(color), _) => *color,
+                (None, true) => Color::default(),
+                (None, false) => return,
+            },
+            None => return,
+        };
+        let (r, g, b, a) = color.to_rgba_f64();
+        cr.save().ok_log();
+        cr.set_operator(cairo::Operator::Source);
+        cr.set_source_rgba(r, g, b, a);
+        cr.paint().ok_log();
+        cr.restore().ok_log();
+    }
+
+    fn realize_window(&self) {
+        self.window.realize();
+        unsafe {
+            self.window
+                .window()
+                .unwrap()
+                .set_data("nativeshell_platform_window", self.weak_self.clone_value());
+        }
+        if get_session_type() == SessionType::X11 {
+            self.apply_application_id().ok_log();
+        }
+    }
+
     fn on_size_allocate(&self) {
-        let mut state = self.window_state.borrow_mut();
-        if !state.is_maximized && !state.is_full_screen {
-            let size = self.window.size();
-            state.width = size.0;
-            state.height = size.1;
+        {
+            let mut state = self.window_state.borrow_mut();
+            if !state.is_maximized && !state.is_full_screen {
+                let size = self.window.size();
+                state.width = size.0;
+                state.height = size.1;
+            }
+        }
+        self.update_layer_shell_fallback_position();
+        if self.input_region.borrow().is_some() {
+            // content offset might have changed
+            self.update_input_shape();
         }
         self.window_size_in_progress.set(false);
+        self.schedule_geometry_changed();
         if self.pending_geometry_request.borrow().is_some() {
             // This must be done after Gtk allocation is done, so schedule it on next
             // run loop turn
@@ -204,6 +409,45 @@ impl PlatformWindow {
         }
     }
 
+    // Notifies delegate on next run loop turn, when Gtk is done with
+    // allocation; Also size allocation may happen while the window is being
+    // created, before delegate is ready to be called.
+    fn schedule_geometry_changed(&self) {
+        if self.geometry_change_scheduled.replace(true) {
+            return;
+        }
+        let weak_self = self.weak_self.borrow().clone();
+        if let Some(context) = self.context.get() {
+            context
+                .run_loop
+                .borrow()
+                .schedule_now(move || {
+                    if let Some(s) = weak_self.upgrade() {
+                        s.geometry_change_scheduled.set(false);
+                        s.report_geometry_changed();
+                    }
+                })
+                .detach();
+        }
+    }
+
+    fn report_geometry_changed(&self) {
+        let origin = self.window.position();
+        let size = self.window.size();
+        {
+            let state = self.window_state.borrow();
+            if !state.is_maximized && !state.is_full_screen && !state.is_minimized {
+                self.normal_origin.set(Some(origin));
+            }
+        }
+        let geometry = Some((origin.0, origin.1, size.0, size.1));
+        if self.last_reported_geometry.replace(geometry) != geometry {
+            if let Some(delegate) = self.delegate.upgrade() {
+                delegate.geometry_changed();
+            }
+        }
+    }
+
     fn on_window_state_changed(&self, state: &EventWindowState) {
         let state_flags_changed = {
             let mut window_state = self.window_state.borrow_mut();
@@ -220,6 +464,9 @@ impl PlatformWindow {
             window_state.is_active = state.new_window_state().contains(gdk::WindowState::FOCUSED);
             *window_state != prev_state
         };
+        if self.window_state.borrow().is_active && self.window.is_urgency_hint() {
+            self.window.set_urgency_hint(false);
+        }
         if state_flags_changed {
             if let Some(delegate) = self.delegate.upgrade() {
                 delegate.state_flags_changed();
@@ -390,6 +637,7 @@ impl PlatformWindow {
 
     pub fn hide(&self) -> PlatformResult<()> {
         if self.ready_to_show.get() {
+            self.pointer_lock.replace(None);
             self.window.hide();
             if let Some(delegate) = self.delegate.upgrade() {
                 delegate.visibility_changed(false);
@@ -423,7 +671,17 @@ impl PlatformWindow {
         }
     }
 
-    pub(super) fn on_event(&self, event: &mut Event) {
+    // Returns true if event was handled and should not be dispatched further
+    pub(super) fn on_event(&self, event: &mut Event) -> bool {
+        self.handle_hide_pointer_while_typing(event);
+
+        if self.handle_pointer_lock(event)
+            || self.handle_resize_border(event)
+            || self.handle_drag_regions(event)
+        {
+            return true;
+        }
+
         if event.event_type() == EventType::ButtonPress
             || event.event_type() == EventType::ButtonRelease
             || event.event_type() == EventType::KeyPress
@@ -435,6 +693,15 @@ impl PlatformWindow {
                 .insert(event.event_type(), event.clone());
         }
 
+        if event.event_type() == EventType::FocusChange {
+            if let Some(context) = self.context.get() {
+                context
+                    .application_delegate_manager
+                    .borrow()
+                    .window_focus_changed();
+            }
+        }
+
         if event.event_type() == EventType::KeyPress {
             if let Some(context) = self.context.get() {
                 context
@@ -449,6 +716,257 @@ impl PlatformWindow {
         if self.window_menu.borrow().should_forward_event(event) {
             self.propagate_event(event);
         }
+
+        false
+    }
+
+    fn handle_pointer_lock(&self, event: &Event) -> bool {
+        let (swallow, delta) = match &*self.pointer_lock.borrow() {
+            Some(lock) => match (event.event_type(), event.root_coords()) {
+                (EventType::MotionNotify, Some(coords)) => {
+                    (lock.swallows_motion(), lock.handle_motion(coords))
+                }
+                (EventType::GrabBroken, _) => (false, None),
+                _ => return false,
+            },
+            None => return false,
+        };
+        if event.event_type() == EventType::GrabBroken {
+            self.pointer_lock.replace(None);
+            return false;
+        }
+        // Delegate may release the pointer
+        if let (Some(delta), Some(delegate)) = (delta, self.delegate.upgrade()) {
+            delegate.relative_pointer_motion(delta);
+        }
+        swallow
+    }
+
+    fn handle_hide_pointer_while_typing(&self, event: &Event) {
+        match event.event_type() {
+            EventType::KeyPress => {
+                if !self.hide_pointer_while_typing.get()
+                    || self.pointer_lock.borrow().is_some()
+                    || self.typing_cursor.borrow().is_some()
+                {
+                    return;
+                }
+                if let Some(window) = self.view.borrow().window() {
+                    let cursor = window.cursor();
+                    let blank_cursor =
+                        Cursor::for_display(&window.display(), CursorType::BlankCursor);
+                    window.set_cursor(Some(&blank_cursor));
+                    self.typing_cursor.replace(Some((window, cursor)));
+                }
+            }
+            EventType::MotionNotify | EventType::ButtonPress | EventType::Scroll => {
+                self.restore_typing_cursor();
+            }
+            _ => {}
+        }
+    }
+
+    fn restore_typing_cursor(&self) {
+        if let Some((window, cursor)) = self.typing_cursor.take() {
+            window.set_cursor(cursor.as_ref());
+        }
+    }
+
+    fn handle_resize_border(&self, event: &Event) -> bool {
+        let edge = match event.event_type() {
+            EventType::MotionNotify | EventType::ButtonPress => {
+                event.root_coords().and_then(|c| self.resize_edge_at(c))
+            }
+            EventType::LeaveNotify => None,
+            _ => return false,
+        };
+        self.update_resize_cursor(event, edge);
+        match (event.event_type(), edge) {
+            (EventType::ButtonPress, Some(edge)) if event.button() == Some(1) => {
+                if let Some(coords) = event.root_coords() {
+                    self.window.window().unwrap().begin_resize_drag(
+                        gdk_window_edge(edge),
+                        1,
+                        coords.0 as i32,
+                        coords.1 as i32,
+                        event.time(),
+                    );
+                }
+                true
+            }
+            // Don't let flutter override the cursor
+            (EventType::MotionNotify, Some(_)) => true,
+            _ => false,
+        }
+    }
+
+    fn handle_drag_regions(&self, event: &Event) -> bool {
+        match event.event_type() {
+            EventType::ButtonPress | EventType::DoubleButtonPress => {
+                let in_region = event
+                    .root_coords()
+                    .map(|c| self.is_in_drag_region(c))
+                    .unwrap_or(false);
+                let button = match event.button() {
+                    Some(button) if in_region && (button == 1 || button == 3) => button,
+                    _ => return false,
+                };
+                if event.event_type() == EventType::DoubleButtonPress {
+                    if button == 1 {
+                        let maximized = self.window_state.borrow().is_maximized;
+                        self.set_maximized(!maximized).ok_log();
+                    }
+                    // Release still needs to be swallowed, but there should be no drag
+                    if let Some(press) = self.drag_region_press.borrow_mut().as_mut() {
+                        press.can_drag = false;
+                    }
+                    return true;
+                }
+                self.drag_region_press.replace(Some(DragRegionPress {
+                    event: event.clone(),
+                    button,
+                    can_drag: button == 1,
+                }));
+                if button == 3 {
+                    let mut event = event.clone();
+                    self.window.window().unwrap().show_window_menu(&mut event);
+                }
+                true
+            }
+            EventType::MotionNotify => {
+                let press = match &*self.drag_region_press.borrow() {
+                    Some(press) if press.can_drag => press.event.clone(),
+                    Some(_) => return true,
+                    None => return false,
+                };
+                if let (Some(start), Some(current)) = (press.root_coords(), event.root_coords()) {
+                    if self.window.drag_check_threshold(
+                        start.0 as i32,
+                        start.1 as i32,
+                        current.0 as i32,
+                        current.1 as i32,
+                    ) {
+                        // Release event will be eaten by window manager
+                        self.drag_region_press.replace(None);
+                        self.window.window().unwrap().begin_move_drag(
+                            1,
+                            start.0 as i32,
+                            start.1 as i32,
+                            event.time(),
+                        );
+                    }
+                }
+                true
+            }
+            EventType::ButtonRelease => {
+                let mut press = self.drag_region_press.borrow_mut();
+                match &*press {
+                    Some(p) if Some(p.button) == event.button() => {
+                        press.take();
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    fn is_in_drag_region(&self, root_coords: (f64, f64)) -> bool {
+        let regions = self.drag_regions.borrow();
+        if regions.is_empty() {
+            return false;
+        }
+        self.root_to_content(root_coords)
+            .map(|point| regions.is_draggable(&point))
+            .unwrap_or(false)
+    }
+
+    // Offset of flutter view within the toplevel window
+    fn content_offset(&self) -> (i32, i32) {
+        self.view
+            .borrow()
+            .translate_coordinates(&self.window, 0, 0)
+            .unwrap_or((0, 0))
+    }
+
+    // Converts root (screen) coordinates to logical coordinates within flutter view
+    fn root_to_content(&self, root_coords: (f64, f64)) -> Option<Point> {
+        let (_, origin_x, origin_y) = self.window.window()?.origin();
+        let (view_x, view_y) = self.content_offset();
+        Some(Point::xy(
+            root_coords.0 - (origin_x + view_x) as f64,
+            root_coords.1 - (origin_y + view_y) as f64,
+        ))
+    }
+
+    fn resize_edge_at(&self, root_coords: (f64, f64)) -> Option<WindowEdge> {
+        let border = match &*self.last_window_style.borrow() {
+            Some(style) if style.can_resize => style.resize_border_width?,
+            _ => return None,
+        };
+        {
+            let state = self.window_state.borrow();
+            if border <= 0.0 || state.is_maximized || state.is_full_screen {
+                return None;
+            }
+        }
+        let window = self.window.window()?;
+        let (_, origin_x, origin_y) = window.origin();
+        let x = root_coords.0 - origin_x as f64;
+        let y = root_coords.1 - origin_y as f64;
+        let width = window.width() as f64;
+        let height = window.height() as f64;
+        let left = x < border;
+        let right = x >= width - border;
+        let top = y < border;
+        let bottom = y >= height - border;
+        match (left, right, top, bottom) {
+            (true, _, true, _) => Some(WindowEdge::TopLeft),
+            (_, true, true, _) => Some(WindowEdge::TopRight),
+            (true, _, _, true) => Some(WindowEdge::BottomLeft),
+            (_, true, _, true) => Some(WindowEdge::BottomRight),
+            (true, _, _, _) => Some(WindowEdge::Left),
+            (_, true, _, _) => Some(WindowEdge::Right),
+            (_, _, true, _) => Some(WindowEdge::Top),
+            (_, _, _, true) => Some(WindowEdge::Bottom),
+            _ => None,
+        }
+    }
+
+    fn update_resize_cursor(&self, event: &Event, edge: Option<WindowEdge>) {
+        let mut resize_cursor = self.resize_cursor.borrow_mut();
+        match edge {
+            Some(edge) => {
+                let (window, _) = match &*resize_cursor {
+                    Some(resize_cursor) => resize_cursor,
+                    None => match event.window() {
+                        Some(window) => {
+                            let cursor = window.cursor();
+                            resize_cursor.insert((window, cursor))
+                        }
+                        None => return,
+                    },
+                };
+                let name = match edge {
+                    WindowEdge::Top => "n-resize",
+                    WindowEdge::Bottom => "s-resize",
+                    WindowEdge::Left => "w-resize",
+                    WindowEdge::Right => "e-resize",
+                    WindowEdge::TopLeft => "nw-resize",
+                    WindowEdge::TopRight => "ne-resize",
+                    WindowEdge::BottomLeft => "sw-resize",
+                    WindowEdge::BottomRight => "se-resize",
+                };
+                let cursor = gdk::Cursor::from_name(&window.display(), name);
+                window.set_cursor(cursor.as_ref());
+            }
+            None => {
+                if let Some((window, cursor)) = resize_cursor.take() {
+                    window.set_cursor(cursor.as_ref());
+                }
+            }
+        }
     }
 
     pub(super) fn propagate_event(&self, event: &mut Event) {
@@ -460,6 +978,7 @@ impl PlatformWindow {
     }
 
     pub fn close(&self) -> PlatformResult<()> {
+        self.pointer_lock.replace(None);
         self.deleting.replace(true);
         self.window.close();
         Ok(())
@@ -487,10 +1006,7 @@ impl PlatformWindow {
         }
 
         self.window.set_modal(true);
-        self.window
-            .window()
-            .unwrap()
-            .set_type_hint(gdk::WindowTypeHint::Dialog);
+        self.update_window_hints();
 
         self.show().ok_log();
     }
@@ -544,8 +1060,10 @@ impl PlatformWindow {
                     );
                     self.window.queue_resize();
                 } else {
-                    self.window
-                        .resize(content_size.width as i32, content_size.height as i32);
+                    self.window.resize(
+                        content_size.width as i32,
+                        content_size.height as i32 + self.app_menu_bar_height(),
+                    );
                 }
             }
         }
@@ -576,7 +1094,10 @@ impl PlatformWindow {
         };
 
         let content_size = self.window.size();
-        let content_size = Size::wh(content_size.0 as f64, content_size.1 as f64);
+        let content_size = Size::wh(
+            content_size.0 as f64,
+            (content_size.1 - self.app_menu_bar_height()) as f64,
+        );
 
         Ok(WindowGeometry {
             frame_origin,
@@ -599,16 +1120,195 @@ impl PlatformWindow {
         })
     }
 
+    // Origin is last position of the window while it was not maximized or
+    // full screen; Only known on X11
+    pub fn get_normal_frame(&self) -> PlatformResult<Option<Rect>> {
+        if get_session_type() != SessionType::X11 {
+            return Ok(None);
+        }
+        let state = self.window_state.borrow();
+        Ok(self.normal_origin.get().map(|origin| {
+            Rect::xywh(
+                origin.0 as f64,
+                origin.1 as f64,
+                state.width as f64,
+                (state.height - self.app_menu_bar_height()) as f64,
+            )
+        }))
+    }
+
     pub fn set_title(&self, title: String) -> PlatformResult<()> {
         self.window.set_title(&title);
         Ok(())
     }
 
+    pub fn set_icon(&self, images: Vec<ImageData>) -> PlatformResult<()> {
+        let pixbufs = images
+            .into_iter()
+            .map(pixbuf_from_image_data)
+            .collect::<PlatformResult<Vec<_>>>()?;
+        self.window.set_icon_list(&pixbufs);
+        Ok(())
+    }
+
+    // X11 window managers read the class when window is mapped, Wayland app_id
+    // can only be set once the toplevel surface exists. The id is stored and
+    // applied when window is realized (X11) or mapped (Wayland).
+    pub fn set_application_id(&self, application_id: String) -> PlatformResult<()> {
+        let id = CString::new(application_id).map_err(|_| PlatformError::OtherError {
+            error: "Invalid application id".into(),
+        })?;
+        self.application_id.replace(Some(id));
+        let can_apply = match get_session_type() {
+            SessionType::X11 => self.window.is_realized(),
+            SessionType::Wayland => self.window.is_mapped(),
+        };
+        if can_apply {
+            self.apply_application_id()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn apply_application_id(&self) -> PlatformResult<()> {
+        let id = match &*self.application_id.borrow() {
+            Some(id) => id.clone(),
+            None => return Ok(()),
+        };
+        let window = self.window.window().ok_or(PlatformError::NotAvailable)?;
+        match get_session_type() {
+            SessionType::X11 => {
+                let mut hint = x11_sys::XClassHint {
+                    res_name: id.as_ptr() as *mut _,
+                    res_class: id.as_ptr() as *mut _,
+                };
+                unsafe {
+                    let display = window.display();
+                    let xdisplay = x11_sys::gdk_x11_display_get_xdisplay(display.to_glib_none().0);
+                    let xid = x11_sys::gdk_x11_window_get_xid(window.to_glib_none().0);
+                    x11_sys::XSetClassHint(xdisplay, xid, &mut hint as *mut _);
+                }
+                Ok(())
+            }
+            SessionType::Wayland => {
+                // Not part of public GDK API, but exported since GTK 3.22
+                type SetApplicationId =
+                    unsafe extern "C" fn(*mut gdk_sys::GdkWindow, *const c_char);
+                let name = CString::new("gdk_wayland_window_set_application_id").unwrap();
+                let symbol = unsafe { libc::dlsym(libc::RTLD_DEFAULT, name.as_ptr()) };
+                if symbol.is_null() {
+                    return Err(PlatformError::NotAvailable);
+                }
+                unsafe {
+                    let set_application_id: SetApplicationId = std::mem::transmute(symbol);
+                    set_application_id(window.to_glib_none().0, id.as_ptr());
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // Renders the flutter view into image surface. GtkGLArea reads back the
+    // framebuffer when drawing into non-window surface.
+    pub fn capture_image(&self) -> PlatformResult<ImageData> {
+        let view = self.view.borrow().clone();
+        if !view.is_realized() {
+            return Err(PlatformError::NotAvailable);
+        }
+        let scale_factor = view.scale_factor();
+        let width = view.allocated_width() * scale_factor;
+        let height = view.allocated_height() * scale_factor;
+        let surface = ImageSurface::create(Format::ARgb32, width, height)?;
+        surface.set_device_scale(scale_factor as f64, scale_factor as f64);
+        {
+            let cr = cairo::Context::new(&surface)?;
+            view.draw(&cr);
+        }
+        let bytes_per_row = surface.stride();
+        let mut data = Vec::new();
+        surface.with_data(|bgra| data.extend_from_slice(bgra))?;
+        premultiplied_bgra_to_rgba(&mut data);
+        Ok(ImageData {
+            width,
+            height,
+            bytes_per_row,
+            data,
+        })
+    }
+
+    pub fn lock_pointer(&self) -> PlatformResult<()> {
+        self.constrain_pointer(PointerConstraint::Lock, None)
+    }
+
+    pub fn confine_pointer(&self, rect: Option<Rect>) -> PlatformResult<()> {
+        self.constrain_pointer(PointerConstraint::Confine, rect)
+    }
+
+    pub fn release_pointer(&self) -> PlatformResult<()> {
+        self.pointer_lock.replace(None);
+        Ok(())
+    }
+
+    pub fn set_hide_pointer_while_typing(&self, hide: bool) -> PlatformResult<()> {
+        self.hide_pointer_while_typing.set(hide);
+        if !hide {
+            self.restore_typing_cursor();
+        }
+        Ok(())
+    }
+
+    // Rect is in content coordinates; Constraint region is always limited to
+    // flutter view.
+    fn constrain_pointer(
+        &self,
+        constraint: PointerConstraint,
+        rect: Option<Rect>,
+    ) -> PlatformResult<()> {
+        self.pointer_lock.replace(None);
+        // Pointer lock remembers the current cursor
+        self.restore_typing_cursor();
+        let window = self.window.window().ok_or(PlatformError::NotAvailable)?;
+        let view = self.view.borrow().clone();
+        let view_window = view.window().ok_or(PlatformError::NotAvailable)?;
+        let (x, y) = self.content_offset();
+        let content = Rect::xywh(
+            x as f64,
+            y as f64,
+            view.allocated_width() as f64,
+            view.allocated_height() as f64,
+        );
+        let region = match rect {
+            Some(rect) => rect
+                .translated(&Point::xy(x as f64, y as f64))
+                .intersect(&content)
+                .ok_or_else(|| PlatformError::OtherError {
+                    error: "Confinement rect is outside of window content".into(),
+                })?,
+            None => content,
+        };
+        let lock = PointerLock::new(
+            constraint,
+            &window,
+            &view_window,
+            region,
+            self.delegate.clone(),
+        )?;
+        self.pointer_lock.replace(Some(lock));
+        Ok(())
+    }
+
     pub fn set_collection_behavior(
         &self,
-        _behavior: WindowCollectionBehavior,
+        behavior: WindowCollectionBehavior,
     ) -> PlatformResult<()> {
-        Err(PlatformError::NotAvailable)
+        self.collection_behavior.replace(behavior);
+        self.update_window_hints();
+        Ok(())
+    }
+
+    pub fn set_urgent(&self, urgent: bool) -> PlatformResult<()> {
+        self.window.set_urgency_hint(urgent);
+        Ok(())
     }
 
     pub fn set_minimized(&self, minimized: bool) -> PlatformResult<()> {
@@ -740,11 +1440,149 @@ impl PlatformWindow {
 
         window.set_functions(func);
 
-        window.set_keep_above(style.always_on_top);
+        // Window paints its own background (see draw_background)
+        self.window
+            .set_app_paintable(style.transparent || style.background_color.is_some());
+        self.window.queue_draw();
+        if !self.pending_first_frame.get() {
+            self.window.set_opacity(self.style_opacity());
+        }
+
+        match &style.layer_shell {
+            Some(options) => self.set_layer_shell(options)?,
+            None => {
+                self.layer_shell_fallback.replace(None);
+                window.set_keep_above(style.always_on_top);
+            }
+        }
+
+        self.update_window_hints();
 
         Ok(())
     }
 
+    // Combines window type and taskbar flags from style with collection behavior
+    // (which is modelled after NSWindowCollectionBehavior)
+    fn update_window_hints(&self) {
+        let (window_type, mut skip_taskbar, mut skip_pager, sticky) =
+            match &*self.last_window_style.borrow() {
+                Some(style) => (
+                    style.window_type,
+                    style.skip_taskbar,
+                    style.skip_pager,
+                    style.sticky,
+                ),
+                None => (WindowType::Normal, false, false, false),
+            };
+        let behavior = self.collection_behavior.borrow().clone();
+
+        let mut hint = match window_type {
+            WindowType::Normal if self.window.is_modal() => WindowTypeHint::Dialog,
+            WindowType::Normal => WindowTypeHint::Normal,
+            WindowType::Dialog => WindowTypeHint::Dialog,
+            WindowType::Utility => WindowTypeHint::Utility,
+            WindowType::Toolbar => WindowTypeHint::Toolbar,
+            WindowType::Splash => WindowTypeHint::Splashscreen,
+            WindowType::Tooltip => WindowTypeHint::Tooltip,
+            WindowType::Notification => WindowTypeHint::Notification,
+            WindowType::Dock => WindowTypeHint::Dock,
+        };
+
+        // Transient windows (floating palettes, HUDs) and windows that are not
+        // part of window cycling shouldn't show up in taskbar or alt-tab
+        skip_taskbar |= behavior.transient || behavior.ignores_cycle;
+        // Transient and stationary windows are not affected by workspace switching
+        skip_pager |= behavior.transient || behavior.stationary;
+        let sticky = sticky || behavior.can_join_all_spaces || behavior.stationary;
+
+        if let Some(options) = &*self.layer_shell_fallback.borrow() {
+            hint = if options.auto_exclusive_zone || options.exclusive_zone > 0 {
+                WindowTypeHint::Dock
+            } else {
+                WindowTypeHint::Notification
+            };
+            skip_taskbar = true;
+            skip_pager = true;
+        }
+
+        if !is_layer_window(&self.window) {
+            self.window.set_type_hint(hint);
+        }
+        self.window.set_skip_taskbar_hint(skip_taskbar);
+        self.window.set_skip_pager_hint(skip_pager);
+        if sticky {
+            self.window.stick();
+        } else {
+            self.window.unstick();
+        }
+    }
+
+    fn set_layer_shell(&self, options: &LayerShellOptions) -> PlatformResult<()> {
+        if get_session_type() == SessionType::Wayland && layer_shell_available() {
+            if !is_layer_window(&self.window) {
+                // Unrealizing the window would destroy flutter view GL context
+                return Err(PlatformError::OtherError {
+                    error: "Layer shell must be enabled through WindowCreateOptions".into(),
+                });
+            }
+            configure_window(&self.window, options);
+        } else {
+            // Type hint and taskbar flags are set in update_window_hints
+            let above = matches!(
+                options.layer,
+                LayerShellLayer::Top | LayerShellLayer::Overlay
+            );
+            self.window.set_keep_above(above);
+            self.window.set_keep_below(!above);
+            self.layer_shell_fallback.replace(Some(options.clone()));
+            self.update_layer_shell_fallback_position();
+        }
+        Ok(())
+    }
+
+    fn update_layer_shell_fallback_position(&self) {
+        let options = match &*self.layer_shell_fallback.borrow() {
+            Some(options) => options.clone(),
+            None => return,
+        };
+        let monitor = self
+            .window
+            .window()
+            .and_then(|w| w.display().monitor_at_window(&w));
+        let area = match monitor {
+            Some(monitor) => monitor.geometry(),
+            None => return,
+        };
+        let (width, height) = self.window.size();
+        // Window is centered along axis where it is not anchored
+        let position =
+            |start: i32, size: i32, anchor: (bool, bool), margin: (i32, i32), window: i32| {
+                match anchor {
+                    (true, _) => start + margin.0,
+                    (false, true) => start + size - window - margin.1,
+                    (false, false) => start + (size - window) / 2,
+                }
+            };
+        let (anchor, margin) = (&options.anchor, &options.margin);
+        let x = position(
+            area.x,
+            area.width,
+            (anchor.left, anchor.right),
+            (margin.left, margin.right),
+            width,
+        );
+        let y = position(
+            area.y,
+            area.height,
+            (anchor.top, anchor.bottom),
+            (margin.top, margin.bottom),
+            height,
+        );
+        if self.window.position() != (x, y) {
+            self.window.move_(x, y);
+        }
+    }
+
     pub fn perform_window_drag(&self) -> PlatformResult<()> {
         if let Some(event) = self.last_event.borrow().get(&EventType::ButtonPress) {
             if let (Some(coords), Some(button)) = (event.root_coords(), event.button()) {
@@ -764,6 +1602,132 @@ impl PlatformWindow {
         Ok(())
     }
 
+    pub fn perform_window_resize(&self, edge: WindowEdge) -> PlatformResult<()> {
+        if let Some(event) = self.last_event.borrow().get(&EventType::ButtonPress) {
+            if let (Some(coords), Some(button)) = (event.root_coords(), event.button()) {
+                // release event will get eaten, we need to synthetize it otherwise flutter keeps waiting for it
+                let mut release = synthetize_button_up(event);
+                gtk::main_do_event(&mut release);
+
+                self.window.window().unwrap().begin_resize_drag(
+                    gdk_window_edge(edge),
+                    button as i32,
+                    coords.0 as i32,
+                    coords.1 as i32,
+                    event.time(),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_drag_regions(&self, regions: WindowDragRegions) -> PlatformResult<()> {
+        self.drag_regions.replace(regions);
+        Ok(())
+    }
+
+    pub fn set_ignore_mouse_events(
+        &self,
+        ignore: bool,
+        forward_motion: bool,
+    ) -> PlatformResult<()> {
+        self.ignore_mouse_events.set(ignore);
+        self.update_input_shape();
+
+        if let Some(source) = self.forward_motion_source.take() {
+            glib::source_remove(source);
+        }
+        if ignore && forward_motion {
+            let weak = self.weak_self.clone_value();
+            let source = glib::timeout_add_local(Duration::from_millis(1000 / 60), move || {
+                match weak.upgrade() {
+                    Some(window) => {
+                        window.forward_pointer_motion();
+                        Continue(true)
+                    }
+                    None => Continue(false),
+                }
+            });
+            self.forward_motion_source.replace(Some(source));
+        } else {
+            self.stop_forwarding_motion();
+        }
+        Ok(())
+    }
+
+    pub fn set_input_region(&self, region: Option<Vec<Rect>>) -> PlatformResult<()> {
+        self.input_region.replace(region);
+        self.update_input_shape();
+        Ok(())
+    }
+
+    // Input shape is in toplevel window coordinates; Empty region makes whole
+    // window transparent for input. This also sets wl_surface input region on
+    // Wayland.
+    fn update_input_shape(&self) {
+        let region = if self.ignore_mouse_events.get() {
+            Some(cairo::Region::create())
+        } else {
+            self.input_region.borrow().as_ref().map(|rects| {
+                let (offset_x, offset_y) = self.content_offset();
+                let region = cairo::Region::create();
+                for rect in rects {
+                    let x = rect.x.floor() as i32;
+                    let y = rect.y.floor() as i32;
+                    let rect = cairo::RectangleInt {
+                        x: x + offset_x,
+                        y: y + offset_y,
+                        width: rect.x2().ceil() as i32 - x,
+                        height: rect.y2().ceil() as i32 - y,
+                    };
+                    region.union_rectangle(&rect).ok_log();
+                }
+                region
+            })
+        };
+        self.window.input_shape_combine_region(region.as_ref());
+    }
+
+    // Windows ignoring mouse events don't get any pointer events; In order for
+    // hover effects to work, motion (and leave) events are synthetized from
+    // pointer position.
+    fn forward_pointer_motion(&self) {
+        let window = match self.window.window() {
+            Some(window) if self.window.is_visible() => window,
+            _ => return,
+        };
+        let device = match window.display().default_seat().and_then(|s| s.pointer()) {
+            Some(device) => device,
+            None => return,
+        };
+        let (_, x, y) = device.position();
+        let (_, origin_x, origin_y) = window.origin();
+        let inside = x >= origin_x
+            && y >= origin_y
+            && x < origin_x + window.width()
+            && y < origin_y + window.height();
+
+        let last = self.forwarded_motion.borrow().clone();
+        if inside {
+            let root = (x as f64, y as f64);
+            if last.as_ref().and_then(|e| e.root_coords()) != Some(root) {
+                let mut motion = synthetize_motion_event(&window, &device, root);
+                self.propagate_event(&mut motion);
+                self.forwarded_motion.replace(Some(motion));
+            }
+        } else if last.is_some() {
+            self.stop_forwarding_motion();
+        }
+    }
+
+    fn stop_forwarding_motion(&self) {
+        if let Some(last) = self.forwarded_motion.take() {
+            let mut leave = synthetize_leave_event_from_motion(&last);
+            self.propagate_event(&mut leave);
+        }
+    }
+
     pub fn begin_drag_session(&self, request: DragRequest) -> PlatformResult<()> {
         // relase event will get eaten
         if let Some(event) = self.last_event.borrow().get(&EventType::ButtonPress) {
@@ -794,6 +1758,13 @@ impl PlatformWindow {
         self.window_menu.borrow().hide_popup_menu(menu)
     }
 
+    pub fn show_alert<F>(&self, request: AlertRequest, on_done: F)
+    where
+        F: FnOnce(PlatformResult<AlertResponse>) + 'static,
+    {
+        show_alert(&self.window, request, on_done);
+    }
+
     pub fn show_system_menu(&self) -> PlatformResult<()> {
         Err(PlatformError::NotAvailable)
     }
@@ -803,6 +1774,12 @@ impl PlatformWindow {
     }
 }
 
+struct DragRegionPress {
+    event: Event,
+    button: u32,
+    can_drag: bool,
+}
+
 #[derive(Debug, Default, Clone)]
 struct GeometryRequest {
     pub frame_origin: Option<Point>,
@@ -821,3 +1798,16 @@ impl GeometryRequest {
         }
     }
 }
+
+fn gdk_window_edge(edge: WindowEdge) -> gdk::WindowEdge {
+    match edge {
+        WindowEdge::Top => gdk::WindowEdge::North,
+        WindowEdge::Bottom => gdk::WindowEdge::South,
+        WindowEdge::Left => gdk::WindowEdge::West,
+        WindowEdge::Right => gdk::WindowEdge::East,
+        WindowEdge::TopLeft => gdk::WindowEdge::NorthWest,
+        WindowEdge::TopRight => gdk::WindowEdge::NorthEast,
+        WindowEdge::BottomLeft => gdk::WindowEdge::SouthWest,
+        WindowEdge::BottomRight => gdk::WindowEdge::SouthEast,
+    }
+}
@@ -0,0 +1,157 @@
+// Minimal Xlib and GDK X11 backend bindings. Only valid when GDK is using the
+// X11 backend.
+
+#![allow(non_upper_case_globals, non_snake_case, clippy::upper_case_acronyms)]
+
+use std::os::raw::{c_char, c_int, c_uchar, c_uint, c_ulong, c_void};
+
+pub type Display = std::ffi::c_void;
+pub type Window = c_ulong;
+pub type Time = c_ulong;
+pub type Bool = c_int;
+
+pub const KeyPress: c_int = 2;
+pub const GenericEvent: c_int = 35;
+pub const GrabModeAsync: c_int = 1;
+
+pub const ShiftMask: c_uint = 1 << 0;
+pub const LockMask: c_uint = 1 << 1;
+pub const ControlMask: c_uint = 1 << 2;
+pub const Mod1Mask: c_uint = 1 << 3;
+pub const Mod2Mask: c_uint = 1 << 4;
+pub const Mod4Mask: c_uint = 1 << 6;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct XKeyEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub root: Window,
+    pub subwindow: Window,
+    pub time: Time,
+    pub x: c_int,
+    pub y: c_int,
+    pub x_root: c_int,
+    pub y_root: c_int,
+    pub state: c_uint,
+    pub keycode: c_uint,
+    pub same_screen: Bool,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct XClassHint {
+    pub res_name: *mut c_char,
+    pub res_class: *mut c_char,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct XGenericEventCookie {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub extension: c_int,
+    pub evtype: c_int,
+    pub cookie: c_uint,
+    pub data: *mut c_void,
+}
+
+// XInput2 (libXi); Functions are loaded at runtime
+
+pub const XIAllMasterDevices: c_int = 1;
+pub const XI_RawKeyPress: c_int = 13;
+pub const XI_RawKeyRelease: c_int = 14;
+pub const XI_RawButtonPress: c_int = 15;
+pub const XI_RawButtonRelease: c_int = 16;
+pub const XI_RawMotion: c_int = 17;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct XIEventMask {
+    pub deviceid: c_int,
+    pub mask_len: c_int,
+    pub mask: *mut c_uchar,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct XIValuatorState {
+    pub mask_len: c_int,
+    pub mask: *mut c_uchar,
+    pub values: *mut f64,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct XIRawEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub extension: c_int,
+    pub evtype: c_int,
+    pub time: Time,
+    pub deviceid: c_int,
+    pub sourceid: c_int,
+    pub detail: c_int,
+    pub flags: c_int,
+    pub valuators: XIValuatorState,
+    pub raw_values: *mut f64,
+}
+
+pub type XISelectEvents = unsafe extern "C" fn(
+    display: *mut Display,
+    window: Window,
+    masks: *mut XIEventMask,
+    num_masks: c_int,
+) -> c_int;
+
+#[link(name = "X11")]
+extern "C" {
+    pub fn XSetClassHint(
+        display: *mut Display,
+        window: Window,
+        class_hints: *mut XClassHint,
+    ) -> c_int;
+    pub fn XDefaultRootWindow(display: *mut Display) -> Window;
+    pub fn XQueryExtension(
+        display: *mut Display,
+        name: *const c_char,
+        major_opcode: *mut c_int,
+        first_event: *mut c_int,
+        first_error: *mut c_int,
+    ) -> Bool;
+    pub fn XGrabKey(
+        display: *mut Display,
+        keycode: c_int,
+        modifiers: c_uint,
+        grab_window: Window,
+        owner_events: Bool,
+        pointer_mode: c_int,
+        keyboard_mode: c_int,
+    ) -> c_int;
+    pub fn XUngrabKey(
+        display: *mut Display,
+        keycode: c_int,
+        modifiers: c_uint,
+        grab_window: Window,
+    ) -> c_int;
+}
+
+// Part of libgdk-3
+extern "C" {
+    pub fn gdk_x11_display_get_xdisplay(display: *mut gdk_sys::GdkDisplay) -> *mut Display;
+    pub fn gdk_x11_display_error_trap_push(display: *mut gdk_sys::GdkDisplay);
+    pub fn gdk_x11_display_error_trap_pop(display: *mut gdk_sys::GdkDisplay) -> c_int;
+    pub fn gdk_x11_window_get_xid(window: *mut gdk_sys::GdkWindow) -> Window;
+    pub fn gdk_x11_window_set_utf8_property(
+        window: *mut gdk_sys::GdkWindow,
+        name: *const c_char,
+        value: *const c_char,
+    );
+}
@@ -0,0 +1,96 @@
+use std::cell::RefCell;
+
+use block::ConcreteBlock;
+use cocoa::{
+    base::{id, YES},
+    foundation::{NSInteger, NSUInteger},
+};
+use objc::{
+    class, msg_send,
+    rc::{autoreleasepool, StrongPtr},
+    sel, sel_impl,
+};
+
+use crate::shell::api_model::{AlertRequest, AlertResponse, AlertStyle};
+
+use super::{error::PlatformResult, utils::to_nsstring};
+
+#[allow(non_upper_case_globals)]
+const NSAlertFirstButtonReturn: NSInteger = 1000;
+
+#[allow(non_upper_case_globals)]
+const NSAlertStyleWarning: NSUInteger = 0;
+#[allow(non_upper_case_globals)]
+const NSAlertStyleInformational: NSUInteger = 1;
+#[allow(non_upper_case_globals)]
+const NSAlertStyleCritical: NSUInteger = 2;
+
+// Shows NSAlert as a sheet attached to parent window. NSAlert returns
+// NSAlertFirstButtonReturn + index for buttons; Anything else (i.e. sheet
+// dismissed by Escape without cancel button) maps to cancel button.
+pub(super) fn show_alert<F>(parent: id, request: AlertRequest, on_done: F)
+where
+    F: FnOnce(PlatformResult<AlertResponse>) + 'static,
+{
+    autoreleasepool(|| unsafe {
+        let alert: id = msg_send![class!(NSAlert), new];
+        let alert = StrongPtr::new(alert);
+
+        let style = match request.style {
+            AlertStyle::Info | AlertStyle::Question => NSAlertStyleInformational,
+            AlertStyle::Warning => NSAlertStyleWarning,
+            AlertStyle::Error => NSAlertStyleCritical,
+        };
+        let () = msg_send![*alert, setAlertStyle: style];
+        let () = msg_send![*alert, setMessageText: *to_nsstring(&request.message)];
+        if let Some(text) = &request.informative_text {
+            let () = msg_send![*alert, setInformativeText: *to_nsstring(text)];
+        }
+
+        for (index, title) in request.buttons.iter().enumerate() {
+            let button: id = msg_send![*alert, addButtonWithTitle: *to_nsstring(title)];
+            // First button is default by default
+            if let Some(default_button) = request.default_button {
+                let key = if index == default_button { "\r" } else { "" };
+                let () = msg_send![button, setKeyEquivalent: *to_nsstring(key)];
+            }
+            if request.cancel_button == Some(index) && request.default_button != Some(index) {
+                let () = msg_send![button, setKeyEquivalent: *to_nsstring("\x1b")];
+            }
+        }
+
+        if let Some(text) = &request.suppression_text {
+            let () = msg_send![*alert, setShowsSuppressionButton: YES];
+            let button: id = msg_send![*alert, suppressionButton];
+            let () = msg_send![button, setTitle: *to_nsstring(text)];
+        }
+
+        let alert_ref = alert.clone();
+        let button_count = request.buttons.len();
+        let cancel_button = request.cancel_button;
+        let shows_suppression = request.suppression_text.is_some();
+        let on_done = RefCell::new(Some(on_done));
+        let block = ConcreteBlock::new(move |response: NSInteger| {
+            let index = response - NSAlertFirstButtonReturn;
+            let button = if index >= 0 && (index as usize) < button_count {
+                Some(index as usize)
+            } else {
+                cancel_button
+            };
+            let suppressed = if shows_suppression {
+                let suppression_button: id = msg_send![*alert_ref, suppressionButton];
+                let state: NSInteger = msg_send![suppression_button, state];
+                state != 0
+            } else {
+                false
+            };
+            if let Some(on_done) = on_done.borrow_mut().take() {
+                on_done(Ok(AlertResponse { button, suppressed }));
+            }
+        });
+        let block = block.copy();
+        let () = msg_send![*alert,
+            beginSheetModalForWindow: parent
+            completionHandler: &*block];
+    });
+}
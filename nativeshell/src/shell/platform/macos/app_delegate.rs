@@ -1,6 +1,9 @@
-use super::utils::from_nsstring;
-use crate::shell::{Context, ContextRef};
-use block::{Block, RcBlock};
+use super::utils::{from_nsstring, to_nsstring};
+use crate::{
+    shell::{api_model::SecondaryInstanceLaunched, Context, ContextRef},
+    util::OkLog,
+};
+use block::{Block, ConcreteBlock, RcBlock};
 use cocoa::{
     appkit::{NSApplication, NSApplicationTerminateReply},
     base::{id, nil, BOOL, NO, YES},
@@ -19,11 +22,16 @@ use objc::{
 use once_cell::sync::Lazy;
 use std::{
     cell::{Cell, RefCell},
+    fs::File,
     mem::ManuallyDrop,
+    os::unix::io::AsRawFd,
+    path::Path,
     rc::{Rc, Weak},
 };
 use url::Url;
 
+pub use crate::shell::app_delegate::SessionEndReason;
+
 pub struct AppTermination {}
 
 impl AppTermination {
@@ -42,6 +50,9 @@ pub enum ApplicationTerminateReply {
     Later,
 }
 
+// Contains all methods of the ApplicationDelegate shared by other platforms
+// (shell/app_delegate.rs). `application_session_will_end` is not called on
+// macOS; Logout is reported through `application_should_terminate`.
 pub trait ApplicationDelegate {
     // done
     fn application_will_finish_launching(&mut self) {}
@@ -85,6 +96,17 @@ pub trait ApplicationDelegate {
 
     fn application_open_urls(&mut self, _urls: &[Url]) {}
 
+    fn application_session_will_end(&mut self, _reason: SessionEndReason) {}
+
+    // Another instance of the application was launched while in single
+    // instance mode. Arguments don't include the executable.
+    fn application_secondary_instance_launched(
+        &mut self,
+        _arguments: &[String],
+        _working_directory: Option<&Path>,
+    ) {
+    }
+
     fn application_continue_user_activity(
         &mut self,
         _user_activity: id, /* NSUserActivity */
@@ -111,11 +133,103 @@ impl DelegateState {
         }
         self.execute_after.borrow_mut().replace(Box::new(f));
     }
+
+    fn secondary_instance_launched(&self, event: SecondaryInstanceLaunched) {
+        let working_directory = event.working_directory.as_ref().map(Path::new);
+        let delegate = self.delegate.borrow().clone();
+        if let Some(delegate) = delegate {
+            delegate
+                .borrow_mut()
+                .application_secondary_instance_launched(&event.arguments, working_directory);
+        }
+        if let Some(context) = self.context.get() {
+            context
+                .application_manager
+                .borrow()
+                .borrow()
+                .secondary_instance_launched(&event.arguments, working_directory);
+        }
+    }
+}
+
+// Launch services only prevent multiple instances of bundled application
+// started from Finder; Primary instance is the one holding lock file and other
+// instances forward their command line through distributed notification.
+struct SingleInstance {
+    _lock_file: File,
+    observer: StrongPtr,
+}
+
+impl SingleInstance {
+    // Returns None if this is the primary instance and single instance is not
+    // available; Exits the process if this is a secondary instance.
+    fn new(app_namespace: &str, state: Weak<DelegateState>) -> Option<Self> {
+        let lock_path = std::env::temp_dir().join(format!("{}.lock", app_namespace));
+        let lock_file = File::create(lock_path).ok_log()?;
+        let notification_name = format!("{}.secondaryInstanceLaunched", app_namespace);
+        let locked =
+            unsafe { libc::flock(lock_file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0;
+        if !locked {
+            Self::notify_primary_instance(&notification_name);
+            std::process::exit(0);
+        }
+        let observer = autoreleasepool(|| unsafe {
+            let center: id = msg_send![class!(NSDistributedNotificationCenter), defaultCenter];
+            let queue: id = msg_send![class!(NSOperationQueue), mainQueue];
+            let block = ConcreteBlock::new(move |notification: id| {
+                let object: id = msg_send![notification, object];
+                if object == nil {
+                    return;
+                }
+                let event =
+                    serde_json::from_str::<SecondaryInstanceLaunched>(&from_nsstring(object));
+                if let (Some(state), Ok(event)) = (state.upgrade(), event) {
+                    state.secondary_instance_launched(event);
+                }
+            });
+            let block = block.copy();
+            let name = to_nsstring(&notification_name);
+            let observer: id = msg_send![center,
+                addObserverForName:*name object:nil queue:queue usingBlock:&*block];
+            StrongPtr::retain(observer)
+        });
+        Some(Self {
+            _lock_file: lock_file,
+            observer,
+        })
+    }
+
+    fn notify_primary_instance(notification_name: &str) {
+        let event = SecondaryInstanceLaunched {
+            arguments: std::env::args().skip(1).collect(),
+            working_directory: std::env::current_dir()
+                .ok()
+                .map(|d| d.to_string_lossy().into()),
+        };
+        let event = serde_json::to_string(&event).unwrap();
+        autoreleasepool(|| unsafe {
+            let center: id = msg_send![class!(NSDistributedNotificationCenter), defaultCenter];
+            let name = to_nsstring(notification_name);
+            let object = to_nsstring(&event);
+            let () = msg_send![center,
+                postNotificationName:*name object:*object userInfo:nil deliverImmediately:YES];
+        });
+    }
+}
+
+impl Drop for SingleInstance {
+    fn drop(&mut self) {
+        unsafe {
+            let center: id = msg_send![class!(NSDistributedNotificationCenter), defaultCenter];
+            let () = msg_send![center, removeObserver: *self.observer];
+        }
+    }
 }
 
 pub struct ApplicationDelegateManager {
     state: Rc<DelegateState>,
     _object: StrongPtr,
+    _single_instance: Option<SingleInstance>,
 }
 
 impl ApplicationDelegateManager {
@@ -135,9 +249,15 @@ impl ApplicationDelegateManager {
             let () = msg_send![app, setDelegate: object];
             StrongPtr::new(object)
         });
+        let single_instance = if context.options.single_instance {
+            SingleInstance::new(&context.options.app_namespace, Rc::downgrade(&state))
+        } else {
+            None
+        };
         Self {
             state,
             _object: object,
+            _single_instance: single_instance,
         }
     }
 
@@ -152,6 +272,15 @@ impl ApplicationDelegateManager {
         self.state.delegate.borrow_mut().replace(delegate);
     }
 
+    // Asks delegate whether application should terminate (same as choosing
+    // Quit from application menu).
+    pub fn request_terminate(&self) {
+        unsafe {
+            let app = NSApplication::sharedApplication(nil);
+            let () = msg_send![app, terminate: nil];
+        }
+    }
+
     // Executes the callback right after current handler returns, in same
     // run loop turn as current handler, but without the handler borrowed.
     // This is useful for situations where the callback might run nested
@@ -0,0 +1,104 @@
+use std::{collections::HashMap, rc::Rc};
+
+use cocoa::{
+    base::{id, BOOL},
+    foundation::NSInteger,
+};
+use objc::{class, msg_send, rc::autoreleasepool, sel, sel_impl};
+
+use crate::shell::{
+    api_model::{ClipboardSelection, DragData},
+    Context, ContextRef,
+};
+
+use super::{
+    drag_data::{
+        DragDataAdapter, FallThroughDragDataAdapter, FilesDragDataAdapter, PasteboardItems,
+        StringDragDataAdapter, UrlsDragDataAdapter,
+    },
+    error::{PlatformError, PlatformResult},
+    utils::array_with_objects,
+};
+
+pub struct PlatformClipboard {
+    context: Context,
+    data_adapters: Vec<Box<dyn DragDataAdapter>>,
+}
+
+impl PlatformClipboard {
+    pub fn new(context: Context) -> Self {
+        let mut data_adapters: Vec<Box<dyn DragDataAdapter>> = vec![
+            Box::new(StringDragDataAdapter::text()),
+            Box::new(StringDragDataAdapter::html()),
+            Box::new(FilesDragDataAdapter::new()),
+            Box::new(UrlsDragDataAdapter::new()),
+        ];
+        if let Some(context) = context.get() {
+            data_adapters.push(Box::new(FallThroughDragDataAdapter::new(&context.options)));
+        }
+        Self {
+            context,
+            data_adapters,
+        }
+    }
+
+    fn data_adapters<'a>(&'a self, context: &'a ContextRef) -> Vec<&'a dyn DragDataAdapter> {
+        context
+            .options
+            .custom_drag_data_adapters
+            .iter()
+            .chain(self.data_adapters.iter())
+            .map(|a| a.as_ref())
+            .collect()
+    }
+
+    fn pasteboard(selection: ClipboardSelection) -> PlatformResult<id> {
+        match selection {
+            ClipboardSelection::Clipboard => {
+                Ok(unsafe { msg_send![class!(NSPasteboard), generalPasteboard] })
+            }
+            ClipboardSelection::Primary => Err(PlatformError::NotAvailable),
+        }
+    }
+
+    pub fn read_data<F>(self: &Rc<Self>, selection: ClipboardSelection, on_done: F)
+    where
+        F: FnOnce(PlatformResult<DragData>) + 'static,
+    {
+        let res = autoreleasepool(|| {
+            let pasteboard = Self::pasteboard(selection)?;
+            let context = self.context.get().ok_or(PlatformError::UnknownError)?;
+            let mut properties = HashMap::new();
+            for adapter in self.data_adapters(&context) {
+                adapter.retrieve_drag_data(pasteboard, &mut properties);
+            }
+            Ok(DragData { properties })
+        });
+        on_done(res);
+    }
+
+    pub fn write_data(&self, selection: ClipboardSelection, data: DragData) -> PlatformResult<()> {
+        let pasteboard = Self::pasteboard(selection)?;
+        let context = self.context.get().ok_or(PlatformError::UnknownError)?;
+        let mut properties = data.properties;
+        let mut pasteboard_items = PasteboardItems::new();
+        for adapter in self.data_adapters(&context) {
+            pasteboard_items.reset_index();
+            adapter.prepare_drag_data(&mut properties, &mut pasteboard_items);
+        }
+        autoreleasepool(|| unsafe {
+            let _: NSInteger = msg_send![pasteboard, clearContents];
+            let items = array_with_objects(&pasteboard_items.get_items());
+            let _: BOOL = msg_send![pasteboard, writeObjects: items];
+        });
+        Ok(())
+    }
+
+    pub fn clear(&self, selection: ClipboardSelection) -> PlatformResult<()> {
+        let pasteboard = Self::pasteboard(selection)?;
+        unsafe {
+            let _: NSInteger = msg_send![pasteboard, clearContents];
+        }
+        Ok(())
+    }
+}
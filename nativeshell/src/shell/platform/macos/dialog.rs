@@ -0,0 +1,136 @@
+use std::cell::RefCell;
+
+use block::ConcreteBlock;
+use cocoa::{
+    base::{id, nil, BOOL, NO, YES},
+    foundation::{NSArray, NSInteger},
+};
+use objc::{
+    class, msg_send,
+    rc::{autoreleasepool, StrongPtr},
+    sel, sel_impl,
+};
+
+use crate::{
+    shell::api_model::{FileDialogRequest, FileDialogType},
+    Context,
+};
+
+use super::{
+    error::PlatformResult,
+    utils::{array_with_objects, from_nsstring, to_nsstring},
+    window::PlatformWindowType,
+};
+
+#[allow(non_upper_case_globals)]
+const NSModalResponseOK: NSInteger = 1;
+
+pub struct PlatformDialogManager {}
+
+impl PlatformDialogManager {
+    pub fn new(_context: Context) -> Self {
+        Self {}
+    }
+
+    unsafe fn path_for_url(url: id) -> String {
+        from_nsstring(msg_send![url, path])
+    }
+
+    pub fn show_file_dialog<F>(
+        &self,
+        dialog_type: FileDialogType,
+        request: FileDialogRequest,
+        parent: Option<PlatformWindowType>,
+        on_done: F,
+    ) where
+        F: FnOnce(PlatformResult<Option<Vec<String>>>) + 'static,
+    {
+        autoreleasepool(|| unsafe {
+            let panel: id = match dialog_type {
+                FileDialogType::Save => msg_send![class!(NSSavePanel), savePanel],
+                FileDialogType::Open | FileDialogType::Folder => {
+                    msg_send![class!(NSOpenPanel), openPanel]
+                }
+            };
+            let panel = StrongPtr::retain(panel);
+
+            if let Some(title) = &request.title {
+                // Title is not visible for sheets
+                let () = msg_send![*panel, setTitle: *to_nsstring(title)];
+                let () = msg_send![*panel, setMessage: *to_nsstring(title)];
+            }
+            if let Some(directory) = &request.default_directory {
+                let url: id = msg_send![class!(NSURL), fileURLWithPath: *to_nsstring(directory)];
+                let () = msg_send![*panel, setDirectoryURL: url];
+            }
+            match dialog_type {
+                FileDialogType::Save => {
+                    if let Some(name) = &request.default_name {
+                        let () = msg_send![*panel, setNameFieldStringValue: *to_nsstring(name)];
+                    }
+                }
+                FileDialogType::Open | FileDialogType::Folder => {
+                    let folder = dialog_type == FileDialogType::Folder;
+                    let () = msg_send![*panel, setCanChooseFiles: if folder { NO } else { YES }];
+                    let () =
+                        msg_send![*panel, setCanChooseDirectories: if folder { YES } else { NO }];
+                    let () = msg_send![*panel, setCanCreateDirectories: YES];
+                    let multi_select: BOOL = if request.multi_select { YES } else { NO };
+                    let () = msg_send![*panel, setAllowsMultipleSelection: multi_select];
+                }
+            }
+
+            // NSSavePanel has no filter selector; All extensions are allowed
+            // at once.
+            let extensions: Vec<&String> = request
+                .filters
+                .iter()
+                .flat_map(|f| f.extensions.iter())
+                .collect();
+            if dialog_type != FileDialogType::Folder
+                && !extensions.is_empty()
+                && !extensions.iter().any(|e| e.as_str() == "*")
+            {
+                let extensions: Vec<StrongPtr> =
+                    extensions.iter().map(|e| to_nsstring(e)).collect();
+                let () = msg_send![*panel, setAllowedFileTypes: array_with_objects(&extensions)];
+            }
+
+            let panel_ref = panel.clone();
+            let on_done = RefCell::new(Some(on_done));
+            let block = ConcreteBlock::new(move |response: NSInteger| {
+                let res = if response == NSModalResponseOK {
+                    let mut paths = Vec::new();
+                    if dialog_type == FileDialogType::Save {
+                        let url: id = msg_send![*panel_ref, URL];
+                        if url != nil {
+                            paths.push(Self::path_for_url(url));
+                        }
+                    } else {
+                        let urls: id = msg_send![*panel_ref, URLs];
+                        for i in 0..NSArray::count(urls) {
+                            paths.push(Self::path_for_url(NSArray::objectAtIndex(urls, i)));
+                        }
+                    }
+                    Some(paths)
+                } else {
+                    None
+                };
+                if let Some(on_done) = on_done.borrow_mut().take() {
+                    on_done(Ok(res));
+                }
+            });
+            let block = block.copy();
+            match parent {
+                Some(window) => {
+                    let () = msg_send![*panel,
+                        beginSheetModalForWindow: *window
+                        completionHandler: &*block];
+                }
+                None => {
+                    let () = msg_send![*panel, beginWithCompletionHandler: &*block];
+                }
+            }
+        });
+    }
+}
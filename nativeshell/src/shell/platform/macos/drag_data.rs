@@ -207,6 +207,63 @@ impl DragDataAdapter for UrlsDragDataAdapter {
     }
 }
 
+// Single string value stored in first pasteboard item (text, html)
+pub(super) struct StringDragDataAdapter {
+    key: &'static str,
+    pasteboard_type: &'static str,
+}
+
+impl StringDragDataAdapter {
+    pub fn text() -> Self {
+        Self {
+            key: drag_data::key::TEXT,
+            pasteboard_type: "public.utf8-plain-text", // NSPasteboardTypeString
+        }
+    }
+
+    pub fn html() -> Self {
+        Self {
+            key: drag_data::key::HTML,
+            pasteboard_type: "public.html", // NSPasteboardTypeHTML
+        }
+    }
+}
+
+impl DragDataAdapter for StringDragDataAdapter {
+    fn retrieve_drag_data(&self, pasteboard: id, data_out: &mut HashMap<String, Value>) {
+        unsafe {
+            let string: id =
+                msg_send![pasteboard, stringForType: *to_nsstring(self.pasteboard_type)];
+            if string != nil {
+                data_out.insert(self.key.into(), Value::String(from_nsstring(string)));
+            }
+        }
+    }
+
+    fn prepare_drag_data(
+        &self,
+        data_in: &mut HashMap<String, Value>,
+        pasteboard_items: &mut PasteboardItems,
+    ) {
+        match data_in.remove(self.key) {
+            Some(Value::String(string)) => {
+                let item = pasteboard_items.next_item();
+                unsafe {
+                    let () = msg_send![*item,
+                        setString:*to_nsstring(&string)
+                        forType:*to_nsstring(self.pasteboard_type)];
+                }
+            }
+            Some(value) => panic!("Invalid value: {:?}, expected string", value),
+            None => {}
+        }
+    }
+
+    fn register_types(&self, types: &mut Vec<StrongPtr>) {
+        types.push(to_nsstring(self.pasteboard_type));
+    }
+}
+
 pub(super) struct FallThroughDragDataAdapter {
     format: StrongPtr,
 }
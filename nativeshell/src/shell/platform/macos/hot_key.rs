@@ -90,13 +90,16 @@ impl PlatformHotKeyManager {
         }
     }
 
-    pub fn create_hot_key(
+    pub fn create_hot_key<F>(
         &self,
         accelerator: Accelerator,
         virtual_key: i64,
         handle: HotKeyHandle,
         engine: EngineHandle,
-    ) -> PlatformResult<()> {
+        on_done: F,
+    ) where
+        F: FnOnce(PlatformResult<()>) + 'static,
+    {
         let id = self.next_id.get();
         self.next_id.replace(id + 1);
 
@@ -140,7 +143,7 @@ impl PlatformHotKeyManager {
 
         self.hot_keys.borrow_mut().insert(id, key);
 
-        Ok(())
+        on_done(Ok(()));
     }
 
     pub fn destroy_hot_key(&self, handle: HotKeyHandle) -> PlatformResult<()> {
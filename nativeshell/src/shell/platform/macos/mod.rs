@@ -1,6 +1,9 @@
+mod alert;
 pub mod app_delegate;
 pub mod binary_messenger;
 pub mod bundle;
+pub mod clipboard;
+pub mod dialog;
 mod drag_context;
 pub mod drag_data;
 pub mod engine;
@@ -10,6 +13,8 @@ pub mod hot_key_sys;
 pub mod init;
 pub mod keyboard_map;
 pub mod menu;
+pub mod notification;
+pub mod pointer_manager;
 pub mod run_loop;
 pub mod screen_manager;
 pub mod status_item;
@@ -0,0 +1,178 @@
+use std::{cell::RefCell, rc::Weak};
+
+use block::ConcreteBlock;
+use cocoa::{
+    base::id,
+    foundation::{NSInteger, NSPoint, NSUInteger},
+};
+use objc::{
+    class, msg_send,
+    rc::{autoreleasepool, StrongPtr},
+    sel, sel_impl,
+};
+
+use crate::shell::{
+    api_model::{
+        GlobalPointerEvent, GlobalPointerEventType, PointerModifiers, PointerState,
+        POINTER_BUTTON_BACK, POINTER_BUTTON_FORWARD, POINTER_BUTTON_MIDDLE, POINTER_BUTTON_PRIMARY,
+        POINTER_BUTTON_SECONDARY,
+    },
+    pointer_manager::PointerManagerDelegate,
+    Point,
+};
+
+use super::{error::PlatformResult, utils::global_screen_frame};
+
+// NSEventModifierFlags
+const SHIFT_KEY_MASK: NSUInteger = 1 << 17;
+const CONTROL_KEY_MASK: NSUInteger = 1 << 18;
+const OPTION_KEY_MASK: NSUInteger = 1 << 19;
+const COMMAND_KEY_MASK: NSUInteger = 1 << 20;
+
+// NSEventType
+const LEFT_MOUSE_DOWN: NSUInteger = 1;
+const LEFT_MOUSE_UP: NSUInteger = 2;
+const RIGHT_MOUSE_DOWN: NSUInteger = 3;
+const RIGHT_MOUSE_UP: NSUInteger = 4;
+const MOUSE_MOVED: NSUInteger = 5;
+const LEFT_MOUSE_DRAGGED: NSUInteger = 6;
+const RIGHT_MOUSE_DRAGGED: NSUInteger = 7;
+const OTHER_MOUSE_DOWN: NSUInteger = 25;
+const OTHER_MOUSE_UP: NSUInteger = 26;
+const OTHER_MOUSE_DRAGGED: NSUInteger = 27;
+const FLAGS_CHANGED: NSUInteger = 12;
+
+fn event_mask() -> u64 {
+    [
+        LEFT_MOUSE_DOWN,
+        LEFT_MOUSE_UP,
+        RIGHT_MOUSE_DOWN,
+        RIGHT_MOUSE_UP,
+        MOUSE_MOVED,
+        LEFT_MOUSE_DRAGGED,
+        RIGHT_MOUSE_DRAGGED,
+        OTHER_MOUSE_DOWN,
+        OTHER_MOUSE_UP,
+        OTHER_MOUSE_DRAGGED,
+        FLAGS_CHANGED,
+    ]
+    .iter()
+    .fold(0, |mask, t| mask | (1 << t))
+}
+
+pub struct PlatformPointerManager {
+    delegate: Weak<RefCell<dyn PointerManagerDelegate>>,
+    monitors: RefCell<Vec<StrongPtr>>,
+}
+
+impl PlatformPointerManager {
+    pub fn new(delegate: Weak<RefCell<dyn PointerManagerDelegate>>) -> Self {
+        Self {
+            delegate,
+            monitors: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn get_pointer_state(&self) -> PlatformResult<PointerState> {
+        Ok(Self::current_state())
+    }
+
+    fn current_state() -> PointerState {
+        unsafe {
+            let location: NSPoint = msg_send![class!(NSEvent), mouseLocation];
+            let flags: NSUInteger = msg_send![class!(NSEvent), modifierFlags];
+            // Bits match POINTER_BUTTON_* values
+            let buttons: NSUInteger = msg_send![class!(NSEvent), pressedMouseButtons];
+            PointerState {
+                position: Point::xy(location.x, global_screen_frame().y2() - location.y),
+                screen_id: None,
+                buttons: buttons as i64,
+                modifiers: PointerModifiers {
+                    shift: flags & SHIFT_KEY_MASK != 0,
+                    control: flags & CONTROL_KEY_MASK != 0,
+                    alt: flags & OPTION_KEY_MASK != 0,
+                    meta: flags & COMMAND_KEY_MASK != 0,
+                },
+            }
+        }
+    }
+
+    fn button_for_event(event: id) -> i64 {
+        let number: NSInteger = unsafe { msg_send![event, buttonNumber] };
+        match number {
+            0 => POINTER_BUTTON_PRIMARY,
+            1 => POINTER_BUTTON_SECONDARY,
+            2 => POINTER_BUTTON_MIDDLE,
+            3 => POINTER_BUTTON_BACK,
+            4 => POINTER_BUTTON_FORWARD,
+            _ => 0,
+        }
+    }
+
+    fn on_event(delegate: &Weak<RefCell<dyn PointerManagerDelegate>>, event: id) {
+        let event_type: NSUInteger = unsafe { msg_send![event, type] };
+        let (event_type, button) = match event_type {
+            LEFT_MOUSE_DOWN | RIGHT_MOUSE_DOWN | OTHER_MOUSE_DOWN => (
+                GlobalPointerEventType::ButtonDown,
+                Self::button_for_event(event),
+            ),
+            LEFT_MOUSE_UP | RIGHT_MOUSE_UP | OTHER_MOUSE_UP => (
+                GlobalPointerEventType::ButtonUp,
+                Self::button_for_event(event),
+            ),
+            FLAGS_CHANGED => (GlobalPointerEventType::ModifiersChanged, 0),
+            _ => (GlobalPointerEventType::Move, 0),
+        };
+        let state = Self::current_state();
+        if let Some(delegate) = delegate.upgrade() {
+            delegate.borrow().on_pointer_event(GlobalPointerEvent {
+                event_type,
+                position: state.position,
+                screen_id: None,
+                button,
+                buttons: state.buttons,
+                modifiers: state.modifiers,
+            });
+        }
+    }
+
+    // Global monitor only receives events sent to other applications, local
+    // monitor is needed for events within our windows.
+    pub fn start_monitoring(&self) -> PlatformResult<()> {
+        if !self.monitors.borrow().is_empty() {
+            return Ok(());
+        }
+        autoreleasepool(|| unsafe {
+            let delegate = self.delegate.clone();
+            let global = ConcreteBlock::new(move |event: id| {
+                Self::on_event(&delegate, event);
+            });
+            let global = global.copy();
+            let monitor: id = msg_send![class!(NSEvent),
+                addGlobalMonitorForEventsMatchingMask: event_mask() handler: &*global];
+
+            let delegate = self.delegate.clone();
+            let local = ConcreteBlock::new(move |event: id| -> id {
+                Self::on_event(&delegate, event);
+                event
+            });
+            let local = local.copy();
+            let local_monitor: id = msg_send![class!(NSEvent),
+                addLocalMonitorForEventsMatchingMask: event_mask() handler: &*local];
+
+            let mut monitors = self.monitors.borrow_mut();
+            monitors.push(StrongPtr::retain(monitor));
+            monitors.push(StrongPtr::retain(local_monitor));
+        });
+        Ok(())
+    }
+
+    pub fn stop_monitoring(&self) {
+        let monitors: Vec<_> = self.monitors.borrow_mut().drain(..).collect();
+        for monitor in monitors {
+            unsafe {
+                let () = msg_send![class!(NSEvent), removeMonitor: *monitor];
+            }
+        }
+    }
+}
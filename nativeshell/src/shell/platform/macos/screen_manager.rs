@@ -3,16 +3,17 @@ use std::{cell::RefCell, rc::Weak};
 use block::ConcreteBlock;
 use cocoa::{
     appkit::NSScreen,
-    base::{id, nil},
+    base::{id, nil, BOOL, YES},
     foundation::{NSArray, NSDictionary},
 };
+use core_graphics::display::CGDisplay;
 use objc::{class, msg_send, rc::autoreleasepool, sel, sel_impl};
 
-use crate::shell::{api_model::Screen, screen_manager::ScreenManagerDelegate, Point, Rect};
+use crate::shell::{api_model::Screen, screen_manager::ScreenManagerDelegate, Point, Rect, Size};
 
 use super::{
     error::PlatformResult,
-    utils::{global_screen_frame, to_nsstring},
+    utils::{from_nsstring, global_screen_frame, to_nsstring},
 };
 
 pub struct PlatformScreenManager {}
@@ -57,14 +58,44 @@ impl PlatformScreenManager {
             let screens = NSScreen::screens(nil);
             for i in 0..NSArray::count(screens) {
                 let screen = NSArray::objectAtIndex(screens, i);
+                let id = Self::get_screen_id(screen);
+                let display = CGDisplay::new(id as u32);
+                let physical_size = display.screen_size();
+                // localizedName is only available on macOS 10.15 and newer
+                let responds: BOOL = msg_send![screen, respondsToSelector: sel!(localizedName)];
+                let name = if responds == YES {
+                    let name: id = msg_send![screen, localizedName];
+                    Some(from_nsstring(name))
+                } else {
+                    None
+                };
                 let s = Screen {
-                    id: Self::get_screen_id(screen),
+                    id,
                     frame: Self::flip_rect(&NSScreen::frame(screen).into(), &global_frame),
                     work_area: Self::flip_rect(
                         &NSScreen::visibleFrame(screen).into(),
                         &global_frame,
                     ),
                     scaling_factor: NSScreen::backingScaleFactor(screen),
+                    name,
+                    model: Some(format!(
+                        "{:04x}:{:04x}",
+                        display.vendor_number(),
+                        display.model_number()
+                    )),
+                    physical_size: if physical_size.width > 0.0 && physical_size.height > 0.0 {
+                        Some(Size::wh(physical_size.width, physical_size.height))
+                    } else {
+                        None
+                    },
+                    // Built-in displays report 0 refresh rate
+                    refresh_rate: display
+                        .display_mode()
+                        .map(|mode| mode.refresh_rate())
+                        .filter(|rate| *rate > 0.0),
+                    rotation: display.rotation() as i32,
+                    // First screen is the one with menu bar
+                    is_primary: i == 0,
                 };
                 res.push(s);
             }
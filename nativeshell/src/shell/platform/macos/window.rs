@@ -1,4 +1,5 @@
 use super::{
+    alert::show_alert,
     drag_context::{DragContext, NSDragOperation},
     engine::PlatformEngine,
     error::{PlatformError, PlatformResult},
@@ -10,11 +11,12 @@ use crate::{
     codec::Value,
     shell::{
         api_model::{
-            BoolTransition, DragEffect, DragRequest, PopupMenuRequest, PopupMenuResponse,
-            WindowCollectionBehavior, WindowFrame, WindowGeometry, WindowGeometryFlags,
+            AlertRequest, AlertResponse, BoolTransition, DragEffect, DragRequest, ImageData,
+            PopupMenuRequest, PopupMenuResponse, WindowCollectionBehavior, WindowCreateOptions,
+            WindowDragRegions, WindowEdge, WindowFrame, WindowGeometry, WindowGeometryFlags,
             WindowGeometryRequest, WindowStateFlags, WindowStyle,
         },
-        Context, PlatformWindowDelegate, Point, Size,
+        Context, PlatformWindowDelegate, Point, Rect, Size,
     },
     util::{LateRefCell, OkLog},
 };
@@ -50,8 +52,8 @@ use std::{
     time::Duration,
 };
 use NSEventType::{
-    NSLeftMouseDown, NSLeftMouseUp, NSMouseEntered, NSMouseExited, NSMouseMoved, NSRightMouseDown,
-    NSRightMouseUp,
+    NSKeyDown, NSLeftMouseDown, NSLeftMouseUp, NSMouseEntered, NSMouseExited, NSMouseMoved,
+    NSRightMouseDown, NSRightMouseUp,
 };
 
 pub type PlatformWindowType = StrongPtr;
@@ -73,6 +75,9 @@ pub struct PlatformWindow {
     flutter_view: LateRefCell<StrongPtr>,
     window_state_flags: RefCell<WindowStateFlags>,
     notify_geometry_changes: Cell<bool>,
+    attention_request: Cell<NSInteger>,
+    drag_regions: RefCell<WindowDragRegions>,
+    hide_pointer_while_typing: Cell<bool>,
 }
 
 #[link(name = "AppKit", kind = "framework")]
@@ -89,6 +94,7 @@ impl PlatformWindow {
         context: Context,
         delegate: Weak<dyn PlatformWindowDelegate>,
         parent: Option<Rc<PlatformWindow>>,
+        _options: &WindowCreateOptions,
     ) -> Self {
         autoreleasepool(|| unsafe {
             let rect = NSRect::new(NSPoint::new(400.0, 400.0), NSSize::new(400.0, 400.0));
@@ -134,6 +140,9 @@ impl PlatformWindow {
                 flutter_view: LateRefCell::new(),
                 window_state_flags: RefCell::new(WindowStateFlags::default()),
                 notify_geometry_changes: Cell::new(false),
+                attention_request: Cell::new(0),
+                drag_regions: RefCell::new(Default::default()),
+                hide_pointer_while_typing: Cell::new(false),
             }
         })
     }
@@ -285,6 +294,12 @@ impl PlatformWindow {
         })
     }
 
+    // There is no API to get frame of zoomed window before zooming; Window
+    // keeps track of it from geometry changes instead
+    pub fn get_normal_frame(&self) -> PlatformResult<Option<Rect>> {
+        Ok(None)
+    }
+
     pub fn get_screen_id(&self) -> PlatformResult<i64> {
         unsafe {
             let screen = NSWindow::screen(*self.platform_window);
@@ -409,6 +424,60 @@ impl PlatformWindow {
         }
     }
 
+    pub fn perform_window_resize(&self, _edge: WindowEdge) -> PlatformResult<()> {
+        // There is no public API to start live resize; Resizable borderless
+        // windows get resize edges from AppKit
+        Err(PlatformError::NotAvailable)
+    }
+
+    pub fn set_ignore_mouse_events(
+        &self,
+        ignore: bool,
+        _forward_motion: bool,
+    ) -> PlatformResult<()> {
+        unsafe {
+            let () = msg_send![*self.platform_window, setIgnoresMouseEvents: if ignore { YES } else { NO }];
+        }
+        Ok(())
+    }
+
+    pub fn set_input_region(&self, _region: Option<Vec<Rect>>) -> PlatformResult<()> {
+        Err(PlatformError::NotAvailable)
+    }
+
+    pub fn set_drag_regions(&self, regions: WindowDragRegions) -> PlatformResult<()> {
+        self.drag_regions.replace(regions);
+        Ok(())
+    }
+
+    // Returns true if mouse down event was consumed by drag region
+    unsafe fn handle_drag_region_mouse_down(&self, event: id) -> bool {
+        if self.drag_regions.borrow().is_empty() {
+            return false;
+        }
+        let content_view = self.platform_window.contentView();
+        let location =
+            NSView::convertPoint_fromView_(content_view, NSEvent::locationInWindow(event), nil);
+        let flipped: BOOL = msg_send![content_view, isFlipped];
+        let y = if flipped == YES {
+            location.y
+        } else {
+            NSView::frame(content_view).size.height - location.y
+        };
+        let point = Point::xy(location.x, y);
+        // performWindowDragWithEvent runs nested loop, don't keep regions borrowed
+        if !self.drag_regions.borrow().is_draggable(&point) {
+            return false;
+        }
+        let click_count: NSInteger = msg_send![event, clickCount];
+        if click_count == 2 {
+            let () = msg_send![*self.platform_window, performZoom: nil];
+        } else {
+            let () = msg_send![*self.platform_window, performWindowDragWithEvent: event];
+        }
+        true
+    }
+
     pub fn set_style(&self, style: WindowStyle) -> PlatformResult<()> {
         unsafe {
             let mut mask: NSWindowStyleMask = NSWindowStyleMask::NSBorderlessWindowMask;
@@ -480,6 +549,21 @@ impl PlatformWindow {
                     false => 0,                                     /* kCGNormalWindowLevel */
                 },
             );
+
+            NSWindow::setAlphaValue_(*self.platform_window, style.opacity.unwrap_or(1.0));
+            let background: id = match (&style.background_color, style.transparent) {
+                (Some(color), _) => {
+                    let (r, g, b, a) = color.to_rgba_f64();
+                    NSColor::colorWithRed_green_blue_alpha_(nil, r, g, b, a)
+                }
+                (None, true) => NSColor::clearColor(nil),
+                (None, false) => msg_send![class!(NSColor), windowBackgroundColor],
+            };
+            NSWindow::setBackgroundColor_(*self.platform_window, background);
+            NSWindow::setOpaque_(
+                *self.platform_window,
+                if style.transparent { NO } else { YES },
+            );
         }
         Ok(())
     }
@@ -514,6 +598,59 @@ impl PlatformWindow {
         self.modal_close_callback.borrow().is_some()
     }
 
+    // Bounces dock icon until application is activated
+    pub fn set_urgent(&self, urgent: bool) -> PlatformResult<()> {
+        unsafe {
+            let app = NSApplication::sharedApplication(nil);
+            let request = self.attention_request.replace(0);
+            if request != 0 {
+                let () = msg_send![app, cancelUserAttentionRequest: request];
+            }
+            if urgent {
+                // NSInformationalRequest
+                let request: NSInteger = msg_send![app, requestUserAttention: 10 as NSUInteger];
+                self.attention_request.set(request);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_icon(&self, _images: Vec<ImageData>) -> PlatformResult<()> {
+        // Windows don't have icons on macOS; Application icon comes from bundle
+        Err(PlatformError::NotAvailable)
+    }
+
+    pub fn set_application_id(&self, _application_id: String) -> PlatformResult<()> {
+        Err(PlatformError::NotAvailable)
+    }
+
+    pub fn capture_image(&self) -> PlatformResult<ImageData> {
+        // TODO: CGWindowListCreateImage; cacheDisplayInRect doesn't capture
+        // metal layer contents
+        Err(PlatformError::NotAvailable)
+    }
+
+    pub fn lock_pointer(&self) -> PlatformResult<()> {
+        // TODO: CGAssociateMouseAndMouseCursorPosition(false) + NSCursor hide,
+        // relative motion from deltaX/deltaY of mouse events
+        Err(PlatformError::NotAvailable)
+    }
+
+    pub fn confine_pointer(&self, _rect: Option<Rect>) -> PlatformResult<()> {
+        // TODO: There is no confinement API; Would need warping pointer back
+        // on mouse moved
+        Err(PlatformError::NotAvailable)
+    }
+
+    pub fn release_pointer(&self) -> PlatformResult<()> {
+        Ok(())
+    }
+
+    pub fn set_hide_pointer_while_typing(&self, hide: bool) -> PlatformResult<()> {
+        self.hide_pointer_while_typing.set(hide);
+        Ok(())
+    }
+
     pub fn set_collection_behavior(
         &self,
         behavior: WindowCollectionBehavior,
@@ -890,6 +1027,14 @@ impl PlatformWindow {
 
     pub fn should_send_event(&self, event: StrongPtr) -> bool {
         let event_type = unsafe { NSEvent::eventType(*event) };
+        if event_type == NSLeftMouseDown && unsafe { self.handle_drag_region_mouse_down(*event) } {
+            return false;
+        }
+        if event_type == NSKeyDown && self.hide_pointer_while_typing.get() {
+            unsafe {
+                let () = msg_send![class!(NSCursor), setHiddenUntilMouseMoves: YES];
+            }
+        }
         if event_type == NSMouseEntered || event_type == NSMouseExited {
             let timestamp = unsafe { NSEvent::timestamp(*event) };
             // we attempt to ignore the event, unfortunately this doesn't work for
@@ -989,6 +1134,13 @@ impl PlatformWindow {
         Ok(())
     }
 
+    pub fn show_alert<F>(&self, request: AlertRequest, on_done: F)
+    where
+        F: FnOnce(PlatformResult<AlertResponse>) + 'static,
+    {
+        show_alert(*self.platform_window, request, on_done);
+    }
+
     pub fn show_system_menu(&self) -> PlatformResult<()> {
         // no system menu in mac
         Err(PlatformError::NotAvailable)
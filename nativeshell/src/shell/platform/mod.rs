@@ -1,21 +1,23 @@
 pub use self::platform_impl::*;
 
-// #[path = "null/mod.rs"]
-// mod platform_impl;
+#[cfg(feature = "null_platform")]
+#[path = "null/mod.rs"]
+mod platform_impl;
 
-#[cfg(target_os = "macos")]
+#[cfg(all(target_os = "macos", not(feature = "null_platform")))]
 #[path = "macos/mod.rs"]
 mod platform_impl;
 
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", not(feature = "null_platform")))]
 #[path = "win32/mod.rs"]
 mod platform_impl;
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", not(feature = "null_platform")))]
 #[path = "linux/mod.rs"]
 mod platform_impl;
 
 // Null implementation - include just to make sure that it compiles
+#[cfg(not(feature = "null_platform"))]
 #[allow(unused_imports, unused_variables, dead_code)]
 #[path = "null/mod.rs"]
 mod null;
@@ -1,15 +1,90 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    path::Path,
+    rc::Rc,
+};
 
-use crate::shell::ContextRef;
+use url::Url;
 
-pub trait ApplicationDelegate {}
+use crate::shell::{app_delegate::DelegateState, ContextRef};
 
-pub struct ApplicationDelegateManager {}
+pub use crate::shell::app_delegate::{
+    AppTermination, ApplicationDelegate, ApplicationTerminateReply, SessionEndReason,
+};
+
+pub struct ApplicationDelegateManager {
+    state: Rc<DelegateState>,
+    // Simulated session end is blocked while termination is cancelled or
+    // pending
+    session_end_blocked: Rc<Cell<bool>>,
+}
 
 impl ApplicationDelegateManager {
-    pub fn new(_context: &ContextRef) -> Self {
-        Self {}
+    pub fn new(context: &ContextRef) -> Self {
+        let state = Rc::new(DelegateState::new(context.weak()));
+        let session_end_blocked = Rc::new(Cell::new(false));
+        let blocked = session_end_blocked.clone();
+        state.set_on_termination_resolved(move |_| blocked.set(false));
+        Self {
+            state,
+            session_end_blocked,
+        }
+    }
+
+    pub fn is_session_end_blocked(&self) -> bool {
+        self.session_end_blocked.get()
+    }
+
+    pub fn set_delegate<D: ApplicationDelegate + 'static>(&self, delegate: D) {
+        self.state.set_delegate(Rc::new(RefCell::new(delegate)));
+    }
+
+    pub fn set_delegate_ref<D: ApplicationDelegate + 'static>(&self, delegate: Rc<RefCell<D>>) {
+        self.state.set_delegate(delegate);
+    }
+
+    pub fn request_terminate(&self) {
+        self.state.request_terminate();
+    }
+
+    //
+    // Simulated system events
+    //
+
+    pub fn simulate_activation_change(&self, active: bool) {
+        if active {
+            self.state
+                .with_delegate(|d| d.application_did_become_active());
+        } else {
+            self.state
+                .with_delegate(|d| d.application_did_resign_active());
+        }
+    }
+
+    pub fn simulate_open_urls(&self, urls: &[Url]) {
+        self.state.open_urls(urls);
+    }
+
+    pub fn simulate_secondary_instance_launched(
+        &self,
+        arguments: &[String],
+        working_directory: Option<&Path>,
+    ) {
+        self.state
+            .secondary_instance_launched(arguments, working_directory);
+    }
+
+    pub fn simulate_session_end(&self, reason: SessionEndReason) {
+        self.state.session_will_end(reason);
+        match reason {
+            SessionEndReason::Logout => {
+                let reply = self.state.request_terminate();
+                self.session_end_blocked.set(matches!(
+                    reply,
+                    ApplicationTerminateReply::Cancel | ApplicationTerminateReply::Later
+                ));
+            }
+            SessionEndReason::Shutdown => self.state.terminate(),
+        }
     }
-    pub fn set_delegate<D: ApplicationDelegate + 'static>(&self, _delegate: D) {}
-    pub fn set_delegate_ref<D: ApplicationDelegate + 'static>(&self, _delegate: Rc<RefCell<D>>) {}
 }
@@ -1,27 +1,41 @@
+use std::rc::Rc;
+
 use crate::shell::BinaryMessengerReply;
 
-use super::error::{PlatformError, PlatformResult};
+use super::{engine::NullEngine, error::PlatformResult};
 
-pub struct PlatformBinaryMessenger {}
+pub struct PlatformBinaryMessenger {
+    engine: NullEngine,
+}
 
-#[allow(unused_variables)]
 impl PlatformBinaryMessenger {
+    pub fn new(engine: NullEngine) -> Self {
+        Self { engine }
+    }
+
     pub fn register_channel_handler<F>(&self, channel: &str, callback: F)
     where
         F: Fn(&[u8], BinaryMessengerReply) + 'static,
     {
+        self.engine
+            .register_channel_handler(channel, Rc::new(callback));
     }
 
-    pub fn unregister_channel_handler(&self, channel: &str) {}
+    pub fn unregister_channel_handler(&self, channel: &str) {
+        self.engine.unregister_channel_handler(channel);
+    }
 
     pub fn send_message<F>(&self, channel: &str, message: &[u8], reply: F) -> PlatformResult<()>
     where
         F: FnOnce(&[u8]) + 'static,
     {
-        Err(PlatformError::NotImplemented)
+        self.engine
+            .add_outgoing_message(channel, message, Some(Box::new(reply)));
+        Ok(())
     }
 
     pub fn post_message(&self, channel: &str, message: &[u8]) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        self.engine.add_outgoing_message(channel, message, None);
+        Ok(())
     }
 }
@@ -0,0 +1,39 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use crate::{
+    shell::api_model::{ClipboardSelection, DragData},
+    Context,
+};
+
+use super::error::PlatformResult;
+
+// In-memory clipboard; Data is stored as is, there are no adapters to run.
+pub struct PlatformClipboard {
+    contents: RefCell<HashMap<ClipboardSelection, DragData>>,
+}
+
+impl PlatformClipboard {
+    pub fn new(_context: Context) -> Self {
+        Self {
+            contents: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn read_data<F>(&self, selection: ClipboardSelection, on_done: F)
+    where
+        F: FnOnce(PlatformResult<DragData>) + 'static,
+    {
+        let data = self.contents.borrow().get(&selection).cloned();
+        on_done(Ok(data.unwrap_or_default()));
+    }
+
+    pub fn write_data(&self, selection: ClipboardSelection, data: DragData) -> PlatformResult<()> {
+        self.contents.borrow_mut().insert(selection, data);
+        Ok(())
+    }
+
+    pub fn clear(&self, selection: ClipboardSelection) -> PlatformResult<()> {
+        self.contents.borrow_mut().remove(&selection);
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use std::cell::RefCell;
+
+use crate::{
+    shell::api_model::{FileDialogRequest, FileDialogType},
+    Context,
+};
+
+use super::{error::PlatformResult, window::PlatformWindowType};
+
+type FileDialogCallback = Box<dyn FnOnce(PlatformResult<Option<Vec<String>>>)>;
+
+struct PendingFileDialog {
+    dialog_type: FileDialogType,
+    request: FileDialogRequest,
+    on_done: FileDialogCallback,
+}
+
+// Dialogs stay open until `complete_file_dialog` is called.
+pub struct PlatformDialogManager {
+    pending_file_dialog: RefCell<Option<PendingFileDialog>>,
+}
+
+impl PlatformDialogManager {
+    pub fn new(_context: Context) -> Self {
+        Self {
+            pending_file_dialog: RefCell::new(None),
+        }
+    }
+
+    pub fn show_file_dialog<F>(
+        &self,
+        dialog_type: FileDialogType,
+        request: FileDialogRequest,
+        _parent: Option<PlatformWindowType>,
+        on_done: F,
+    ) where
+        F: FnOnce(PlatformResult<Option<Vec<String>>>) + 'static,
+    {
+        let previous = self.pending_file_dialog.replace(Some(PendingFileDialog {
+            dialog_type,
+            request,
+            on_done: Box::new(on_done),
+        }));
+        if let Some(previous) = previous {
+            (previous.on_done)(Ok(None));
+        }
+    }
+
+    pub fn pending_file_dialog(&self) -> Option<(FileDialogType, FileDialogRequest)> {
+        self.pending_file_dialog
+            .borrow()
+            .as_ref()
+            .map(|d| (d.dialog_type, d.request.clone()))
+    }
+
+    // Closes pending dialog; None means the dialog was cancelled
+    pub fn complete_file_dialog(&self, paths: Option<Vec<String>>) {
+        let dialog = self.pending_file_dialog.take();
+        if let Some(dialog) = dialog {
+            (dialog.on_done)(Ok(paths));
+        }
+    }
+}
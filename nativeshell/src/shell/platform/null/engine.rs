@@ -1,6 +1,375 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::{
+    codec::{MessageCodec, MethodCall, MethodCallResult, MethodCodec, StandardMethodCodec, Value},
+    shell::{
+        api_constants::channel,
+        window_method_channel::{
+            decode_method_call, decode_result, encode_method_call, encode_result,
+        },
+        BinaryMessengerReply, WindowHandle, WindowMethodCall,
+    },
+};
+
 use super::{binary_messenger::PlatformBinaryMessenger, error::PlatformResult};
 
-pub type PlatformEngineType = isize;
+type ChannelHandler = Rc<dyn Fn(&[u8], BinaryMessengerReply)>;
+
+// Message sent from shell to the (simulated) Dart side.
+pub struct OutgoingMessage {
+    pub channel: String,
+    pub message: Vec<u8>,
+    reply: Option<Box<dyn FnOnce(&[u8])>>,
+}
+
+impl OutgoingMessage {
+    // Whether the sender expects reply (sent through `send_message` rather
+    // than `post_message`).
+    pub fn expects_reply(&self) -> bool {
+        self.reply.is_some()
+    }
+
+    pub fn reply(mut self, message: &[u8]) {
+        if let Some(reply) = self.reply.take() {
+            reply(message);
+        }
+    }
+}
+
+// Method call sent from shell to Dart. Unless replied to, the caller will never
+// get the result.
+pub struct OutgoingMethodCall {
+    pub call: MethodCall<Value>,
+    message: OutgoingMessage,
+}
+
+impl OutgoingMethodCall {
+    pub fn reply(self, result: MethodCallResult<Value>) {
+        let encoded = StandardMethodCodec.encode_method_call_result(&result);
+        self.message.reply(&encoded);
+    }
+}
+
+// Method call sent from shell to Dart code of a window through window method
+// channel.
+pub struct OutgoingWindowMethodCall {
+    pub call: WindowMethodCall,
+    message: OutgoingMessage,
+}
+
+impl OutgoingWindowMethodCall {
+    pub fn reply(self, result: MethodCallResult<Value>) {
+        let encoded = StandardMethodCodec.encode_message(&encode_result(result));
+        self.message.reply(&encoded);
+    }
+}
+
+// Message broadcasted to all windows through WindowMessageBroadcaster
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowMessage {
+    pub source_window_handle: WindowHandle,
+    pub channel: String,
+    pub message: String,
+    pub arguments: Value,
+}
+
+// Reply to message sent from simulated Dart side. Shell handlers can reply
+// asynchronously, in which case the reply is only available after run loop
+// processes pending tasks.
+pub struct PendingReply<T> {
+    value: Rc<RefCell<Option<T>>>,
+}
+
+impl<T> PendingReply<T> {
+    fn new() -> Self {
+        Self {
+            value: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    fn setter(&self) -> impl FnOnce(T) {
+        let value = self.value.clone();
+        move |v| {
+            value.replace(Some(v));
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.value.borrow().is_some()
+    }
+
+    pub fn take(&self) -> Option<T> {
+        self.value.borrow_mut().take()
+    }
+
+    // Returns reply value, panics if shell hasn't replied yet.
+    pub fn unwrap(&self) -> T {
+        self.take().expect("Shell did not reply to the message")
+    }
+}
+
+#[derive(Default)]
+struct EngineState {
+    launched: bool,
+    handlers: HashMap<String, ChannelHandler>,
+    outgoing: Vec<OutgoingMessage>,
+}
+
+// In-process stand-in for Flutter engine. Instead of running Dart code it
+// keeps track of channel handlers registered by shell and collects all
+// messages sent to Dart. Tests can use it to play the Dart side of platform
+// channels: invoke methods, subscribe to events and reply to method calls.
+#[derive(Clone, Default)]
+pub struct NullEngine {
+    state: Rc<RefCell<EngineState>>,
+}
+
+impl NullEngine {
+    pub fn is_launched(&self) -> bool {
+        self.state.borrow().launched
+    }
+
+    pub fn has_channel_handler(&self, channel: &str) -> bool {
+        self.state.borrow().handlers.contains_key(channel)
+    }
+
+    // Simulates message sent from Dart to shell. Reply callback is invoked
+    // when the shell responds (possibly after this method returns). Returns
+    // false if there is no handler registered for the channel.
+    pub fn dispatch_message<F>(&self, channel: &str, message: &[u8], reply: F) -> bool
+    where
+        F: FnOnce(&[u8]) + 'static,
+    {
+        let handler = self.state.borrow().handlers.get(channel).cloned();
+        match handler {
+            Some(handler) => {
+                handler(message, BinaryMessengerReply::new(reply));
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Returns all messages sent to Dart since last call.
+    pub fn take_outgoing_messages(&self) -> Vec<OutgoingMessage> {
+        std::mem::take(&mut self.state.borrow_mut().outgoing)
+    }
+
+    // Returns messages sent to Dart on given channel, leaving messages for
+    // other channels in place.
+    pub fn take_outgoing_messages_for_channel(&self, channel: &str) -> Vec<OutgoingMessage> {
+        let mut state = self.state.borrow_mut();
+        let (res, rest) = std::mem::take(&mut state.outgoing)
+            .into_iter()
+            .partition(|m| m.channel == channel);
+        state.outgoing = rest;
+        res
+    }
+
+    //
+    // Typed helpers, all using StandardMethodCodec
+    //
+
+    // Sends message through message channel.
+    pub fn send_message(&self, channel: &str, message: &Value) -> PendingReply<Value> {
+        let res = PendingReply::new();
+        let setter = res.setter();
+        self.dispatch_or_panic(
+            channel,
+            &StandardMethodCodec.encode_message(message),
+            move |reply| {
+                setter(StandardMethodCodec.decode_message(reply).unwrap());
+            },
+        );
+        res
+    }
+
+    // Invokes method on method channel.
+    pub fn invoke_method(
+        &self,
+        channel: &str,
+        method: &str,
+        args: Value,
+    ) -> PendingReply<MethodCallResult<Value>> {
+        let res = PendingReply::new();
+        let setter = res.setter();
+        let call = MethodCall {
+            method: method.into(),
+            args,
+        };
+        self.dispatch_or_panic(
+            channel,
+            &StandardMethodCodec.encode_method_call(&call),
+            move |reply| {
+                setter(StandardMethodCodec.decode_envelope(reply).unwrap());
+            },
+        );
+        res
+    }
+
+    // Invokes method on window method channel, i.e. call from Dart code of
+    // window that belongs to this engine.
+    pub fn invoke_window_method(
+        &self,
+        target_window_handle: WindowHandle,
+        channel: &str,
+        method: &str,
+        arguments: Value,
+    ) -> PendingReply<MethodCallResult<Value>> {
+        let res = PendingReply::new();
+        let setter = res.setter();
+        let call = encode_method_call(WindowMethodCall {
+            target_window_handle,
+            method: method.into(),
+            channel: channel.into(),
+            arguments,
+        });
+        self.dispatch_or_panic(
+            channel::DISPATCHER,
+            &StandardMethodCodec.encode_message(&call),
+            move |reply| {
+                setter(decode_result(
+                    StandardMethodCodec.decode_message(reply).unwrap(),
+                ));
+            },
+        );
+        res
+    }
+
+    // Subscribes to event channel.
+    pub fn listen(&self, channel: &str, args: Value) -> PendingReply<MethodCallResult<Value>> {
+        self.invoke_method(channel, "listen", args)
+    }
+
+    // Cancels event channel subscription.
+    pub fn cancel(&self, channel: &str) -> PendingReply<MethodCallResult<Value>> {
+        self.invoke_method(channel, "cancel", Value::Null)
+    }
+
+    // Returns method calls sent to Dart on given method channel.
+    pub fn take_method_calls(&self, channel: &str) -> Vec<OutgoingMethodCall> {
+        self.take_outgoing_messages_for_channel(channel)
+            .into_iter()
+            .map(|message| OutgoingMethodCall {
+                call: StandardMethodCodec
+                    .decode_method_call(&message.message)
+                    .unwrap(),
+                message,
+            })
+            .collect()
+    }
+
+    // Returns events sent to Dart on given event channel.
+    pub fn take_events(&self, channel: &str) -> Vec<MethodCallResult<Value>> {
+        self.take_outgoing_messages_for_channel(channel)
+            .into_iter()
+            .map(|message| {
+                StandardMethodCodec
+                    .decode_envelope(&message.message)
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    // Returns method calls sent to windows of this engine through window
+    // method channel.
+    pub fn take_window_method_calls(&self) -> Vec<OutgoingWindowMethodCall> {
+        let (calls, rest): (Vec<_>, Vec<_>) = self
+            .take_outgoing_messages_for_channel(channel::DISPATCHER)
+            .into_iter()
+            .partition(|m| m.expects_reply());
+        self.restore_outgoing_messages(rest);
+        calls
+            .into_iter()
+            .map(|message| OutgoingWindowMethodCall {
+                call: decode_method_call(
+                    StandardMethodCodec
+                        .decode_message(&message.message)
+                        .unwrap(),
+                ),
+                message,
+            })
+            .collect()
+    }
+
+    // Returns messages broadcasted to windows of this engine.
+    pub fn take_window_messages(&self) -> Vec<WindowMessage> {
+        let (messages, rest): (Vec<_>, Vec<_>) = self
+            .take_outgoing_messages_for_channel(channel::DISPATCHER)
+            .into_iter()
+            .partition(|m| !m.expects_reply());
+        self.restore_outgoing_messages(rest);
+        messages
+            .into_iter()
+            .map(|m| decode_window_message(StandardMethodCodec.decode_message(&m.message).unwrap()))
+            .collect()
+    }
+
+    fn dispatch_or_panic<F>(&self, channel: &str, message: &[u8], reply: F)
+    where
+        F: FnOnce(&[u8]) + 'static,
+    {
+        if !self.dispatch_message(channel, message, reply) {
+            panic!("No handler registered for channel {}", channel);
+        }
+    }
+
+    fn restore_outgoing_messages(&self, mut messages: Vec<OutgoingMessage>) {
+        let mut state = self.state.borrow_mut();
+        messages.append(&mut state.outgoing);
+        state.outgoing = messages;
+    }
+
+    pub(super) fn register_channel_handler(&self, channel: &str, handler: ChannelHandler) {
+        self.state
+            .borrow_mut()
+            .handlers
+            .insert(channel.into(), handler);
+    }
+
+    pub(super) fn unregister_channel_handler(&self, channel: &str) {
+        self.state.borrow_mut().handlers.remove(channel);
+    }
+
+    pub(super) fn add_outgoing_message(
+        &self,
+        channel: &str,
+        message: &[u8],
+        reply: Option<Box<dyn FnOnce(&[u8])>>,
+    ) {
+        self.state.borrow_mut().outgoing.push(OutgoingMessage {
+            channel: channel.into(),
+            message: message.into(),
+            reply,
+        });
+    }
+}
+
+fn decode_window_message(message: Value) -> WindowMessage {
+    if let Value::Map(mut map) = message {
+        let source_window_handle = map.remove(&"sourceWindowHandle".into());
+        let channel = map.remove(&"channel".into());
+        let message = map.remove(&"message".into());
+        let arguments = map.remove(&"arguments".into());
+        match (source_window_handle, channel, message) {
+            (
+                Some(Value::I64(source_window_handle)),
+                Some(Value::String(channel)),
+                Some(Value::String(message)),
+            ) => WindowMessage {
+                source_window_handle: WindowHandle(source_window_handle),
+                channel,
+                message,
+                arguments: arguments.unwrap_or(Value::Null),
+            },
+            _ => panic!("Invalid window message"),
+        }
+    } else {
+        panic!("Invalid window message");
+    }
+}
+
+pub type PlatformEngineType = NullEngine;
 
 pub struct PlatformEngine {
     pub(crate) handle: PlatformEngineType,
@@ -10,18 +379,24 @@ pub type PlatformPlugin = isize;
 
 impl PlatformEngine {
     pub fn new(_plugins: &[PlatformPlugin]) -> Self {
-        PlatformEngine { handle: 0 }
+        PlatformEngine {
+            handle: Default::default(),
+        }
     }
 
     pub fn new_binary_messenger(&self) -> PlatformBinaryMessenger {
-        PlatformBinaryMessenger {}
+        PlatformBinaryMessenger::new(self.handle.clone())
     }
 
     pub fn launch(&mut self) -> PlatformResult<()> {
-        Err(super::error::PlatformError::NotImplemented)
+        self.handle.state.borrow_mut().launched = true;
+        Ok(())
     }
 
     pub fn shut_down(&mut self) -> PlatformResult<()> {
-        Err(super::error::PlatformError::NotImplemented)
+        let mut state = self.handle.state.borrow_mut();
+        state.launched = false;
+        state.handlers.clear();
+        Ok(())
     }
 }
@@ -1,5 +1,7 @@
+use crate::shell::ContextOptions;
+
 use super::error::PlatformResult;
 
-pub fn init_platform() -> PlatformResult<()> {
+pub fn init_platform(_options: &ContextOptions) -> PlatformResult<()> {
     Ok(())
 }
@@ -3,38 +3,83 @@ use std::{
     rc::{Rc, Weak},
 };
 
-use super::error::{PlatformError, PlatformResult};
+use super::error::PlatformResult;
 use crate::shell::{api_model::Menu, Context, MenuDelegate, MenuHandle, MenuManager};
 
-pub struct PlatformMenu {}
+pub struct PlatformMenu {
+    handle: MenuHandle,
+    delegate: Weak<RefCell<dyn MenuDelegate>>,
+    menu: RefCell<Menu>,
+}
 
-#[allow(unused_variables)]
 impl PlatformMenu {
     pub fn new(
-        context: Context,
+        _context: Context,
         handle: MenuHandle,
         delegate: Weak<RefCell<dyn MenuDelegate>>,
     ) -> Self {
-        Self {}
+        Self {
+            handle,
+            delegate,
+            menu: RefCell::new(Default::default()),
+        }
+    }
+
+    pub fn assign_weak_self(&self, _weak: Weak<PlatformMenu>) {}
+
+    pub fn update_from_menu(&self, menu: Menu, _manager: &MenuManager) -> PlatformResult<()> {
+        self.menu.replace(menu);
+        Ok(())
+    }
+
+    pub fn handle(&self) -> MenuHandle {
+        self.handle
     }
 
-    pub fn assign_weak_self(&self, weak: Weak<PlatformMenu>) {}
+    // Returns (id, title) of all menu items
+    pub fn items(&self) -> Vec<(i64, String)> {
+        self.menu
+            .borrow()
+            .items
+            .iter()
+            .map(|item| (item.id, item.title.clone()))
+            .collect()
+    }
+
+    // Simulates opening the menu
+    pub fn open(&self) {
+        if let Some(delegate) = self.delegate.upgrade() {
+            delegate.borrow().on_menu_open(self.handle);
+        }
+    }
 
-    pub fn update_from_menu(&self, menu: Menu, manager: &MenuManager) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+    // Simulates user selecting the menu item
+    pub fn select_item(&self, id: i64) {
+        if let Some(delegate) = self.delegate.upgrade() {
+            delegate.borrow().on_menu_action(self.handle, id);
+        }
     }
 }
 
-pub struct PlatformMenuManager {}
+pub struct PlatformMenuManager {
+    app_menu: RefCell<Option<Rc<PlatformMenu>>>,
+}
 
 impl PlatformMenuManager {
-    pub fn new(context: Context) -> Self {
-        Self {}
+    pub fn new(_context: Context) -> Self {
+        Self {
+            app_menu: RefCell::new(None),
+        }
     }
 
     pub(crate) fn assign_weak_self(&self, _weak_self: Weak<PlatformMenuManager>) {}
 
     pub fn set_app_menu(&self, menu: Option<Rc<PlatformMenu>>) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        self.app_menu.replace(menu);
+        Ok(())
+    }
+
+    pub fn app_menu(&self) -> Option<Rc<PlatformMenu>> {
+        self.app_menu.borrow().clone()
     }
 }
@@ -1,5 +1,12 @@
+// Headless platform implementation. Windows, menus, status items and screens
+// only exist in memory and engines don't run any Dart code; Messages sent to
+// engines are collected by `NullEngine` and can be answered from Rust.
+// Enabled by "null_platform" feature.
+
 pub mod app_delegate;
 pub mod binary_messenger;
+pub mod clipboard;
+pub mod dialog;
 pub mod drag_data;
 pub mod engine;
 pub mod error;
@@ -7,7 +14,12 @@ pub mod hot_key;
 pub mod init;
 pub mod keyboard_map;
 pub mod menu;
+pub mod notification;
+pub mod pointer_manager;
 pub mod run_loop;
 pub mod screen_manager;
 pub mod status_item;
 pub mod window;
+
+#[cfg(all(test, feature = "null_platform"))]
+mod tests;
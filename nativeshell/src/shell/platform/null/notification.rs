@@ -0,0 +1,66 @@
+use std::{cell::RefCell, collections::HashMap, rc::Weak};
+
+use crate::shell::{
+    api_model::{NotificationCloseReason, NotificationShowRequest},
+    Context, NotificationHandle, NotificationManagerDelegate,
+};
+
+use super::error::PlatformResult;
+
+// Keeps shown notifications in memory; Tests simulate user interaction
+// through activate_notification and dismiss_notification.
+pub struct PlatformNotificationManager {
+    delegate: Weak<RefCell<dyn NotificationManagerDelegate>>,
+    notifications: RefCell<HashMap<NotificationHandle, NotificationShowRequest>>,
+}
+
+impl PlatformNotificationManager {
+    pub fn new(
+        _context: Context,
+        delegate: Weak<RefCell<dyn NotificationManagerDelegate>>,
+    ) -> Self {
+        Self {
+            delegate,
+            notifications: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn assign_weak_self(&self, _weak: Weak<PlatformNotificationManager>) {}
+
+    pub fn show_notification<F>(
+        &self,
+        handle: NotificationHandle,
+        request: NotificationShowRequest,
+        on_done: F,
+    ) where
+        F: FnOnce(PlatformResult<()>) + 'static,
+    {
+        self.notifications.borrow_mut().insert(handle, request);
+        on_done(Ok(()));
+    }
+
+    pub fn close_notification(&self, handle: NotificationHandle) -> PlatformResult<()> {
+        self.notifications.borrow_mut().remove(&handle);
+        Ok(())
+    }
+
+    pub fn notification(&self, handle: NotificationHandle) -> Option<NotificationShowRequest> {
+        self.notifications.borrow().get(&handle).cloned()
+    }
+
+    pub fn activate_notification(&self, handle: NotificationHandle, action: Option<String>) {
+        if let Some(delegate) = self.delegate.upgrade() {
+            delegate.borrow().on_notification_activated(handle, action);
+        }
+    }
+
+    pub fn dismiss_notification(&self, handle: NotificationHandle) {
+        if self.notifications.borrow_mut().remove(&handle).is_some() {
+            if let Some(delegate) = self.delegate.upgrade() {
+                delegate
+                    .borrow()
+                    .on_notification_closed(handle, NotificationCloseReason::Dismissed);
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Weak,
+};
+
+use crate::shell::{
+    api_model::{GlobalPointerEvent, PointerState},
+    pointer_manager::PointerManagerDelegate,
+};
+
+use super::error::PlatformResult;
+
+pub struct PlatformPointerManager {
+    delegate: Weak<RefCell<dyn PointerManagerDelegate>>,
+    state: RefCell<PointerState>,
+    monitoring: Cell<bool>,
+}
+
+impl PlatformPointerManager {
+    pub fn new(delegate: Weak<RefCell<dyn PointerManagerDelegate>>) -> Self {
+        Self {
+            delegate,
+            state: RefCell::new(Default::default()),
+            monitoring: Cell::new(false),
+        }
+    }
+
+    // Replaces simulated pointer state; Position is in system coordinates.
+    pub fn set_pointer_state(&self, state: PointerState) {
+        self.state.replace(state);
+    }
+
+    pub fn is_monitoring(&self) -> bool {
+        self.monitoring.get()
+    }
+
+    // Delivers simulated event to delegate if monitoring is active.
+    pub fn simulate_event(&self, event: GlobalPointerEvent) {
+        if !self.monitoring.get() {
+            return;
+        }
+        if let Some(delegate) = self.delegate.upgrade() {
+            delegate.borrow().on_pointer_event(event);
+        }
+    }
+
+    pub fn get_pointer_state(&self) -> PlatformResult<PointerState> {
+        Ok(self.state.borrow().clone())
+    }
+
+    pub fn start_monitoring(&self) -> PlatformResult<()> {
+        self.monitoring.set(true);
+        Ok(())
+    }
+
+    pub fn stop_monitoring(&self) {
+        self.monitoring.set(false);
+    }
+}
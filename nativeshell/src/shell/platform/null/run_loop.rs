@@ -1,43 +1,153 @@
-use std::time::Duration;
+use std::{
+    cell::{Cell, RefCell},
+    mem::take,
+    sync::{Arc, Condvar, Mutex},
+    time::{Duration, Instant},
+};
 
 pub type HandleType = usize;
 pub const INVALID_HANDLE: HandleType = 0;
 
-pub struct PlatformRunLoop {}
+type SendCallback = Box<dyn FnOnce() + Send>;
+
+struct Timer {
+    handle: HandleType,
+    fire_at: Instant,
+    callback: Box<dyn FnOnce()>,
+}
+
+#[derive(Default)]
+struct SenderQueue {
+    callbacks: Mutex<Vec<SendCallback>>,
+    condition: Condvar,
+}
+
+// Run loop without any native event source. Timers and callbacks sent from
+// other threads are processed by `run`, or by `run_until_idle` which returns
+// as soon as there is nothing left to do (useful in tests).
+pub struct PlatformRunLoop {
+    next_handle: Cell<HandleType>,
+    timers: RefCell<Vec<Timer>>,
+    running: Cell<bool>,
+    queue: Arc<SenderQueue>,
+}
 
-#[allow(unused_variables)]
 impl PlatformRunLoop {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            next_handle: Cell::new(INVALID_HANDLE + 1),
+            timers: RefCell::new(Vec::new()),
+            running: Cell::new(false),
+            queue: Arc::new(Default::default()),
+        }
     }
 
-    pub fn unschedule(&self, handle: HandleType) {}
+    pub fn unschedule(&self, handle: HandleType) {
+        self.timers.borrow_mut().retain(|t| t.handle != handle);
+    }
 
     #[must_use]
     pub fn schedule<F>(&self, in_time: Duration, callback: F) -> HandleType
     where
         F: FnOnce() + 'static,
     {
-        INVALID_HANDLE
+        let handle = self.next_handle.get();
+        self.next_handle.set(handle + 1);
+        self.timers.borrow_mut().push(Timer {
+            handle,
+            fire_at: Instant::now() + in_time,
+            callback: Box::new(callback),
+        });
+        handle
+    }
+
+    fn next_due_timer(&self) -> Option<Timer> {
+        let mut timers = self.timers.borrow_mut();
+        let now = Instant::now();
+        // timers with same fire time are processed in order they were scheduled
+        let index = timers
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.fire_at <= now)
+            .min_by_key(|(_, t)| (t.fire_at, t.handle))
+            .map(|(index, _)| index);
+        index.map(|index| timers.remove(index))
     }
 
-    pub fn run(&self) {}
+    fn next_fire_time(&self) -> Option<Instant> {
+        self.timers.borrow().iter().map(|t| t.fire_at).min()
+    }
 
-    pub fn stop(&self) {}
+    // Processes pending callbacks and due timers; Returns false if there was
+    // nothing to process.
+    fn process_pending(&self) -> bool {
+        let callbacks = take(&mut *self.queue.callbacks.lock().unwrap());
+        let mut processed = !callbacks.is_empty();
+        for callback in callbacks {
+            callback();
+        }
+        while let Some(timer) = self.next_due_timer() {
+            (timer.callback)();
+            processed = true;
+        }
+        processed
+    }
+
+    pub fn run(&self) {
+        self.running.set(true);
+        while self.running.get() {
+            if self.process_pending() {
+                continue;
+            }
+            let callbacks = self.queue.callbacks.lock().unwrap();
+            if !callbacks.is_empty() || !self.running.get() {
+                continue;
+            }
+            match self.next_fire_time() {
+                Some(fire_at) => {
+                    let timeout = fire_at.saturating_duration_since(Instant::now());
+                    drop(self.queue.condition.wait_timeout(callbacks, timeout));
+                }
+                None => {
+                    drop(self.queue.condition.wait(callbacks));
+                }
+            }
+        }
+    }
+
+    // Runs all callbacks and timers that are due, including the ones scheduled
+    // while processing. Returns when there are no more due callbacks.
+    pub fn run_until_idle(&self) {
+        while self.process_pending() {}
+    }
+
+    pub fn stop(&self) {
+        self.running.set(false);
+        self.queue.condition.notify_one();
+    }
 
     pub fn new_sender(&self) -> PlatformRunLoopSender {
-        PlatformRunLoopSender {}
+        PlatformRunLoopSender {
+            queue: self.queue.clone(),
+        }
     }
 }
 
 #[derive(Clone)]
-pub struct PlatformRunLoopSender {}
+pub struct PlatformRunLoopSender {
+    queue: Arc<SenderQueue>,
+}
 
-#[allow(unused_variables)]
 impl PlatformRunLoopSender {
     pub fn send<F>(&self, callback: F)
     where
         F: FnOnce() + 'static + Send,
     {
+        self.queue
+            .callbacks
+            .lock()
+            .unwrap()
+            .push(Box::new(callback));
+        self.queue.condition.notify_one();
     }
 }
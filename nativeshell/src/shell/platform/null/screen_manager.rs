@@ -1,29 +1,75 @@
 use std::{cell::RefCell, rc::Weak};
 
-use crate::shell::{api_model::Screen, screen_manager::ScreenManagerDelegate, Point};
+use crate::shell::{api_model::Screen, screen_manager::ScreenManagerDelegate, Point, Rect};
 
 use super::error::{PlatformError, PlatformResult};
 
-pub struct PlatformScreenManager {}
+thread_local! {
+    static SCREENS: RefCell<Vec<Screen>> = RefCell::new(vec![default_screen()]);
+}
+
+fn default_screen() -> Screen {
+    Screen {
+        id: 1,
+        frame: Rect::xywh(0.0, 0.0, 1920.0, 1080.0),
+        work_area: Rect::xywh(0.0, 0.0, 1920.0, 1080.0),
+        scaling_factor: 1.0,
+        name: Some("Null Display".into()),
+        refresh_rate: Some(60.0),
+        is_primary: true,
+        ..Default::default()
+    }
+}
+
+pub struct PlatformScreenManager {
+    delegate: Weak<RefCell<dyn ScreenManagerDelegate>>,
+}
 
 impl PlatformScreenManager {
     pub fn new(delegate: Weak<RefCell<dyn ScreenManagerDelegate>>) -> Self {
-        Self {}
+        Self { delegate }
+    }
+
+    // Screens are shared by all contexts on current thread. First screen is
+    // considered the main screen.
+    pub fn screens() -> Vec<Screen> {
+        SCREENS.with(|s| s.borrow().clone())
+    }
+
+    // Replaces the simulated screen configuration and notifies delegate.
+    pub fn set_screens(&self, screens: Vec<Screen>) {
+        SCREENS.with(|s| s.replace(screens));
+        if let Some(delegate) = self.delegate.upgrade() {
+            delegate.borrow().screen_configuration_changed();
+        }
+    }
+
+    // Returns screen that contains given point or main screen if there is none.
+    pub fn screen_for_point(point: &Point) -> Option<Screen> {
+        let screens = Self::screens();
+        screens
+            .iter()
+            .find(|s| s.frame.is_inside(point))
+            .or_else(|| screens.first())
+            .cloned()
     }
 
     pub fn get_screens(&self) -> PlatformResult<Vec<Screen>> {
-        Err(PlatformError::NotImplemented)
+        Ok(Self::screens())
     }
 
     pub fn get_main_screen(&self) -> PlatformResult<i64> {
-        Err(PlatformError::NotImplemented)
+        Self::screens()
+            .first()
+            .map(|s| s.id)
+            .ok_or(PlatformError::UnknownError)
     }
 
     pub fn logical_to_system(&self, offset: Point) -> PlatformResult<Point> {
-        Err(PlatformError::NotImplemented)
+        Ok(offset)
     }
 
     pub fn system_to_logical(&self, offset: Point) -> PlatformResult<Point> {
-        Err(PlatformError::NotImplemented)
+        Ok(offset)
     }
 }
@@ -1,65 +1,98 @@
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     rc::{Rc, Weak},
 };
 
 use crate::{
     shell::{
-        api_model::ImageData,
+        api_model::{ImageData, StatusItemActionType},
         status_item_manager::{StatusItemDelegate, StatusItemHandle},
         EngineHandle, Point, Rect,
     },
     Context,
 };
 
-use super::{
-    error::{PlatformError, PlatformResult},
-    menu::PlatformMenu,
-};
+use super::{error::PlatformResult, menu::PlatformMenu, screen_manager::PlatformScreenManager};
 
 pub struct PlatformStatusItem {
+    handle: StatusItemHandle,
+    delegate: Weak<RefCell<dyn StatusItemDelegate>>,
     pub(crate) engine: EngineHandle,
+    image: RefCell<Vec<ImageData>>,
+    hint: RefCell<String>,
+    highlighted: Cell<bool>,
 }
 
 impl PlatformStatusItem {
-    pub fn assign_weak_self(&self, weak: Weak<PlatformStatusItem>) {}
+    pub fn assign_weak_self(&self, _weak: Weak<PlatformStatusItem>) {}
 
     pub fn set_image(&self, image: Vec<ImageData>) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        self.image.replace(image);
+        Ok(())
     }
 
     pub fn set_hint(&self, hint: String) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        self.hint.replace(hint);
+        Ok(())
     }
 
-    pub fn show_menu<F>(&self, menu: Rc<PlatformMenu>, offset: Point, on_done: F)
+    pub fn show_menu<F>(&self, menu: Rc<PlatformMenu>, _offset: Point, on_done: F)
     where
         F: FnOnce(PlatformResult<()>) + 'static,
     {
-        on_done(Err(PlatformError::NotImplemented))
+        menu.open();
+        on_done(Ok(()))
     }
 
     pub fn set_highlighted(&self, highlighted: bool) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        self.highlighted.set(highlighted);
+        Ok(())
     }
 
     pub fn get_geometry(&self) -> PlatformResult<Rect> {
-        Err(PlatformError::NotImplemented)
+        Ok(Rect::xywh(0.0, 0.0, 24.0, 24.0))
     }
 
     pub fn get_screen_id(&self) -> PlatformResult<i64> {
-        Err(PlatformError::NotImplemented)
+        Ok(
+            PlatformScreenManager::screen_for_point(&Point::xy(0.0, 0.0))
+                .map(|s| s.id)
+                .unwrap_or(0),
+        )
+    }
+
+    pub fn handle(&self) -> StatusItemHandle {
+        self.handle
+    }
+
+    pub fn image(&self) -> Vec<ImageData> {
+        self.image.borrow().clone()
+    }
+
+    pub fn hint(&self) -> String {
+        self.hint.borrow().clone()
+    }
+
+    pub fn is_highlighted(&self) -> bool {
+        self.highlighted.get()
+    }
+
+    // Simulates mouse action on the status item
+    pub fn perform_action(&self, action: StatusItemActionType, position: Point) {
+        if let Some(delegate) = self.delegate.upgrade() {
+            delegate.borrow().on_action(self.handle, action, position);
+        }
     }
 }
 
 pub struct PlatformStatusItemManager {}
 
 impl PlatformStatusItemManager {
-    pub fn new(context: Context) -> Self {
+    pub fn new(_context: Context) -> Self {
         Self {}
     }
 
-    pub fn assign_weak_self(&self, weak: Weak<PlatformStatusItemManager>) {}
+    pub fn assign_weak_self(&self, _weak: Weak<PlatformStatusItemManager>) {}
 
     pub fn create_status_item(
         &self,
@@ -67,8 +100,15 @@ impl PlatformStatusItemManager {
         delegate: Weak<RefCell<dyn StatusItemDelegate>>,
         engine: EngineHandle,
     ) -> PlatformResult<Rc<PlatformStatusItem>> {
-        Err(PlatformError::NotImplemented)
+        Ok(Rc::new(PlatformStatusItem {
+            handle,
+            delegate,
+            engine,
+            image: RefCell::new(Vec::new()),
+            hint: RefCell::new(String::new()),
+            highlighted: Cell::new(false),
+        }))
     }
 
-    pub fn unregister_status_item(&self, item: &Rc<PlatformStatusItem>) {}
+    pub fn unregister_status_item(&self, _item: &Rc<PlatformStatusItem>) {}
 }
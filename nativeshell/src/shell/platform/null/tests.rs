@@ -0,0 +1,1577 @@
+use std::{cell::RefCell, path::Path, rc::Rc};
+
+use async_trait::async_trait;
+use velcro::hash_map;
+
+use crate::{
+    codec::{
+        value::{from_value, to_value},
+        MethodCall, MethodCallError, MethodCallReply, MethodCallResult, Value,
+    },
+    shell::{
+        api_constants::{channel, method},
+        api_model::{
+            BoolTransition, FileDialogType, GeometryPreference, GlobalPointerEvent,
+            GlobalPointerEventType, ImageData, PointerModifiers, PointerState, Screen,
+            ScreensChanged, StatusItemActionType, WindowCreateOptions, WindowFrame, WindowGeometry,
+            WindowGeometryRequest, WindowPlacement, WindowPosition, WindowStyle, WindowType,
+            POINTER_BUTTON_PRIMARY, POINTER_BUTTON_SECONDARY,
+        },
+        AsyncMethodCallHandler, AsyncMethodInvoker, ContextOptions, ContextRef, EngineHandle,
+        EventChannelHandler, EventSink, MenuDelegate, MenuHandle, MethodCallHandler,
+        MethodInvokerProvider, NotificationHandle, Point, Rect, Size, WindowEvent, WindowHandle,
+    },
+    Context, Error,
+};
+
+use super::{
+    app_delegate::{
+        AppTermination, ApplicationDelegate, ApplicationTerminateReply, SessionEndReason,
+    },
+    engine::{NullEngine, WindowMessage},
+    screen_manager::PlatformScreenManager,
+    window::PointerConstraint,
+};
+
+fn new_context() -> ContextRef {
+    Context::new(ContextOptions {
+        on_last_engine_removed: Box::new(|_| {}),
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+fn create_window(context: &ContextRef) -> (WindowHandle, EngineHandle, NullEngine) {
+    let window = context
+        .window_manager
+        .borrow_mut()
+        .create_window(Value::Null, None)
+        .unwrap();
+    run_until_idle(context);
+    let engine_handle = context
+        .window_manager
+        .borrow()
+        .get_engine_for_window(window)
+        .unwrap();
+    let engine = context
+        .engine_manager
+        .borrow()
+        .get_null_engine(engine_handle)
+        .unwrap();
+    (window, engine_handle, engine)
+}
+
+fn window_method(
+    engine: &NullEngine,
+    window: WindowHandle,
+    method: &str,
+    args: Value,
+) -> MethodCallResult<Value> {
+    engine
+        .invoke_window_method(window, channel::win::WINDOW_MANAGER, method, args)
+        .unwrap()
+}
+
+// Returns names of window events broadcasted to engine
+fn window_events(engine: &NullEngine) -> Vec<String> {
+    engine
+        .take_window_messages()
+        .into_iter()
+        .map(|m| m.message)
+        .collect()
+}
+
+fn run_until_idle(context: &ContextRef) {
+    context.run_loop.borrow().platform_run_loop.run_until_idle();
+}
+
+//
+// Managers
+//
+
+#[test]
+fn test_window_lifecycle() {
+    let context = new_context();
+    let (window, engine_handle, engine) = create_window(&context);
+    assert!(engine.is_launched());
+    let platform_window = context
+        .window_manager
+        .borrow()
+        .get_platform_window(window)
+        .unwrap();
+
+    let init = window_method(
+        &engine,
+        window,
+        method::window_manager::INIT_WINDOW,
+        Value::Null,
+    )
+    .unwrap();
+    match init {
+        Value::Map(map) => {
+            assert_eq!(map.get(&"currentWindow".into()), Some(&window.0.into()));
+        }
+        _ => panic!("invalid init response"),
+    }
+    assert_eq!(window_events(&engine), vec!["event:Window.initialize"]);
+
+    window_method(&engine, window, method::window::SHOW, Value::Null).unwrap();
+    assert!(!platform_window.is_visible());
+    window_method(&engine, window, method::window::READY_TO_SHOW, Value::Null).unwrap();
+    assert!(platform_window.is_visible());
+    assert_eq!(
+        engine.take_window_messages(),
+        vec![WindowMessage {
+            source_window_handle: window,
+            channel: channel::win::WINDOW_MANAGER.into(),
+            message: "event:Window.visibilityChanged".into(),
+            arguments: true.into(),
+        }]
+    );
+
+    window_method(&engine, window, method::window::SET_TITLE, "Hello".into()).unwrap();
+    assert_eq!(platform_window.title(), "Hello");
+
+    window_method(&engine, window, method::window::CLOSE, Value::Null).unwrap();
+    run_until_idle(&context);
+    assert!(context
+        .window_manager
+        .borrow()
+        .get_platform_window(window)
+        .is_none());
+    assert!(context
+        .engine_manager
+        .borrow()
+        .get_engine(engine_handle)
+        .is_none());
+}
+
+#[test]
+fn test_window_controller() {
+    let context = new_context();
+    let (window, _, engine) = create_window(&context);
+    let controller = context
+        .window_manager
+        .borrow()
+        .get_window_controller(window)
+        .unwrap();
+
+    let events = Rc::new(RefCell::new(Vec::<String>::new()));
+    let events_clone = events.clone();
+    let _handle = controller
+        .register_event_notification(move |event| {
+            events_clone.borrow_mut().push(match event {
+                WindowEvent::VisibilityChanged(visible) => format!("visible: {visible}"),
+                WindowEvent::GeometryChanged(_) => "geometry".into(),
+                WindowEvent::StateFlagsChanged(flags) => format!("active: {}", flags.active),
+                WindowEvent::ScreenChanged(screen) => format!("screen: {screen}"),
+                WindowEvent::ScaleFactorChanged(scale) => format!("scale: {scale}"),
+                WindowEvent::RelativePointerMotion(delta) => format!("motion: {delta:?}"),
+                WindowEvent::CloseRequest => "close request".into(),
+                WindowEvent::Close => "close".into(),
+            });
+        })
+        .unwrap();
+
+    window_method(&engine, window, method::window::READY_TO_SHOW, Value::Null).unwrap();
+    controller.show().unwrap();
+    assert!(controller.activate(false).unwrap());
+    assert!(controller.get_window_state_flags().unwrap().active);
+    controller.close_with_result("done".into()).unwrap();
+    run_until_idle(&context);
+    assert_eq!(
+        *events.borrow(),
+        vec!["visible: true", "active: true", "visible: false", "close"]
+    );
+    assert!(matches!(
+        controller.get_geometry(),
+        Err(Error::InvalidWindowHandle)
+    ));
+}
+
+#[test]
+fn test_session_save_restore() {
+    let context = new_context();
+    let (parent, _, _) = create_window(&context);
+    let child = context
+        .window_manager
+        .borrow_mut()
+        .create_window("child".into(), Some(parent))
+        .unwrap();
+    let controller = |handle| {
+        context
+            .window_manager
+            .borrow()
+            .get_window_controller(handle)
+            .unwrap()
+    };
+    controller(parent)
+        .set_geometry(WindowGeometryRequest {
+            geometry: WindowGeometry {
+                frame_origin: Some(Point::xy(1800.0, 100.0)),
+                ..Default::default()
+            },
+            preference: GeometryPreference::PreferFrame,
+        })
+        .unwrap();
+    controller(child)
+        .set_geometry(WindowGeometryRequest {
+            geometry: WindowGeometry {
+                frame_origin: Some(Point::xy(200.0, 200.0)),
+                frame_size: Some(Size::wh(300.0, 200.0)),
+                ..Default::default()
+            },
+            preference: GeometryPreference::PreferFrame,
+        })
+        .unwrap();
+    controller(child).set_maximized(true).unwrap();
+    assert_eq!(
+        controller(child).get_geometry().unwrap().frame_size,
+        Some(Size::wh(1920.0, 1080.0))
+    );
+
+    let session = context
+        .session_manager
+        .borrow()
+        .save_session_to_string()
+        .unwrap();
+    let restored = context
+        .session_manager
+        .borrow()
+        .restore_session_from_string(&session)
+        .unwrap();
+    assert_eq!(restored.len(), 2);
+
+    let window_manager = context.window_manager.borrow();
+    let restored_child = window_manager.get_window(restored[1]).unwrap();
+    assert_eq!(restored_child.parent, Some(restored[0]));
+    assert_eq!(restored_child.init_data, "child".into());
+    assert_eq!(
+        controller(restored[1])
+            .get_window_state_flags()
+            .unwrap()
+            .maximized,
+        BoolTransition::Yes
+    );
+
+    // Normal frame is saved for maximized window
+    controller(restored[1]).set_maximized(false).unwrap();
+    let geometry = controller(restored[1]).get_geometry().unwrap();
+    assert_eq!(geometry.frame_origin, Some(Point::xy(200.0, 200.0)));
+    assert_eq!(geometry.frame_size, Some(Size::wh(300.0, 200.0)));
+
+    // Frame is moved back to screen
+    let geometry = controller(restored[0]).get_geometry().unwrap();
+    assert_eq!(geometry.frame_origin, Some(Point::xy(1520.0, 100.0)));
+}
+
+#[test]
+fn test_screens_changed() {
+    let context = new_context();
+    let (window, _, engine) = create_window(&context);
+    window_method(
+        &engine,
+        window,
+        method::window_manager::INIT_WINDOW,
+        Value::Null,
+    )
+    .unwrap();
+    engine
+        .invoke_method(
+            channel::SCREEN_MANAGER,
+            method::screen_manager::GET_SCREENS,
+            Value::Null,
+        )
+        .unwrap()
+        .unwrap();
+    engine.take_outgoing_messages();
+    engine.take_window_messages();
+
+    let screens = PlatformScreenManager::screens();
+    let mut main = screens[0].clone();
+    main.scaling_factor = 2.0;
+    let secondary = Screen {
+        id: 2,
+        frame: Rect::xywh(1920.0, 0.0, 1280.0, 1024.0),
+        work_area: Rect::xywh(1920.0, 0.0, 1280.0, 1024.0),
+        scaling_factor: 1.0,
+        ..Default::default()
+    };
+    context
+        .screen_manager
+        .borrow()
+        .borrow()
+        .platform_manager()
+        .set_screens(vec![main.clone(), secondary.clone()]);
+
+    let calls = engine.take_method_calls(channel::SCREEN_MANAGER);
+    assert_eq!(calls.len(), 1);
+    assert_eq!(
+        calls[0].call.args,
+        to_value(ScreensChanged {
+            added: vec![secondary],
+            removed: vec![],
+            changed: vec![main],
+        })
+        .unwrap()
+    );
+    assert_eq!(
+        window_events(&engine),
+        vec!["event:Window.scaleFactorChanged"]
+    );
+
+    window_method(
+        &engine,
+        window,
+        method::window::SET_GEOMETRY,
+        Value::Map(hash_map! {
+            "geometry".into(): Value::Map(hash_map! {
+                "frameOrigin".into(): Value::Map(hash_map! {
+                    "x".into(): 2000.0.into(),
+                    "y".into(): 100.0.into(),
+                }),
+            }),
+            "preference".into(): "preferFrame".into(),
+        }),
+    )
+    .unwrap();
+    assert_eq!(
+        window_events(&engine),
+        vec![
+            "event:Window.geometryChanged",
+            "event:Window.screenChanged",
+            "event:Window.scaleFactorChanged"
+        ]
+    );
+}
+
+#[test]
+fn test_window_moved_between_screens() {
+    let context = new_context();
+    let main = PlatformScreenManager::screens()[0].clone();
+    let secondary = Screen {
+        id: 2,
+        frame: Rect::xywh(1920.0, 0.0, 1280.0, 1024.0),
+        work_area: Rect::xywh(1920.0, 0.0, 1280.0, 1024.0),
+        scaling_factor: 2.0,
+        ..Default::default()
+    };
+    context
+        .screen_manager
+        .borrow()
+        .borrow()
+        .platform_manager()
+        .set_screens(vec![main.clone(), secondary]);
+
+    let (window, _, _) = create_window(&context);
+    let controller = context
+        .window_manager
+        .borrow()
+        .get_window_controller(window)
+        .unwrap();
+
+    let events = Rc::new(RefCell::new(Vec::<String>::new()));
+    let events_clone = events.clone();
+    let _handle = controller
+        .register_event_notification(move |event| match event {
+            WindowEvent::ScreenChanged(screen) => {
+                events_clone.borrow_mut().push(format!("screen: {screen}"))
+            }
+            WindowEvent::ScaleFactorChanged(scale) => {
+                events_clone.borrow_mut().push(format!("scale: {scale}"))
+            }
+            _ => {}
+        })
+        .unwrap();
+
+    let move_to = |x: f64| {
+        controller
+            .set_geometry(WindowGeometryRequest {
+                geometry: WindowGeometry {
+                    frame_origin: Some(Point::xy(x, 100.0)),
+                    ..Default::default()
+                },
+                preference: GeometryPreference::PreferFrame,
+            })
+            .unwrap();
+        events.take()
+    };
+
+    assert_eq!(move_to(2000.0), vec!["screen: 2", "scale: 2"]);
+    // moving within the same screen doesn't send any screen events
+    assert!(move_to(2100.0).is_empty());
+    assert_eq!(
+        move_to(100.0),
+        vec![
+            format!("screen: {}", main.id),
+            format!("scale: {}", main.scaling_factor)
+        ]
+    );
+}
+
+#[test]
+fn test_window_placement() {
+    let context = new_context();
+    let (parent, _, _) = create_window(&context);
+    let controller = |handle| {
+        context
+            .window_manager
+            .borrow()
+            .get_window_controller(handle)
+            .unwrap()
+    };
+    let frame = |handle| {
+        let geometry = controller(handle).get_geometry().unwrap();
+        Rect::origin_size(
+            &geometry.frame_origin.unwrap(),
+            &geometry.frame_size.unwrap(),
+        )
+    };
+    let create_child = || {
+        let window = context
+            .window_manager
+            .borrow_mut()
+            .create_window(Value::Null, Some(parent))
+            .unwrap();
+        let engine_handle = context
+            .window_manager
+            .borrow()
+            .get_engine_for_window(window)
+            .unwrap();
+        let engine = context
+            .engine_manager
+            .borrow()
+            .get_null_engine(engine_handle)
+            .unwrap();
+        (window, engine)
+    };
+    controller(parent)
+        .set_geometry(WindowGeometryRequest {
+            geometry: WindowGeometry {
+                frame_origin: Some(Point::xy(100.0, 100.0)),
+                frame_size: Some(Size::wh(800.0, 600.0)),
+                ..Default::default()
+            },
+            preference: GeometryPreference::PreferFrame,
+        })
+        .unwrap();
+
+    // Placement is deferred until window is shown
+    let (child, engine) = create_child();
+    window_method(
+        &engine,
+        child,
+        method::window::SET_PLACEMENT,
+        Value::Map(hash_map! {
+            "position".into(): "centerOnParent".into(),
+        }),
+    )
+    .unwrap();
+    controller(child).show().unwrap();
+    assert_eq!(frame(child).origin(), Point::xy(0.0, 0.0));
+    window_method(&engine, child, method::window::READY_TO_SHOW, Value::Null).unwrap();
+    assert_eq!(frame(child), Rect::xywh(300.0, 200.0, 400.0, 400.0));
+
+    let (cascaded, engine) = create_child();
+    window_method(
+        &engine,
+        cascaded,
+        method::window::READY_TO_SHOW,
+        Value::Null,
+    )
+    .unwrap();
+    controller(cascaded).show().unwrap();
+    controller(cascaded)
+        .set_placement(WindowPlacement {
+            position: WindowPosition::Cascade,
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(frame(cascaded).origin(), Point::xy(324.0, 224.0));
+
+    // Visible window is placed immediately
+    controller(cascaded)
+        .set_geometry(WindowGeometryRequest {
+            geometry: WindowGeometry {
+                frame_size: Some(Size::wh(2000.0, 500.0)),
+                ..Default::default()
+            },
+            preference: GeometryPreference::PreferFrame,
+        })
+        .unwrap();
+    controller(cascaded)
+        .set_placement(WindowPlacement {
+            position: WindowPosition::CenterOnScreen,
+            constrain_to_work_area: true,
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(frame(cascaded), Rect::xywh(0.0, 290.0, 1920.0, 500.0));
+}
+
+#[test]
+fn test_menu_action() {
+    let context = new_context();
+    let (_, _, engine) = create_window(&context);
+
+    let menu = engine
+        .invoke_method(
+            channel::MENU_MANAGER,
+            method::menu::CREATE_OR_UPDATE,
+            Value::Map(hash_map! {
+                "menu".into(): Value::Map(hash_map! {
+                    "items".into(): Value::List(vec![Value::Map(hash_map! {
+                        "id".into(): 10i64.into(),
+                        "title".into(): "Quit".into(),
+                        "enabled".into(): true.into(),
+                        "separator".into(): false.into(),
+                        "checkStatus".into(): "none".into(),
+                    })]),
+                }),
+            }),
+        )
+        .unwrap()
+        .unwrap();
+    let menu: MenuHandle = from_value(&menu).unwrap();
+
+    engine
+        .invoke_method(
+            channel::MENU_MANAGER,
+            method::menu::SET_APP_MENU,
+            Value::Map(hash_map! { "handle".into(): menu.0.into() }),
+        )
+        .unwrap()
+        .unwrap();
+
+    let platform_menu = context
+        .menu_manager
+        .borrow()
+        .borrow()
+        .get_platform_menu(menu)
+        .unwrap();
+    assert_eq!(platform_menu.items(), vec![(10, "Quit".to_string())]);
+    let app_menu = context
+        .menu_manager
+        .borrow()
+        .borrow()
+        .get_platform_menu_manager()
+        .app_menu()
+        .unwrap();
+    assert_eq!(app_menu.handle(), menu);
+
+    engine.take_outgoing_messages();
+    app_menu.select_item(10);
+    let calls = engine.take_method_calls(channel::MENU_MANAGER);
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].call.method, method::menu::ON_ACTION);
+    assert_eq!(
+        calls[0].call.args,
+        Value::Map(hash_map! {
+            "handle".into(): menu.0.into(),
+            "id".into(): 10i64.into(),
+        })
+    );
+}
+
+#[test]
+fn test_status_item_action() {
+    let context = new_context();
+    let (_, _, engine) = create_window(&context);
+
+    let item = engine
+        .invoke_method(
+            channel::STATUS_ITEM_MANAGER,
+            method::status_item::CREATE,
+            Value::Map(Default::default()),
+        )
+        .unwrap()
+        .unwrap();
+    engine
+        .invoke_method(
+            channel::STATUS_ITEM_MANAGER,
+            method::status_item::SET_HINT,
+            Value::Map(hash_map! {
+                "handle".into(): item.clone(),
+                "hint".into(): "Tooltip".into(),
+            }),
+        )
+        .unwrap()
+        .unwrap();
+
+    let platform_item = context
+        .status_item_manager
+        .borrow()
+        .borrow()
+        .get_platform_status_item(from_value(&item).unwrap())
+        .unwrap();
+    assert_eq!(platform_item.hint(), "Tooltip");
+
+    engine.take_outgoing_messages();
+    platform_item.perform_action(StatusItemActionType::LeftMouseUp, Point::xy(5.0, 5.0));
+    let calls = engine.take_method_calls(channel::STATUS_ITEM_MANAGER);
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].call.method, method::status_item::ON_ACTION);
+}
+
+//
+// Channel handlers
+//
+
+const TEST_CHANNEL: &str = "nativeshell/test";
+
+#[derive(Default)]
+struct EchoHandler {
+    provider: Option<MethodInvokerProvider>,
+    responses: Rc<RefCell<Vec<MethodCallResult<Value>>>>,
+}
+
+impl MethodCallHandler for EchoHandler {
+    fn on_method_call(
+        &mut self,
+        call: MethodCall<Value>,
+        reply: MethodCallReply<Value>,
+        engine: EngineHandle,
+    ) {
+        match call.method.as_str() {
+            "echo" => reply.send_ok(call.args),
+            "callBack" => {
+                let responses = self.responses.clone();
+                self.provider
+                    .as_ref()
+                    .unwrap()
+                    .get_method_invoker_for_engine(engine)
+                    .call_method("ping", call.args, move |res| {
+                        responses.borrow_mut().push(res);
+                    })
+                    .unwrap();
+                reply.send_ok(Value::Null);
+            }
+            _ => reply.send_error("unknown", Some("Unknown method"), Value::Null),
+        }
+    }
+
+    fn assign_invoker_provider(&mut self, provider: MethodInvokerProvider) {
+        self.provider = Some(provider);
+    }
+}
+
+#[test]
+fn test_method_call_handler() {
+    let context = new_context();
+    let (_, _, engine) = create_window(&context);
+    let responses = Rc::new(RefCell::new(Vec::new()));
+    let _handler = EchoHandler {
+        provider: None,
+        responses: responses.clone(),
+    }
+    .register(context.weak(), TEST_CHANNEL);
+
+    assert_eq!(
+        engine
+            .invoke_method(TEST_CHANNEL, "echo", 42i64.into())
+            .unwrap(),
+        Ok(42i64.into())
+    );
+    assert_eq!(
+        engine
+            .invoke_method(TEST_CHANNEL, "foo", Value::Null)
+            .unwrap(),
+        Err(MethodCallError {
+            code: "unknown".into(),
+            message: Some("Unknown method".into()),
+            details: Value::Null,
+        })
+    );
+
+    engine
+        .invoke_method(TEST_CHANNEL, "callBack", "hello".into())
+        .unwrap()
+        .unwrap();
+    let mut calls = engine.take_method_calls(TEST_CHANNEL);
+    assert_eq!(calls.len(), 1);
+    let call = calls.remove(0);
+    assert_eq!(call.call.method, "ping");
+    assert_eq!(call.call.args, "hello".into());
+    assert!(responses.borrow().is_empty());
+    call.reply(Ok("pong".into()));
+    assert_eq!(*responses.borrow(), vec![Ok("pong".into())]);
+}
+
+#[derive(Default)]
+struct CounterEventChannel {
+    sinks: Vec<EventSink>,
+    unregistered: Vec<i64>,
+}
+
+impl CounterEventChannel {
+    fn send(&self, value: i64) {
+        for sink in &self.sinks {
+            sink.send_message(&value.into()).unwrap();
+        }
+    }
+}
+
+impl EventChannelHandler for CounterEventChannel {
+    fn register_event_sink(&mut self, sink: EventSink, _listen_argument: Value) {
+        self.sinks.push(sink);
+    }
+
+    fn unregister_event_sink(&mut self, sink_id: i64) {
+        self.sinks.retain(|s| s.id() != sink_id);
+        self.unregistered.push(sink_id);
+    }
+}
+
+#[test]
+fn test_event_channel() {
+    let context = new_context();
+    let (_, _, engine) = create_window(&context);
+    let channel = CounterEventChannel::default().register(context.weak(), TEST_CHANNEL);
+
+    channel.borrow().send(1);
+    assert!(engine.take_events(TEST_CHANNEL).is_empty());
+
+    engine.listen(TEST_CHANNEL, Value::Null).unwrap().unwrap();
+    channel.borrow().send(2);
+    channel.borrow().send(3);
+    assert_eq!(
+        engine.take_events(TEST_CHANNEL),
+        vec![Ok(2i64.into()), Ok(3i64.into())]
+    );
+
+    engine.cancel(TEST_CHANNEL).unwrap().unwrap();
+    assert_eq!(channel.borrow().unregistered, vec![1]);
+    channel.borrow().send(4);
+    assert!(engine.take_events(TEST_CHANNEL).is_empty());
+}
+
+#[derive(Default)]
+struct AsyncHandler {
+    invoker: Option<AsyncMethodInvoker>,
+}
+
+#[async_trait(?Send)]
+impl AsyncMethodCallHandler for AsyncHandler {
+    async fn on_method_call(
+        &self,
+        call: MethodCall<Value>,
+        engine: EngineHandle,
+    ) -> MethodCallResult<Value> {
+        let invoker = self.invoker.clone().unwrap();
+        let res = invoker.call_method(engine, "ask", call.args).await.unwrap();
+        Ok(Value::List(vec![res, "done".into()]))
+    }
+
+    fn assign_invoker(&mut self, invoker: AsyncMethodInvoker) {
+        self.invoker = Some(invoker);
+    }
+}
+
+#[test]
+fn test_async_method_call_handler() {
+    let context = new_context();
+    let (_, _, engine) = create_window(&context);
+    let _handler = AsyncHandler::default().register(context.weak(), TEST_CHANNEL);
+
+    let reply = engine.invoke_method(TEST_CHANNEL, "start", "question".into());
+    run_until_idle(&context);
+    assert!(!reply.is_ready());
+
+    let mut calls = engine.take_method_calls(TEST_CHANNEL);
+    assert_eq!(calls.len(), 1);
+    let call = calls.remove(0);
+    assert_eq!(call.call.method, "ask");
+    assert_eq!(call.call.args, "question".into());
+    call.reply(Ok("answer".into()));
+
+    run_until_idle(&context);
+    assert_eq!(
+        reply.unwrap(),
+        Ok(Value::List(vec!["answer".into(), "done".into()]))
+    );
+}
+
+#[test]
+fn test_window_method_channel() {
+    let context = new_context();
+    let (window, _, engine) = create_window(&context);
+    context
+        .window_method_channel
+        .borrow_mut()
+        .register_method_handler(TEST_CHANNEL, |call, reply, _engine| {
+            reply.send(Ok(Value::List(vec![
+                call.target_window_handle.0.into(),
+                call.method.into(),
+                call.arguments,
+            ])));
+        });
+
+    assert_eq!(
+        engine
+            .invoke_window_method(window, TEST_CHANNEL, "hello", 1i64.into())
+            .unwrap(),
+        Ok(Value::List(vec![
+            window.0.into(),
+            "hello".into(),
+            1i64.into()
+        ]))
+    );
+
+    // calling Dart code of the window
+    let invoker = context
+        .window_method_channel
+        .borrow()
+        .get_method_invoker(&context.window_manager.borrow(), window, "dartChannel")
+        .unwrap();
+    let result = Rc::new(RefCell::new(None));
+    let result_clone = result.clone();
+    invoker
+        .call_method("getValue", Value::Null, move |res| {
+            result_clone.replace(Some(res));
+        })
+        .unwrap();
+    let mut calls = engine.take_window_method_calls();
+    assert_eq!(calls.len(), 1);
+    let call = calls.remove(0);
+    assert_eq!(call.call.target_window_handle, window);
+    assert_eq!(call.call.channel, "dartChannel");
+    assert_eq!(call.call.method, "getValue");
+    call.reply(Ok(10i64.into()));
+    assert_eq!(result.take(), Some(Ok(10i64.into())));
+}
+
+#[derive(Default)]
+struct RecordingAppDelegate {
+    events: Vec<String>,
+}
+
+impl ApplicationDelegate for RecordingAppDelegate {
+    fn application_should_terminate(
+        &mut self,
+        _termination: AppTermination,
+    ) -> ApplicationTerminateReply {
+        self.events.push("should_terminate".into());
+        ApplicationTerminateReply::Later
+    }
+
+    fn application_will_terminate(&mut self) {
+        self.events.push("will_terminate".into());
+    }
+
+    fn application_session_will_end(&mut self, reason: SessionEndReason) {
+        self.events.push(format!("session_will_end {:?}", reason));
+    }
+}
+
+#[test]
+fn test_application_delegate_termination() {
+    let context = new_context();
+    let (_, _, engine) = create_window(&context);
+    let delegate = Rc::new(RefCell::new(RecordingAppDelegate::default()));
+    context
+        .application_delegate_manager
+        .borrow()
+        .set_delegate_ref(delegate.clone());
+
+    context
+        .application_delegate_manager
+        .borrow()
+        .simulate_session_end(SessionEndReason::Logout);
+    run_until_idle(&context);
+    assert_eq!(
+        delegate.borrow().events,
+        vec!["session_will_end Logout", "should_terminate"]
+    );
+    assert!(engine.is_launched());
+    assert!(context
+        .application_delegate_manager
+        .borrow()
+        .is_session_end_blocked());
+
+    // delegate decided not to terminate
+    AppTermination::terminate_reply(false);
+    run_until_idle(&context);
+    assert!(engine.is_launched());
+    assert!(!context
+        .application_delegate_manager
+        .borrow()
+        .is_session_end_blocked());
+
+    context
+        .application_delegate_manager
+        .borrow()
+        .simulate_session_end(SessionEndReason::Logout);
+    run_until_idle(&context);
+
+    // delegate finished pending work
+    AppTermination::terminate_reply(true);
+    run_until_idle(&context);
+    assert_eq!(
+        delegate.borrow().events.last().map(|s| s.as_str()),
+        Some("will_terminate")
+    );
+    assert!(!engine.is_launched());
+}
+
+#[test]
+fn test_secondary_instance_launched() {
+    let context = new_context();
+    let (_, _, engine) = create_window(&context);
+    engine
+        .invoke_method(
+            channel::APPLICATION_MANAGER,
+            method::application_manager::INIT,
+            Value::Null,
+        )
+        .unwrap()
+        .unwrap();
+
+    context
+        .application_delegate_manager
+        .borrow()
+        .simulate_secondary_instance_launched(
+            &["--flag".into(), "file.txt".into()],
+            Some(Path::new("/home/user")),
+        );
+    let calls = engine.take_method_calls(channel::APPLICATION_MANAGER);
+    assert_eq!(calls.len(), 1);
+    assert_eq!(
+        calls[0].call.method,
+        method::application_manager::ON_SECONDARY_INSTANCE_LAUNCHED
+    );
+    assert_eq!(
+        calls[0].call.args,
+        Value::Map(hash_map! {
+            "arguments".into(): Value::List(vec!["--flag".into(), "file.txt".into()]),
+            "workingDirectory".into(): "/home/user".into(),
+        })
+    );
+}
+
+#[test]
+fn test_clipboard_round_trip() {
+    let context = new_context();
+    let (_, _, engine) = create_window(&context);
+
+    let data = Value::Map(hash_map! {
+        "properties".into(): Value::Map(hash_map! {
+            "drag-data:internal:text".into(): "Hello".into(),
+        }),
+    });
+    engine
+        .invoke_method(
+            channel::CLIPBOARD_MANAGER,
+            method::clipboard_manager::WRITE_DATA,
+            Value::Map(hash_map! {
+                "selection".into(): "primary".into(),
+                "data".into(): data.clone(),
+            }),
+        )
+        .unwrap()
+        .unwrap();
+
+    let read = |selection: &str| {
+        engine
+            .invoke_method(
+                channel::CLIPBOARD_MANAGER,
+                method::clipboard_manager::READ_DATA,
+                Value::Map(hash_map! {
+                    "selection".into(): selection.into(),
+                }),
+            )
+            .unwrap()
+            .unwrap()
+    };
+    assert_eq!(read("primary"), data);
+    assert_eq!(
+        read("clipboard"),
+        Value::Map(hash_map! {
+            "properties".into(): Value::Map(Default::default()),
+        })
+    );
+}
+
+#[test]
+fn test_file_dialog() {
+    let context = new_context();
+    let (window, _, engine) = create_window(&context);
+
+    let reply = engine.invoke_method(
+        channel::DIALOG_MANAGER,
+        method::dialog_manager::SHOW_OPEN_DIALOG,
+        Value::Map(hash_map! {
+            "parentWindow".into(): to_value(window).unwrap(),
+            "filters".into(): Value::List(vec![Value::Map(hash_map! {
+                "name".into(): "Text".into(),
+                "extensions".into(): Value::List(vec!["txt".into()]),
+            })]),
+            "multiSelect".into(): true.into(),
+        }),
+    );
+
+    let manager = context.dialog_manager.borrow().borrow().platform_manager();
+    let (dialog_type, request) = manager.pending_file_dialog().unwrap();
+    assert_eq!(dialog_type, FileDialogType::Open);
+    assert!(request.multi_select);
+    assert_eq!(request.filters[0].extensions, vec!["txt".to_string()]);
+    assert!(!reply.is_ready());
+
+    manager.complete_file_dialog(Some(vec!["/tmp/a.txt".into()]));
+    assert_eq!(reply.unwrap(), Ok(Value::List(vec!["/tmp/a.txt".into()])));
+}
+
+#[test]
+fn test_window_alert() {
+    let context = new_context();
+    let (window, _, engine) = create_window(&context);
+    let platform_window = context
+        .window_manager
+        .borrow()
+        .get_platform_window(window)
+        .unwrap();
+
+    let reply = engine.invoke_window_method(
+        window,
+        channel::win::WINDOW_MANAGER,
+        method::window::SHOW_ALERT,
+        Value::Map(hash_map! {
+            "message".into(): "Save changes?".into(),
+            "buttons".into(): Value::List(vec!["Save".into(), "Cancel".into()]),
+            "cancelButton".into(): 1i64.into(),
+            "suppressionText".into(): "Don't ask again".into(),
+        }),
+    );
+
+    let request = platform_window.pending_alert().unwrap();
+    assert_eq!(request.message, "Save changes?");
+    assert_eq!(request.cancel_button, Some(1));
+    assert!(!reply.is_ready());
+
+    // Dismissing without a button resolves to the cancel button
+    platform_window.complete_alert(None, true);
+    assert_eq!(
+        reply.unwrap(),
+        Ok(Value::Map(hash_map! {
+            "button".into(): 1i64.into(),
+            "suppressed".into(): true.into(),
+        }))
+    );
+}
+
+#[test]
+fn test_window_type_and_urgency() {
+    let context = new_context();
+    let (window, _, engine) = create_window(&context);
+    let platform_window = context
+        .window_manager
+        .borrow()
+        .get_platform_window(window)
+        .unwrap();
+
+    let reply = engine.invoke_window_method(
+        window,
+        channel::win::WINDOW_MANAGER,
+        method::window::SET_STYLE,
+        Value::Map(hash_map! {
+            "frame".into(): "noTitle".into(),
+            "canResize".into(): false.into(),
+            "canClose".into(): true.into(),
+            "canMinimize".into(): false.into(),
+            "canMaximize".into(): false.into(),
+            "canFullScreen".into(): false.into(),
+            "alwaysOnTop".into(): true.into(),
+            "windowType".into(): "utility".into(),
+            "skipTaskbar".into(): true.into(),
+        }),
+    );
+    assert_eq!(reply.unwrap(), Ok(Value::Null));
+    let style = platform_window.style();
+    assert_eq!(style.window_type, WindowType::Utility);
+    assert!(style.skip_taskbar);
+    assert!(!style.skip_pager);
+    assert!(!style.sticky);
+
+    let reply = engine.invoke_window_method(
+        window,
+        channel::win::WINDOW_MANAGER,
+        method::window::SET_URGENT,
+        true.into(),
+    );
+    assert_eq!(reply.unwrap(), Ok(Value::Null));
+    assert!(platform_window.is_urgent());
+
+    // Activating the window clears urgency
+    engine.invoke_window_method(
+        window,
+        channel::win::WINDOW_MANAGER,
+        method::window::ACTIVATE,
+        Value::Map(hash_map! {
+            "activateApplication".into(): false.into(),
+        }),
+    );
+    assert!(!platform_window.is_urgent());
+}
+
+#[test]
+fn test_window_create_options() {
+    let context = new_context();
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let events_clone = events.clone();
+    let _handle = context
+        .window_manager
+        .borrow_mut()
+        .register_window_event_notification(move |_, event| {
+            events_clone.borrow_mut().push(format!("{:?}", event));
+        });
+
+    // Changing frame style notifies geometry change synchronously; this must
+    // not happen while window manager is borrowed
+    let window = context
+        .window_manager
+        .borrow_mut()
+        .create_window_with_options(
+            Value::Null,
+            None,
+            WindowCreateOptions {
+                style: Some(WindowStyle {
+                    frame: WindowFrame::NoTitle,
+                    ..Default::default()
+                }),
+            },
+        )
+        .unwrap();
+    run_until_idle(&context);
+
+    let platform_window = context
+        .window_manager
+        .borrow()
+        .get_platform_window(window)
+        .unwrap();
+    assert_eq!(platform_window.style().frame, WindowFrame::NoTitle);
+    assert!(events.borrow()[0].starts_with("GeometryChanged"));
+}
+
+#[test]
+fn test_window_drag_regions() {
+    let context = new_context();
+    let (window, _, engine) = create_window(&context);
+    let platform_window = context
+        .window_manager
+        .borrow()
+        .get_platform_window(window)
+        .unwrap();
+
+    let rect = |x: f64, y: f64, width: f64, height: f64| {
+        Value::Map(hash_map! {
+            "x".into(): x.into(),
+            "y".into(): y.into(),
+            "width".into(): width.into(),
+            "height".into(): height.into(),
+        })
+    };
+    let reply = engine.invoke_window_method(
+        window,
+        channel::win::WINDOW_MANAGER,
+        method::window::SET_DRAG_REGIONS,
+        Value::Map(hash_map! {
+            "draggable".into(): Value::List(vec![rect(0.0, 0.0, 400.0, 30.0)]),
+            "noDrag".into(): Value::List(vec![rect(360.0, 0.0, 40.0, 30.0)]),
+        }),
+    );
+    assert_eq!(reply.unwrap(), Ok(Value::Null));
+
+    let regions = platform_window.drag_regions();
+    assert!(regions.is_draggable(&Point::xy(10.0, 10.0)));
+    assert!(!regions.is_draggable(&Point::xy(370.0, 10.0)));
+    assert!(!regions.is_draggable(&Point::xy(10.0, 40.0)));
+}
+
+#[test]
+fn test_window_input_region() {
+    let context = new_context();
+    let (window, _, engine) = create_window(&context);
+    let platform_window = context
+        .window_manager
+        .borrow()
+        .get_platform_window(window)
+        .unwrap();
+
+    let reply = engine.invoke_window_method(
+        window,
+        channel::win::WINDOW_MANAGER,
+        method::window::SET_INPUT_REGION,
+        Value::List(vec![Value::Map(hash_map! {
+            "x".into(): 10.0.into(),
+            "y".into(): 20.0.into(),
+            "width".into(): 100.0.into(),
+            "height".into(): 50.0.into(),
+        })]),
+    );
+    assert_eq!(reply.unwrap(), Ok(Value::Null));
+    assert_eq!(
+        platform_window.input_region(),
+        Some(vec![Rect::xywh(10.0, 20.0, 100.0, 50.0)])
+    );
+
+    engine.invoke_window_method(
+        window,
+        channel::win::WINDOW_MANAGER,
+        method::window::SET_INPUT_REGION,
+        Value::Null,
+    );
+    assert_eq!(platform_window.input_region(), None);
+
+    let reply = engine.invoke_window_method(
+        window,
+        channel::win::WINDOW_MANAGER,
+        method::window::SET_IGNORE_MOUSE_EVENTS,
+        Value::Map(hash_map! {
+            "ignore".into(): true.into(),
+            "forwardMotion".into(): true.into(),
+        }),
+    );
+    assert_eq!(reply.unwrap(), Ok(Value::Null));
+    assert_eq!(platform_window.ignore_mouse_events(), (true, true));
+}
+
+#[test]
+fn test_window_icon_and_application_id() {
+    let context = new_context();
+    let (window, _, engine) = create_window(&context);
+    let platform_window = context
+        .window_manager
+        .borrow()
+        .get_platform_window(window)
+        .unwrap();
+
+    let reply = engine.invoke_window_method(
+        window,
+        channel::win::WINDOW_MANAGER,
+        method::window::SET_ICON,
+        Value::List(vec![Value::Map(hash_map! {
+            "width".into(): 2i64.into(),
+            "height".into(): 1i64.into(),
+            "bytesPerRow".into(): 8i64.into(),
+            "data".into(): Value::U8List(vec![255; 8]),
+        })]),
+    );
+    assert_eq!(reply.unwrap(), Ok(Value::Null));
+    let icon = platform_window.icon();
+    assert_eq!(icon.len(), 1);
+    assert_eq!((icon[0].width, icon[0].height), (2, 1));
+
+    let reply = engine.invoke_window_method(
+        window,
+        channel::win::WINDOW_MANAGER,
+        method::window::SET_APPLICATION_ID,
+        "dev.nativeshell.example".into(),
+    );
+    assert_eq!(reply.unwrap(), Ok(Value::Null));
+    assert_eq!(
+        platform_window.application_id(),
+        Some("dev.nativeshell.example".into())
+    );
+}
+
+#[test]
+fn test_window_capture_image() {
+    let context = new_context();
+    let (window, _, engine) = create_window(&context);
+
+    let image = context
+        .window_manager
+        .borrow()
+        .capture_window_image(window)
+        .unwrap();
+    assert_eq!(
+        (image.width, image.height, image.bytes_per_row),
+        (400, 400, 1600)
+    );
+    assert_eq!(image.data.len(), 400 * 1600);
+
+    let reply = window_method(&engine, window, method::window::CAPTURE_IMAGE, Value::Null);
+    let image: ImageData = from_value(&reply.unwrap()).unwrap();
+    assert_eq!(image.width, 400);
+}
+
+#[test]
+fn test_window_pointer_lock() {
+    let context = new_context();
+    let (window, _, engine) = create_window(&context);
+    let platform_window = context
+        .window_manager
+        .borrow()
+        .get_platform_window(window)
+        .unwrap();
+    window_method(
+        &engine,
+        window,
+        method::window_manager::INIT_WINDOW,
+        Value::Null,
+    )
+    .unwrap();
+    engine.take_window_messages();
+
+    // Motion is only reported while locked
+    platform_window.simulate_pointer_motion(Point::xy(1.0, 1.0));
+    assert!(window_events(&engine).is_empty());
+
+    window_method(&engine, window, method::window::LOCK_POINTER, Value::Null).unwrap();
+    assert_eq!(
+        platform_window.pointer_constraint(),
+        Some(PointerConstraint::Lock)
+    );
+    platform_window.simulate_pointer_motion(Point::xy(5.0, -3.0));
+    let messages = engine.take_window_messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].message, "event:Window.relativePointerMotion");
+    let delta: Point = from_value(&messages[0].arguments).unwrap();
+    assert_eq!(delta, Point::xy(5.0, -3.0));
+
+    let rect = to_value(Rect::xywh(300.0, 300.0, 200.0, 200.0)).unwrap();
+    window_method(&engine, window, method::window::CONFINE_POINTER, rect).unwrap();
+    assert_eq!(
+        platform_window.pointer_constraint(),
+        Some(PointerConstraint::Confine(Rect::xywh(
+            300.0, 300.0, 100.0, 100.0
+        )))
+    );
+    platform_window.simulate_pointer_motion(Point::xy(1.0, 1.0));
+    assert!(window_events(&engine).is_empty());
+
+    let rect = to_value(Rect::xywh(500.0, 500.0, 10.0, 10.0)).unwrap();
+    assert!(window_method(&engine, window, method::window::CONFINE_POINTER, rect).is_err());
+
+    window_method(
+        &engine,
+        window,
+        method::window::CONFINE_POINTER,
+        Value::Null,
+    )
+    .unwrap();
+    assert_eq!(
+        platform_window.pointer_constraint(),
+        Some(PointerConstraint::Confine(Rect::xywh(
+            0.0, 0.0, 400.0, 400.0
+        )))
+    );
+
+    window_method(
+        &engine,
+        window,
+        method::window::RELEASE_POINTER,
+        Value::Null,
+    )
+    .unwrap();
+    assert_eq!(platform_window.pointer_constraint(), None);
+}
+
+#[test]
+fn test_hide_pointer_while_typing() {
+    let context = new_context();
+    let (window, _, engine) = create_window(&context);
+    let platform_window = context
+        .window_manager
+        .borrow()
+        .get_platform_window(window)
+        .unwrap();
+
+    platform_window.simulate_key_press();
+    assert!(!platform_window.pointer_hidden());
+
+    window_method(
+        &engine,
+        window,
+        method::window::SET_HIDE_POINTER_WHILE_TYPING,
+        Value::Bool(true),
+    )
+    .unwrap();
+    platform_window.simulate_key_press();
+    assert!(platform_window.pointer_hidden());
+    platform_window.simulate_pointer_motion(Point::xy(1.0, 1.0));
+    assert!(!platform_window.pointer_hidden());
+
+    platform_window.simulate_key_press();
+    window_method(
+        &engine,
+        window,
+        method::window::SET_HIDE_POINTER_WHILE_TYPING,
+        Value::Bool(false),
+    )
+    .unwrap();
+    assert!(!platform_window.pointer_hidden());
+}
+
+#[test]
+fn test_notifications() {
+    let context = new_context();
+    let (window, _, engine) = create_window(&context);
+
+    let show = |replace: Option<&Value>| {
+        let mut args = hash_map! {
+            "title".into(): "Download finished".into(),
+            "actions".into(): Value::List(vec![Value::Map(hash_map! {
+                "id".into(): "open".into(),
+                "label".into(): "Open".into(),
+            })]),
+        };
+        if let Some(replace) = replace {
+            args.insert("replace".into(), replace.clone());
+        }
+        engine
+            .invoke_method(
+                channel::NOTIFICATION_MANAGER,
+                method::notification::SHOW,
+                Value::Map(args),
+            )
+            .unwrap()
+            .unwrap()
+    };
+
+    let notification = show(None);
+    assert_eq!(show(Some(&notification)), notification);
+    let handle: NotificationHandle = from_value(&notification).unwrap();
+    let manager = context
+        .notification_manager
+        .borrow()
+        .borrow()
+        .platform_manager();
+    assert_eq!(manager.notification(handle).unwrap().actions[0].id, "open");
+
+    engine.take_outgoing_messages();
+    manager.activate_notification(handle, Some("open".into()));
+    manager.dismiss_notification(handle);
+    let calls = engine.take_method_calls(channel::NOTIFICATION_MANAGER);
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].call.method, method::notification::ON_ACTIVATED);
+    assert_eq!(
+        calls[0].call.args,
+        Value::Map(hash_map! {
+            "handle".into(): notification.clone(),
+            "action".into(): "open".into(),
+        })
+    );
+    assert_eq!(calls[1].call.method, method::notification::ON_CLOSED);
+
+    // Only engine that posted the notification can replace or close it
+    let notification = show(None);
+    let (_, _, other_engine) = create_window(&context);
+    let replace = Value::Map(hash_map! {
+        "title".into(): "Download finished".into(),
+        "replace".into(): notification.clone(),
+    });
+    let close = Value::Map(hash_map! {
+        "handle".into(): notification.clone(),
+    });
+    for (method, args) in [
+        (method::notification::SHOW, replace),
+        (method::notification::CLOSE, close.clone()),
+    ] {
+        assert!(other_engine
+            .invoke_method(channel::NOTIFICATION_MANAGER, method, args)
+            .unwrap()
+            .is_err());
+    }
+
+    // Explicitly closed notification is reported as closed
+    engine
+        .invoke_method(
+            channel::NOTIFICATION_MANAGER,
+            method::notification::CLOSE,
+            close,
+        )
+        .unwrap()
+        .unwrap();
+    let calls = engine.take_method_calls(channel::NOTIFICATION_MANAGER);
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].call.method, method::notification::ON_CLOSED);
+    assert_eq!(
+        calls[0].call.args,
+        Value::Map(hash_map! {
+            "handle".into(): notification,
+            "reason".into(): "closed".into(),
+        })
+    );
+
+    // Notifications are removed together with engine that posted them
+    let handle: NotificationHandle = from_value(&show(None)).unwrap();
+    assert!(manager.notification(handle).is_some());
+    window_method(&engine, window, method::window::CLOSE, Value::Null).unwrap();
+    run_until_idle(&context);
+    assert!(manager.notification(handle).is_none());
+}
+
+#[test]
+fn test_pointer_manager() {
+    let context = new_context();
+    let (_, _, engine) = create_window(&context);
+    let modifiers = PointerModifiers {
+        shift: true,
+        ..Default::default()
+    };
+    context
+        .pointer_manager
+        .borrow()
+        .borrow()
+        .platform_manager()
+        .set_pointer_state(PointerState {
+            position: Point::xy(100.0, 200.0),
+            buttons: POINTER_BUTTON_PRIMARY,
+            modifiers: modifiers.clone(),
+            ..Default::default()
+        });
+    let state = engine
+        .invoke_method(
+            channel::POINTER_MANAGER,
+            method::pointer_manager::GET_POINTER_STATE,
+            Value::Null,
+        )
+        .unwrap()
+        .unwrap();
+    let state: PointerState = from_value(&state).unwrap();
+    assert_eq!(state.position, Point::xy(100.0, 200.0));
+    assert_eq!(state.screen_id, Some(1));
+    assert_eq!(state.buttons, POINTER_BUTTON_PRIMARY);
+    assert_eq!(state.modifiers, modifiers);
+
+    let is_monitoring = || {
+        context
+            .pointer_manager
+            .borrow()
+            .borrow()
+            .platform_manager()
+            .is_monitoring()
+    };
+    assert!(!is_monitoring());
+    engine
+        .listen(channel::POINTER_EVENTS, Value::Null)
+        .unwrap()
+        .unwrap();
+    assert!(is_monitoring());
+
+    let event = GlobalPointerEvent {
+        event_type: GlobalPointerEventType::ButtonDown,
+        position: Point::xy(10.0, 20.0),
+        screen_id: None,
+        button: POINTER_BUTTON_SECONDARY,
+        buttons: POINTER_BUTTON_SECONDARY,
+        modifiers: Default::default(),
+    };
+    context
+        .pointer_manager
+        .borrow()
+        .borrow()
+        .platform_manager()
+        .simulate_event(event.clone());
+    assert_eq!(
+        engine.take_events(channel::POINTER_EVENTS),
+        vec![Ok(to_value(GlobalPointerEvent {
+            screen_id: Some(1),
+            ..event
+        })
+        .unwrap())]
+    );
+
+    engine.cancel(channel::POINTER_EVENTS).unwrap().unwrap();
+    assert!(!is_monitoring());
+}
@@ -1,155 +1,684 @@
-use std::rc::{Rc, Weak};
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+};
 
 use crate::{
     codec::Value,
     shell::{
         api_model::{
-            DragEffect, DragRequest, PopupMenuRequest, PopupMenuResponse, WindowCollectionBehavior,
-            WindowGeometry, WindowGeometryFlags, WindowGeometryRequest, WindowStateFlags,
-            WindowStyle,
+            AlertRequest, AlertResponse, BoolTransition, DragEffect, DragRequest, ImageData,
+            PopupMenuRequest, PopupMenuResponse, WindowCollectionBehavior, WindowCreateOptions,
+            WindowDragRegions, WindowEdge, WindowGeometry, WindowGeometryFlags,
+            WindowGeometryRequest, WindowStateFlags, WindowStyle,
         },
-        Context, PlatformWindowDelegate,
+        Context, PlatformWindowDelegate, Point, Rect, Size,
     },
+    util::{LateRefCell, OkLog},
 };
 
 use super::{
     engine::PlatformEngine,
     error::{PlatformError, PlatformResult},
     menu::PlatformMenu,
+    screen_manager::PlatformScreenManager,
 };
 
-pub type PlatformWindowType = isize;
+pub type PlatformWindowType = Rc<PlatformWindow>;
+
+type AlertCallback = Box<dyn FnOnce(PlatformResult<AlertResponse>)>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PointerConstraint {
+    Lock,
+    // Region in content coordinates
+    Confine(Rect),
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq)]
+struct WindowState {
+    origin: Point,
+    size: Size,
+    is_minimized: bool,
+    is_maximized: bool,
+    is_full_screen: bool,
+    is_active: bool,
+}
+
+impl Default for WindowState {
+    fn default() -> Self {
+        Self {
+            origin: Point::xy(0.0, 0.0),
+            size: Size::wh(400.0, 400.0),
+            is_minimized: false,
+            is_maximized: false,
+            is_full_screen: false,
+            is_active: false,
+        }
+    }
+}
 
-pub struct PlatformWindow {}
+// Window without any native counterpart. Frame and content are the same
+// (there are no decorations) and all state changes take effect immediately.
+pub struct PlatformWindow {
+    context: Context,
+    weak_self: LateRefCell<Weak<PlatformWindow>>,
+    parent: Option<Weak<PlatformWindow>>,
+    delegate: Weak<dyn PlatformWindowDelegate>,
+    modal_close_callback: RefCell<Option<Box<dyn FnOnce(PlatformResult<Value>)>>>,
+    ready_to_show: Cell<bool>,
+    show_when_ready: Cell<bool>,
+    visible: Cell<bool>,
+    modal: Cell<bool>,
+    closing: Cell<bool>,
+    title: RefCell<String>,
+    state: RefCell<WindowState>,
+    min_size: RefCell<Option<Size>>,
+    max_size: RefCell<Option<Size>>,
+    style: RefCell<WindowStyle>,
+    collection_behavior: RefCell<WindowCollectionBehavior>,
+    urgent: Cell<bool>,
+    drag_regions: RefCell<WindowDragRegions>,
+    ignore_mouse_events: Cell<(bool, bool)>,
+    input_region: RefCell<Option<Vec<Rect>>>,
+    pointer_constraint: RefCell<Option<PointerConstraint>>,
+    // Frame before window was maximized
+    restored_frame: RefCell<Option<Rect>>,
+    hide_pointer_while_typing: Cell<bool>,
+    pointer_hidden: Cell<bool>,
+    icon: RefCell<Vec<ImageData>>,
+    application_id: RefCell<Option<String>>,
+    window_menu: RefCell<Option<Rc<PlatformMenu>>>,
+    pending_effect: Cell<DragEffect>,
+    pending_alert: RefCell<Option<(AlertRequest, AlertCallback)>>,
+}
 
-#[allow(unused_variables)]
 impl PlatformWindow {
     pub fn new(
         context: Context,
         delegate: Weak<dyn PlatformWindowDelegate>,
         parent: Option<Rc<PlatformWindow>>,
+        _options: &WindowCreateOptions,
     ) -> Self {
-        Self {}
+        Self {
+            context,
+            weak_self: LateRefCell::new(),
+            parent: parent.map(|p| Rc::downgrade(&p)),
+            delegate,
+            modal_close_callback: RefCell::new(None),
+            ready_to_show: Cell::new(false),
+            show_when_ready: Cell::new(false),
+            visible: Cell::new(false),
+            modal: Cell::new(false),
+            closing: Cell::new(false),
+            title: RefCell::new(String::new()),
+            state: RefCell::new(Default::default()),
+            min_size: RefCell::new(None),
+            max_size: RefCell::new(None),
+            style: RefCell::new(Default::default()),
+            collection_behavior: RefCell::new(Default::default()),
+            urgent: Cell::new(false),
+            drag_regions: RefCell::new(Default::default()),
+            ignore_mouse_events: Cell::new((false, false)),
+            input_region: RefCell::new(None),
+            pointer_constraint: RefCell::new(None),
+            restored_frame: RefCell::new(None),
+            hide_pointer_while_typing: Cell::new(false),
+            pointer_hidden: Cell::new(false),
+            icon: RefCell::new(Vec::new()),
+            application_id: RefCell::new(None),
+            window_menu: RefCell::new(None),
+            pending_effect: Cell::new(DragEffect::None),
+            pending_alert: RefCell::new(None),
+        }
     }
 
-    pub fn assign_weak_self(&self, weak: Weak<PlatformWindow>, engine: &PlatformEngine) {}
+    pub fn assign_weak_self(&self, weak: Weak<PlatformWindow>, _engine: &PlatformEngine) {
+        self.weak_self.set(weak);
+    }
 
     pub fn get_platform_window(&self) -> PlatformWindowType {
-        Default::default()
+        self.weak_self.borrow().upgrade().unwrap()
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible.get()
+    }
+
+    pub fn is_modal(&self) -> bool {
+        self.modal.get()
+    }
+
+    pub fn title(&self) -> String {
+        self.title.borrow().clone()
+    }
+
+    pub fn style(&self) -> WindowStyle {
+        self.style.borrow().clone()
+    }
+
+    pub fn collection_behavior(&self) -> WindowCollectionBehavior {
+        self.collection_behavior.borrow().clone()
+    }
+
+    pub fn is_urgent(&self) -> bool {
+        self.urgent.get()
+    }
+
+    pub fn drag_regions(&self) -> WindowDragRegions {
+        self.drag_regions.borrow().clone()
+    }
+
+    // Returns (ignore, forward_motion)
+    pub fn ignore_mouse_events(&self) -> (bool, bool) {
+        self.ignore_mouse_events.get()
+    }
+
+    pub fn input_region(&self) -> Option<Vec<Rect>> {
+        self.input_region.borrow().clone()
+    }
+
+    pub fn pointer_constraint(&self) -> Option<PointerConstraint> {
+        self.pointer_constraint.borrow().clone()
+    }
+
+    // Whether cursor is hidden after key press
+    pub fn pointer_hidden(&self) -> bool {
+        self.pointer_hidden.get()
+    }
+
+    pub fn icon(&self) -> Vec<ImageData> {
+        self.icon.borrow().clone()
+    }
+
+    pub fn application_id(&self) -> Option<String> {
+        self.application_id.borrow().clone()
+    }
+
+    pub fn window_menu(&self) -> Option<Rc<PlatformMenu>> {
+        self.window_menu.borrow().clone()
+    }
+
+    pub fn pending_effect(&self) -> DragEffect {
+        self.pending_effect.get()
+    }
+
+    pub fn parent(&self) -> Option<Rc<PlatformWindow>> {
+        self.parent.as_ref().and_then(|p| p.upgrade())
+    }
+
+    fn set_visible(&self, visible: bool) {
+        if self.visible.replace(visible) != visible {
+            if let Some(delegate) = self.delegate.upgrade() {
+                delegate.visibility_changed(visible);
+            }
+        }
+    }
+
+    fn update_state<F>(&self, f: F)
+    where
+        F: FnOnce(&mut WindowState),
+    {
+        let changed = {
+            let mut state = self.state.borrow_mut();
+            let prev_state = state.clone();
+            f(&mut state);
+            *state != prev_state
+        };
+        if changed {
+            if let Some(delegate) = self.delegate.upgrade() {
+                delegate.state_flags_changed();
+            }
+        }
+    }
+
+    // Simulates user clicking the close button
+    pub fn request_close(&self) {
+        if let Some(delegate) = self.delegate.upgrade() {
+            delegate.did_request_close();
+        }
+    }
+
+    // Simulates key press; Hides pointer if hide while typing is enabled
+    pub fn simulate_key_press(&self) {
+        if self.hide_pointer_while_typing.get() {
+            self.pointer_hidden.set(true);
+        }
+    }
+
+    // Simulates pointer movement; Only reported while pointer is locked
+    pub fn simulate_pointer_motion(&self, delta: Point) {
+        self.pointer_hidden.set(false);
+        let locked = matches!(
+            *self.pointer_constraint.borrow(),
+            Some(PointerConstraint::Lock)
+        );
+        if locked {
+            if let Some(delegate) = self.delegate.upgrade() {
+                delegate.relative_pointer_motion(delta);
+            }
+        }
     }
 
     pub fn show(&self) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        if self.ready_to_show.get() {
+            self.set_visible(true);
+        } else {
+            self.show_when_ready.set(true);
+        }
+        Ok(())
     }
 
     pub fn ready_to_show(&self) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        self.ready_to_show.set(true);
+        if self.show_when_ready.get() {
+            self.set_visible(true);
+        }
+        Ok(())
     }
 
     pub fn close(&self) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        if self.closing.replace(true) {
+            return Ok(());
+        }
+        // Like on actual platforms the window is closed asynchronously
+        let weak = self.weak_self.borrow().clone();
+        if let Some(context) = self.context.get() {
+            context
+                .run_loop
+                .borrow()
+                .schedule_now(move || {
+                    if let Some(s) = weak.upgrade() {
+                        s.on_close();
+                    }
+                })
+                .detach();
+        }
+        Ok(())
+    }
+
+    fn on_close(&self) {
+        self.pointer_constraint.replace(None);
+        let callback = self.modal_close_callback.borrow_mut().take();
+        if let Some(callback) = callback {
+            callback(Ok(Value::Null));
+        }
+        self.set_visible(false);
+        if let Some(delegate) = self.delegate.upgrade() {
+            delegate.will_close();
+        }
     }
 
     pub fn close_with_result(&self, result: Value) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        let callback = self.modal_close_callback.borrow_mut().take();
+        if let Some(callback) = callback {
+            callback(Ok(result));
+        }
+        self.close()
     }
 
     pub fn hide(&self) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        self.pointer_constraint.replace(None);
+        if self.ready_to_show.get() {
+            self.set_visible(false);
+        } else {
+            self.show_when_ready.set(false);
+        }
+        Ok(())
     }
 
-    pub fn activate(&self, activate_application: bool) -> PlatformResult<bool> {
-        Err(PlatformError::NotImplemented)
+    pub fn activate(&self, _activate_application: bool) -> PlatformResult<bool> {
+        self.update_state(|s| s.is_active = true);
+        self.urgent.set(false);
+        Ok(true)
     }
 
-    pub fn deactivate(&self, deactivate_application: bool) -> PlatformResult<bool> {
-        Err(PlatformError::NotImplemented)
+    pub fn deactivate(&self, _deactivate_application: bool) -> PlatformResult<bool> {
+        self.update_state(|s| s.is_active = false);
+        Ok(true)
     }
 
     pub fn show_modal<F>(&self, done_callback: F)
     where
         F: FnOnce(PlatformResult<Value>) + 'static,
     {
-        done_callback(Err(PlatformError::NotImplemented))
+        self.modal_close_callback
+            .borrow_mut()
+            .replace(Box::new(done_callback));
+        self.modal.set(true);
+        self.show().ok_log();
     }
 
     pub fn set_geometry(
         &self,
         geometry: WindowGeometryRequest,
     ) -> PlatformResult<WindowGeometryFlags> {
-        Err(PlatformError::NotImplemented)
+        let geometry = geometry.filtered_by_preference();
+
+        let origin = geometry.frame_origin.or(geometry.content_origin);
+        let size = geometry.frame_size.or(geometry.content_size);
+        let min_size = geometry.min_frame_size.or(geometry.min_content_size);
+        let max_size = geometry.max_frame_size.or(geometry.max_content_size);
+
+        if min_size.is_some() {
+            self.min_size.replace(min_size);
+        }
+        if max_size.is_some() {
+            self.max_size.replace(max_size);
+        }
+
+        let changed = {
+            let mut state = self.state.borrow_mut();
+            let prev_state = state.clone();
+            if let Some(origin) = origin {
+                state.origin = origin;
+            }
+            if let Some(size) = size {
+                state.size = size;
+            }
+            state.size = self.constrain_size(&state.size);
+            *state != prev_state
+        };
+
+        if changed {
+            if let Some(delegate) = self.delegate.upgrade() {
+                delegate.geometry_changed();
+            }
+        }
+
+        self.supported_geometry()
+    }
+
+    fn constrain_size(&self, size: &Size) -> Size {
+        let mut size = size.clone();
+        if let Some(min_size) = self.min_size.borrow().as_ref() {
+            size.width = size.width.max(min_size.width);
+            size.height = size.height.max(min_size.height);
+        }
+        if let Some(max_size) = self.max_size.borrow().as_ref() {
+            size.width = size.width.min(max_size.width);
+            size.height = size.height.min(max_size.height);
+        }
+        size
     }
 
     pub fn get_geometry(&self) -> PlatformResult<WindowGeometry> {
-        Err(PlatformError::NotImplemented)
+        let state = self.state.borrow();
+        Ok(WindowGeometry {
+            frame_origin: Some(state.origin.clone()),
+            frame_size: Some(state.size.clone()),
+            content_origin: Some(state.origin.clone()),
+            content_size: Some(state.size.clone()),
+            min_frame_size: self.min_size.borrow().clone(),
+            max_frame_size: self.max_size.borrow().clone(),
+            min_content_size: self.min_size.borrow().clone(),
+            max_content_size: self.max_size.borrow().clone(),
+        })
+    }
+
+    pub fn get_normal_frame(&self) -> PlatformResult<Option<Rect>> {
+        Ok(self.restored_frame.borrow().clone())
     }
 
     pub fn supported_geometry(&self) -> PlatformResult<WindowGeometryFlags> {
-        Err(PlatformError::NotImplemented)
+        Ok(WindowGeometryFlags {
+            frame_origin: true,
+            frame_size: true,
+            content_origin: true,
+            content_size: true,
+            min_frame_size: true,
+            max_frame_size: true,
+            min_content_size: true,
+            max_content_size: true,
+        })
     }
 
     pub fn get_screen_id(&self) -> PlatformResult<i64> {
-        Err(PlatformError::NotImplemented)
+        let origin = self.state.borrow().origin.clone();
+        Ok(PlatformScreenManager::screen_for_point(&origin)
+            .map(|s| s.id)
+            .unwrap_or(0))
     }
 
     pub fn set_title(&self, title: String) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        self.title.replace(title);
+        Ok(())
+    }
+
+    pub fn set_icon(&self, images: Vec<ImageData>) -> PlatformResult<()> {
+        self.icon.replace(images);
+        Ok(())
+    }
+
+    pub fn set_application_id(&self, application_id: String) -> PlatformResult<()> {
+        self.application_id.replace(Some(application_id));
+        Ok(())
+    }
+
+    // There is no rendering; Returns transparent image of content size
+    pub fn capture_image(&self) -> PlatformResult<ImageData> {
+        let size = self.state.borrow().size.clone();
+        let (width, height) = (size.width as i32, size.height as i32);
+        Ok(ImageData {
+            width,
+            height,
+            bytes_per_row: width * 4,
+            data: vec![0; (width * height * 4) as usize],
+        })
+    }
+
+    pub fn lock_pointer(&self) -> PlatformResult<()> {
+        self.pointer_constraint
+            .replace(Some(PointerConstraint::Lock));
+        Ok(())
+    }
+
+    // Region is limited to window content
+    pub fn confine_pointer(&self, rect: Option<Rect>) -> PlatformResult<()> {
+        let content = Rect::origin_size(&Point::xy(0.0, 0.0), &self.state.borrow().size);
+        let region = match rect {
+            Some(rect) => rect
+                .intersect(&content)
+                .ok_or(PlatformError::UnknownError)?,
+            None => content,
+        };
+        self.pointer_constraint
+            .replace(Some(PointerConstraint::Confine(region)));
+        Ok(())
+    }
+
+    pub fn release_pointer(&self) -> PlatformResult<()> {
+        self.pointer_constraint.replace(None);
+        Ok(())
+    }
+
+    pub fn set_hide_pointer_while_typing(&self, hide: bool) -> PlatformResult<()> {
+        self.hide_pointer_while_typing.set(hide);
+        if !hide {
+            self.pointer_hidden.set(false);
+        }
+        Ok(())
     }
 
     pub fn set_collection_behavior(
         &self,
         behavior: WindowCollectionBehavior,
     ) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        self.collection_behavior.replace(behavior);
+        Ok(())
+    }
+
+    pub fn set_urgent(&self, urgent: bool) -> PlatformResult<()> {
+        self.urgent.set(urgent);
+        Ok(())
     }
 
     pub fn set_minimized(&self, minimized: bool) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        self.update_state(|s| s.is_minimized = minimized);
+        Ok(())
     }
 
+    // Maximized window fills work area of its screen
     pub fn set_maximized(&self, maximized: bool) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        if self.state.borrow().is_maximized == maximized {
+            return Ok(());
+        }
+        let frame = if maximized {
+            let state = self.state.borrow();
+            self.restored_frame
+                .replace(Some(Rect::origin_size(&state.origin, &state.size)));
+            PlatformScreenManager::screen_for_point(&state.origin).map(|s| s.work_area)
+        } else {
+            self.restored_frame.take()
+        };
+        self.update_state(|s| {
+            s.is_maximized = maximized;
+            if let Some(frame) = &frame {
+                s.origin = frame.origin();
+                s.size = frame.size();
+            }
+        });
+        if frame.is_some() {
+            if let Some(delegate) = self.delegate.upgrade() {
+                delegate.geometry_changed();
+            }
+        }
+        Ok(())
     }
 
     pub fn set_full_screen(&self, full_screen: bool) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        self.update_state(|s| s.is_full_screen = full_screen);
+        Ok(())
     }
 
     pub fn get_window_state_flags(&self) -> PlatformResult<WindowStateFlags> {
-        Err(PlatformError::NotImplemented)
+        let state = self.state.borrow();
+        let transition = |value: bool| {
+            if value {
+                BoolTransition::Yes
+            } else {
+                BoolTransition::No
+            }
+        };
+        Ok(WindowStateFlags {
+            maximized: transition(state.is_maximized),
+            minimized: transition(state.is_minimized),
+            full_screen: transition(state.is_full_screen),
+            active: state.is_active,
+        })
     }
 
     pub fn save_position_to_string(&self) -> PlatformResult<String> {
-        Ok(String::new())
+        let state = self.state.borrow();
+        Ok(serde_json::to_string(&*state).unwrap())
     }
 
     pub fn restore_position_from_string(&self, position: String) -> PlatformResult<()> {
+        let state: WindowState =
+            serde_json::from_str(&position).map_err(|_| PlatformError::UnknownError)?;
+        let geometry_changed = {
+            let current = self.state.borrow();
+            current.origin != state.origin || current.size != state.size
+        };
+        self.update_state(|s| *s = state);
+        if geometry_changed {
+            if let Some(delegate) = self.delegate.upgrade() {
+                delegate.geometry_changed();
+            }
+        }
         Ok(())
     }
 
     pub fn set_style(&self, style: WindowStyle) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        let frame_changed = self.style.borrow().frame != style.frame;
+        self.style.replace(style);
+        // Like on macOS changing frame resizes the window synchronously
+        if frame_changed {
+            if let Some(delegate) = self.delegate.upgrade() {
+                delegate.geometry_changed();
+            }
+        }
+        Ok(())
     }
 
     pub fn perform_window_drag(&self) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        Ok(())
+    }
+
+    pub fn perform_window_resize(&self, _edge: WindowEdge) -> PlatformResult<()> {
+        Ok(())
+    }
+
+    pub fn set_drag_regions(&self, regions: WindowDragRegions) -> PlatformResult<()> {
+        self.drag_regions.replace(regions);
+        Ok(())
     }
 
-    pub fn begin_drag_session(&self, request: DragRequest) -> PlatformResult<()> {
+    pub fn set_ignore_mouse_events(
+        &self,
+        ignore: bool,
+        forward_motion: bool,
+    ) -> PlatformResult<()> {
+        self.ignore_mouse_events.set((ignore, forward_motion));
+        Ok(())
+    }
+
+    pub fn set_input_region(&self, region: Option<Vec<Rect>>) -> PlatformResult<()> {
+        self.input_region.replace(region);
+        Ok(())
+    }
+
+    pub fn begin_drag_session(&self, _request: DragRequest) -> PlatformResult<()> {
         Err(PlatformError::NotImplemented)
     }
 
-    pub fn set_pending_effect(&self, effect: DragEffect) {}
+    pub fn set_pending_effect(&self, effect: DragEffect) {
+        self.pending_effect.set(effect);
+    }
 
-    pub fn show_popup_menu<F>(&self, menu: Rc<PlatformMenu>, request: PopupMenuRequest, on_done: F)
+    pub fn show_popup_menu<F>(&self, menu: Rc<PlatformMenu>, _request: PopupMenuRequest, on_done: F)
     where
         F: FnOnce(PlatformResult<PopupMenuResponse>) + 'static,
     {
-        on_done(Err(PlatformError::NotImplemented))
+        menu.open();
+        on_done(Ok(PopupMenuResponse {
+            item_selected: false,
+        }))
     }
 
-    pub fn hide_popup_menu(&self, menu: Rc<PlatformMenu>) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+    // Alert stays open until `complete_alert` is called
+    pub fn show_alert<F>(&self, request: AlertRequest, on_done: F)
+    where
+        F: FnOnce(PlatformResult<AlertResponse>) + 'static,
+    {
+        if self.pending_alert.borrow().is_some() {
+            // Window already shows an alert
+            on_done(Err(PlatformError::UnknownError));
+            return;
+        }
+        self.pending_alert
+            .borrow_mut()
+            .replace((request, Box::new(on_done)));
+    }
+
+    pub fn pending_alert(&self) -> Option<AlertRequest> {
+        self.pending_alert.borrow().as_ref().map(|a| a.0.clone())
+    }
+
+    // Simulates user closing the alert; None button means the alert was
+    // dismissed (i.e. Escape pressed)
+    pub fn complete_alert(&self, button: Option<usize>, suppressed: bool) {
+        let alert = self.pending_alert.take();
+        if let Some((request, on_done)) = alert {
+            on_done(Ok(AlertResponse {
+                button: button.or(request.cancel_button),
+                suppressed,
+            }));
+        }
+    }
+
+    pub fn hide_popup_menu(&self, _menu: Rc<PlatformMenu>) -> PlatformResult<()> {
+        Ok(())
     }
 
     pub fn show_system_menu(&self) -> PlatformResult<()> {
@@ -157,6 +686,7 @@ impl PlatformWindow {
     }
 
     pub fn set_window_menu(&self, menu: Option<Rc<PlatformMenu>>) -> PlatformResult<()> {
-        Err(PlatformError::NotImplemented)
+        self.window_menu.replace(menu);
+        Ok(())
     }
 }
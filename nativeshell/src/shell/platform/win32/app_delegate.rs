@@ -1,15 +1,277 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    path::Path,
+    rc::{Rc, Weak},
+    time::Duration,
+};
 
-use crate::shell::ContextRef;
+use url::Url;
+use windows::Win32::{
+    Foundation::{
+        CloseHandle, GetLastError, BOOL, ERROR_ALREADY_EXISTS, HANDLE, HWND, LPARAM, LRESULT,
+        PWSTR, WPARAM,
+    },
+    System::{
+        DataExchange::COPYDATASTRUCT,
+        Shutdown::{ShutdownBlockReasonCreate, ShutdownBlockReasonDestroy},
+        Threading::CreateMutexW,
+    },
+    UI::WindowsAndMessaging::{
+        DestroyWindow, FindWindowW, SendMessageW, ENDSESSION_LOGOFF, WINDOW_EX_STYLE, WINDOW_STYLE,
+        WM_ACTIVATEAPP, WM_COPYDATA, WM_ENDSESSION, WM_QUERYENDSESSION,
+    },
+};
 
-pub trait ApplicationDelegate {}
+use crate::shell::{api_model::SecondaryInstanceLaunched, app_delegate::DelegateState, ContextRef};
 
-pub struct ApplicationDelegateManager {}
+use super::window_adapter::WindowAdapter;
+
+pub use crate::shell::app_delegate::{
+    AppTermination, ApplicationDelegate, ApplicationTerminateReply, SessionEndReason,
+};
+
+const FIND_WINDOW_ATTEMPTS: usize = 50;
+const FIND_WINDOW_INTERVAL: Duration = Duration::from_millis(100);
+
+// Hidden top level window; Receives application activation and session end
+// messages, and command line forwarded by secondary instances.
+struct MessageWindow {
+    hwnd: Cell<HWND>,
+    state: Weak<DelegateState>,
+}
+
+impl MessageWindow {
+    fn new(title: &str, state: Weak<DelegateState>) -> Box<Self> {
+        let res = Box::new(Self {
+            hwnd: Cell::new(HWND(0)),
+            state,
+        });
+        res.hwnd
+            .set(res.create_window_custom(title, WINDOW_STYLE(0), WINDOW_EX_STYLE(0)));
+        res
+    }
+
+    fn on_activate_app(&self, active: bool) {
+        if let Some(state) = self.state.upgrade() {
+            if active {
+                state.with_delegate(|d| d.application_did_become_active());
+            } else {
+                state.with_delegate(|d| d.application_did_resign_active());
+            }
+        }
+    }
+
+    // Returns whether session may end; While termination is cancelled or
+    // pending the reason is shown to user.
+    fn on_query_end_session(&self, l_param: LPARAM) -> bool {
+        let state = match self.state.upgrade() {
+            Some(state) => state,
+            None => return true,
+        };
+        let reason = if l_param.0 as u32 & ENDSESSION_LOGOFF != 0 {
+            SessionEndReason::Logout
+        } else {
+            SessionEndReason::Shutdown
+        };
+        state.session_will_end(reason);
+        let reason = match state.request_terminate() {
+            ApplicationTerminateReply::DelegateToSuper | ApplicationTerminateReply::Now => {
+                return true;
+            }
+            ApplicationTerminateReply::Cancel => "Application cancelled logout",
+            ApplicationTerminateReply::Later => "Application is finishing pending work",
+        };
+        unsafe { ShutdownBlockReasonCreate(self.hwnd.get(), reason) };
+        false
+    }
+
+    fn on_copy_data(&self, l_param: LPARAM) {
+        let data = unsafe {
+            let data = &*(l_param.0 as *const COPYDATASTRUCT);
+            std::slice::from_raw_parts(data.lpData as *const u8, data.cbData as usize)
+        };
+        let event = serde_json::from_slice::<SecondaryInstanceLaunched>(data);
+        if let (Ok(event), Some(state)) = (event, self.state.upgrade()) {
+            let working_directory = event.working_directory.as_ref().map(Path::new);
+            state.secondary_instance_launched(&event.arguments, working_directory);
+        }
+    }
+}
+
+impl WindowAdapter for MessageWindow {
+    fn wnd_proc(&self, h_wnd: HWND, msg: u32, w_param: WPARAM, l_param: LPARAM) -> LRESULT {
+        match msg {
+            WM_ACTIVATEAPP => {
+                self.on_activate_app(w_param.0 != 0);
+                LRESULT(0)
+            }
+            WM_QUERYENDSESSION => LRESULT(self.on_query_end_session(l_param) as isize),
+            WM_ENDSESSION => {
+                if w_param.0 == 0 {
+                    // Session end was cancelled
+                    unsafe { ShutdownBlockReasonDestroy(h_wnd) };
+                }
+                LRESULT(0)
+            }
+            WM_COPYDATA => {
+                self.on_copy_data(l_param);
+                LRESULT(1)
+            }
+            _ => self.default_wnd_proc(h_wnd, msg, w_param, l_param),
+        }
+    }
+}
+
+impl Drop for MessageWindow {
+    fn drop(&mut self) {
+        unsafe {
+            DestroyWindow(self.hwnd.get());
+        }
+    }
+}
+
+// Primary instance owns named mutex; Other instances forward their command
+// line to message window of the primary instance through WM_COPYDATA.
+struct SingleInstance {
+    mutex: HANDLE,
+}
+
+impl SingleInstance {
+    // Exits the process if this is a secondary instance.
+    fn new(app_namespace: &str, window_title: &str) -> Self {
+        let mutex_name = format!("Local\\nativeshell-{}", app_namespace);
+        let mutex = unsafe { CreateMutexW(std::ptr::null(), BOOL(0), mutex_name.as_str()) };
+        if unsafe { GetLastError() } == ERROR_ALREADY_EXISTS {
+            Self::notify_primary_instance(window_title);
+            std::process::exit(0);
+        }
+        Self { mutex }
+    }
+
+    // Primary instance creates its window after taking the mutex, so it may
+    // not exist yet when launched at the same time.
+    fn find_primary_window(window_title: &str) -> Option<HWND> {
+        for _ in 0..FIND_WINDOW_ATTEMPTS {
+            let hwnd = unsafe { FindWindowW(PWSTR::default(), window_title) };
+            if hwnd.0 != 0 {
+                return Some(hwnd);
+            }
+            std::thread::sleep(FIND_WINDOW_INTERVAL);
+        }
+        None
+    }
+
+    fn notify_primary_instance(window_title: &str) {
+        let hwnd = match Self::find_primary_window(window_title) {
+            Some(hwnd) => hwnd,
+            None => return,
+        };
+        let event = SecondaryInstanceLaunched {
+            arguments: std::env::args().skip(1).collect(),
+            working_directory: std::env::current_dir()
+                .ok()
+                .map(|d| d.to_string_lossy().into()),
+        };
+        let mut event = serde_json::to_vec(&event).unwrap();
+        let data = COPYDATASTRUCT {
+            dwData: 0,
+            cbData: event.len() as u32,
+            lpData: event.as_mut_ptr() as *mut _,
+        };
+        unsafe {
+            SendMessageW(
+                hwnd,
+                WM_COPYDATA,
+                WPARAM(0),
+                LPARAM(&data as *const _ as isize),
+            );
+        }
+    }
+}
+
+impl Drop for SingleInstance {
+    fn drop(&mut self) {
+        unsafe {
+            CloseHandle(self.mutex);
+        }
+    }
+}
+
+pub struct ApplicationDelegateManager {
+    state: Rc<DelegateState>,
+    _message_window: Box<MessageWindow>,
+    _single_instance: Option<SingleInstance>,
+}
 
 impl ApplicationDelegateManager {
-    pub fn new(_context: &ContextRef) -> Self {
-        Self {}
+    pub fn new(context: &ContextRef) -> Self {
+        let state = Rc::new(DelegateState::new(context.weak()));
+        let window_title = format!("nativeshell {}", context.options.app_namespace);
+        // Must be checked before creating message window, otherwise secondary
+        // instance could find its own window
+        let single_instance = if context.options.single_instance {
+            Some(SingleInstance::new(
+                &context.options.app_namespace,
+                &window_title,
+            ))
+        } else {
+            None
+        };
+        let message_window = MessageWindow::new(&window_title, Rc::downgrade(&state));
+        let hwnd = message_window.hwnd.get();
+        state.set_on_termination_resolved(move |_| unsafe {
+            ShutdownBlockReasonDestroy(hwnd);
+        });
+        // Delegate is usually set after context is created; Wait for run loop
+        // to start.
+        let weak = Rc::downgrade(&state);
+        context
+            .run_loop
+            .borrow()
+            .schedule_now(move || {
+                if let Some(state) = weak.upgrade() {
+                    state.open_urls(&command_line_urls());
+                }
+            })
+            .detach();
+        Self {
+            state,
+            _message_window: message_window,
+            _single_instance: single_instance,
+        }
+    }
+
+    pub fn set_delegate<D: ApplicationDelegate + 'static>(&self, delegate: D) {
+        self.state.set_delegate(Rc::new(RefCell::new(delegate)));
+    }
+
+    pub fn set_delegate_ref<D: ApplicationDelegate + 'static>(&self, delegate: Rc<RefCell<D>>) {
+        self.state.set_delegate(delegate);
+    }
+
+    // Asks delegate whether application should terminate and if so, shuts
+    // down engines and stops the run loop.
+    pub fn request_terminate(&self) {
+        self.state.request_terminate();
     }
-    pub fn set_delegate<D: ApplicationDelegate + 'static>(&self, _delegate: D) {}
-    pub fn set_delegate_ref<D: ApplicationDelegate + 'static>(&self, _delegate: Rc<RefCell<D>>) {}
+}
+
+// Files and URLs given on command line; Options are ignored.
+fn command_line_urls() -> Vec<Url> {
+    std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-'))
+        .filter_map(|arg| {
+            let path = Path::new(&arg);
+            if path.exists() {
+                path.canonicalize()
+                    .ok()
+                    .and_then(|p| Url::from_file_path(p).ok())
+            } else if arg.contains("://") {
+                Url::parse(&arg).ok()
+            } else {
+                None
+            }
+        })
+        .collect()
 }
@@ -0,0 +1,102 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use windows::Win32::System::{
+    Com::IDataObject,
+    Ole::{OleGetClipboard, OleSetClipboard},
+};
+
+use crate::shell::{
+    api_model::{ClipboardSelection, DragData},
+    Context, ContextRef,
+};
+
+use super::{
+    drag_com::DataObject,
+    drag_data::{
+        DragDataAdapter, FallThroughDragDataAdapter, FilesDragDataAdapter, TextDragDataAdapter,
+        UrlsDragDataAdapter,
+    },
+    error::{PlatformError, PlatformResult},
+};
+
+pub struct PlatformClipboard {
+    context: Context,
+    data_adapters: Vec<Box<dyn DragDataAdapter>>,
+    // DataObject only keeps weak reference to data; Data must be kept alive
+    // for as long as the object is on clipboard.
+    data: RefCell<Rc<RefCell<HashMap<u32, Vec<u8>>>>>,
+}
+
+impl PlatformClipboard {
+    pub fn new(context: Context) -> Self {
+        let mut data_adapters: Vec<Box<dyn DragDataAdapter>> = vec![
+            Box::new(TextDragDataAdapter::new()),
+            Box::new(FilesDragDataAdapter::new()),
+            Box::new(UrlsDragDataAdapter::new()),
+        ];
+        if let Some(context) = context.get() {
+            data_adapters.push(Box::new(FallThroughDragDataAdapter::new(&context.options)));
+        }
+        Self {
+            context,
+            data_adapters,
+            data: RefCell::new(Rc::new(RefCell::new(HashMap::new()))),
+        }
+    }
+
+    fn data_adapters<'a>(&'a self, context: &'a ContextRef) -> Vec<&'a dyn DragDataAdapter> {
+        context
+            .options
+            .custom_drag_data_adapters
+            .iter()
+            .chain(self.data_adapters.iter())
+            .map(|a| a.as_ref())
+            .collect()
+    }
+
+    fn check_selection(selection: ClipboardSelection) -> PlatformResult<()> {
+        match selection {
+            ClipboardSelection::Clipboard => Ok(()),
+            ClipboardSelection::Primary => Err(PlatformError::NotAvailable),
+        }
+    }
+
+    pub fn read_data<F>(self: &Rc<Self>, selection: ClipboardSelection, on_done: F)
+    where
+        F: FnOnce(PlatformResult<DragData>) + 'static,
+    {
+        let res = || -> PlatformResult<DragData> {
+            Self::check_selection(selection)?;
+            let context = self.context.get().ok_or(PlatformError::UnknownError)?;
+            let object = unsafe { OleGetClipboard() }?;
+            let mut res = DragData::default();
+            for adapter in self.data_adapters(&context) {
+                adapter.retrieve_drag_data(object.clone(), &mut res.properties);
+            }
+            Ok(res)
+        };
+        on_done(res());
+    }
+
+    pub fn write_data(&self, selection: ClipboardSelection, data: DragData) -> PlatformResult<()> {
+        Self::check_selection(selection)?;
+        let context = self.context.get().ok_or(PlatformError::UnknownError)?;
+        let mut properties = data.properties;
+        let mut res = HashMap::new();
+        for adapter in self.data_adapters(&context) {
+            adapter.prepare_drag_data(&mut properties, &mut res);
+        }
+        let data = Rc::new(RefCell::new(res));
+        let object: IDataObject = DataObject::new(Rc::downgrade(&data)).into();
+        unsafe { OleSetClipboard(object) }?;
+        self.data.replace(data);
+        Ok(())
+    }
+
+    pub fn clear(&self, selection: ClipboardSelection) -> PlatformResult<()> {
+        Self::check_selection(selection)?;
+        unsafe { OleSetClipboard(None::<IDataObject>) }?;
+        self.data.replace(Rc::new(RefCell::new(HashMap::new())));
+        Ok(())
+    }
+}
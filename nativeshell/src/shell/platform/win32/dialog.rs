@@ -0,0 +1,30 @@
+use crate::{
+    shell::api_model::{FileDialogRequest, FileDialogType},
+    Context,
+};
+
+use super::{
+    error::{PlatformError, PlatformResult},
+    window::PlatformWindowType,
+};
+
+pub struct PlatformDialogManager {}
+
+impl PlatformDialogManager {
+    pub fn new(_context: Context) -> Self {
+        Self {}
+    }
+
+    // TODO: Implement using IFileOpenDialog / IFileSaveDialog
+    pub fn show_file_dialog<F>(
+        &self,
+        _dialog_type: FileDialogType,
+        _request: FileDialogRequest,
+        _parent: Option<PlatformWindowType>,
+        on_done: F,
+    ) where
+        F: FnOnce(PlatformResult<Option<Vec<String>>>) + 'static,
+    {
+        on_done(Err(PlatformError::NotAvailable));
+    }
+}
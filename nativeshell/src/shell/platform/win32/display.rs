@@ -1,7 +1,9 @@
 use windows::Win32::{
-    Foundation::{BOOL, LPARAM, RECT},
+    Foundation::{BOOL, LPARAM, PWSTR, RECT},
     Graphics::Gdi::{
-        EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFO, MONITORINFOEXW,
+        EnumDisplayDevicesW, EnumDisplayMonitors, EnumDisplaySettingsW, GetMonitorInfoW, DEVMODEW,
+        DISPLAY_DEVICEW, ENUM_CURRENT_SETTINGS, HDC, HMONITOR, MONITORINFO, MONITORINFOEXW,
+        MONITORINFOF_PRIMARY,
     },
 };
 
@@ -30,6 +32,10 @@ pub struct Display {
     pub handle: HMONITOR,
     pub work: Rect,
     pub id: i64,
+    pub name: Option<String>,
+    pub refresh_rate: Option<f64>,
+    pub rotation: i32,
+    pub is_primary: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -76,6 +82,46 @@ impl Displays {
             }
             *s.name_to_id.get(&name).unwrap()
         });
+        let is_primary = monitor_info.dwFlags & MONITORINFOF_PRIMARY != 0;
+        let device = PWSTR(monitor_info_ex.szDevice.as_mut_ptr());
+
+        // Monitor name (i.e. "DELL U2415") is device string of the first
+        // device attached to the adapter
+        let mut display_device = DISPLAY_DEVICEW {
+            cb: mem::size_of::<DISPLAY_DEVICEW>() as u32,
+            ..Default::default()
+        };
+        let name = if unsafe { EnumDisplayDevicesW(device, 0, &mut display_device as *mut _, 0) }
+            .as_bool()
+        {
+            let name = &display_device.DeviceString;
+            let len = name.iter().position(|c| *c == 0).unwrap_or(name.len());
+            Some(String::from_utf16_lossy(&name[..len]))
+        } else {
+            None
+        };
+
+        let mut mode = DEVMODEW {
+            dmSize: mem::size_of::<DEVMODEW>() as u16,
+            ..Default::default()
+        };
+        let (refresh_rate, rotation) =
+            if unsafe { EnumDisplaySettingsW(device, ENUM_CURRENT_SETTINGS, &mut mode as *mut _) }
+                .as_bool()
+            {
+                // 0 and 1 mean default refresh rate
+                let refresh_rate = if mode.dmDisplayFrequency > 1 {
+                    Some(mode.dmDisplayFrequency as f64)
+                } else {
+                    None
+                };
+                // DMDO_DEFAULT, DMDO_90, DMDO_180, DMDO_270
+                let orientation = unsafe { mode.Anonymous1.Anonymous2.dmDisplayOrientation };
+                (refresh_rate, orientation as i32 * 90)
+            } else {
+                (None, 0)
+            };
+
         Display {
             physical,
             logical,
@@ -83,6 +129,10 @@ impl Displays {
             handle: d.original.handle,
             work,
             id,
+            name,
+            refresh_rate,
+            rotation,
+            is_primary,
         }
     }
 
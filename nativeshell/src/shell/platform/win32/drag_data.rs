@@ -4,7 +4,9 @@ use byte_slice_cast::AsByteSlice;
 use log::warn;
 use widestring::WideCString;
 use windows::Win32::System::{
-    Com::IDataObject, DataExchange::RegisterClipboardFormatW, SystemServices::CF_HDROP,
+    Com::IDataObject,
+    DataExchange::RegisterClipboardFormatW,
+    SystemServices::{CF_HDROP, CF_UNICODETEXT},
 };
 
 use crate::{
@@ -122,6 +124,40 @@ impl DragDataAdapter for UrlsDragDataAdapter {
     }
 }
 
+pub(super) struct TextDragDataAdapter {}
+
+impl TextDragDataAdapter {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl DragDataAdapter for TextDragDataAdapter {
+    fn retrieve_drag_data(&self, data: IDataObject, data_out: &mut HashMap<String, Value>) {
+        let text = DataUtil::get_data(data, CF_UNICODETEXT.0).map(|d| DataUtil::extract_url_w(&d));
+        if let Ok(text) = text {
+            data_out.insert(drag_data::key::TEXT.into(), Value::String(text));
+        }
+    }
+
+    fn prepare_drag_data(
+        &self,
+        data_in: &mut HashMap<String, Value>,
+        data_out: &mut HashMap<u32, Vec<u8>>,
+    ) {
+        match data_in.remove(drag_data::key::TEXT) {
+            Some(Value::String(text)) => {
+                let text = WideCString::from_str(text).unwrap();
+                let mut data = Vec::from(text.as_slice().as_byte_slice());
+                data.extend_from_slice(&[0, 0]);
+                data_out.insert(CF_UNICODETEXT.0, data);
+            }
+            Some(value) => panic!("Invalid value: {:?}, expected string", value),
+            None => {}
+        }
+    }
+}
+
 pub(super) struct FallThroughDragDataAdapter {
     format: u32,
 }
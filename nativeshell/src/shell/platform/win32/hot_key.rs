@@ -63,13 +63,16 @@ impl PlatformHotKeyManager {
         }
     }
 
-    pub fn create_hot_key(
+    pub fn create_hot_key<F>(
         &self,
         accelerator: Accelerator,
         virtual_key: i64,
         handle: HotKeyHandle,
         engine: EngineHandle,
-    ) -> PlatformResult<()> {
+        on_done: F,
+    ) where
+        F: FnOnce(PlatformResult<()>) + 'static,
+    {
         let mut modifiers = HOT_KEY_MODIFIERS::default();
         if accelerator.alt {
             modifiers |= MOD_ALT;
@@ -88,7 +91,7 @@ impl PlatformHotKeyManager {
             let vk = MapVirtualKeyW(virtual_key as u32, MAPVK_VSC_TO_VK);
             RegisterHotKey(self.hwnd(), handle.0 as i32, modifiers, vk);
         }
-        Ok(())
+        on_done(Ok(()));
     }
 
     pub fn destroy_hot_key(&self, handle: HotKeyHandle) -> PlatformResult<()> {
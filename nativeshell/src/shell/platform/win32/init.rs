@@ -6,6 +6,8 @@ use windows::Win32::System::{
     Ole::OleInitialize,
 };
 
+use crate::shell::ContextOptions;
+
 use super::{
     dpi::become_dpi_aware,
     dxgi_hook::init_dxgi_hook,
@@ -13,7 +15,7 @@ use super::{
     util::direct_composition_supported,
 };
 
-pub fn init_platform() -> PlatformResult<()> {
+pub fn init_platform(_options: &ContextOptions) -> PlatformResult<()> {
     unsafe {
         // Angle will try opening these with GetModuleHandleEx, which means they need to be
         // loaded first; Otherwise it falls back to d3dcompiler_47, which is not present on
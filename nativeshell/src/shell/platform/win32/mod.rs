@@ -1,5 +1,7 @@
 pub mod app_delegate;
 pub mod binary_messenger;
+pub mod clipboard;
+pub mod dialog;
 pub mod display;
 pub mod dpi;
 pub mod drag_com;
@@ -14,6 +16,8 @@ pub mod hot_key;
 pub mod init;
 pub mod keyboard_map;
 pub mod menu;
+pub mod notification;
+pub mod pointer_manager;
 pub mod run_loop;
 pub mod screen_manager;
 pub mod status_item;
@@ -0,0 +1,36 @@
+use std::{cell::RefCell, rc::Weak};
+
+use crate::shell::{
+    api_model::NotificationShowRequest, Context, NotificationHandle, NotificationManagerDelegate,
+};
+
+use super::error::{PlatformError, PlatformResult};
+
+pub struct PlatformNotificationManager {}
+
+impl PlatformNotificationManager {
+    pub fn new(
+        _context: Context,
+        _delegate: Weak<RefCell<dyn NotificationManagerDelegate>>,
+    ) -> Self {
+        Self {}
+    }
+
+    pub fn assign_weak_self(&self, _weak: Weak<PlatformNotificationManager>) {}
+
+    // TODO: Implement using ToastNotificationManager
+    pub fn show_notification<F>(
+        &self,
+        _handle: NotificationHandle,
+        _request: NotificationShowRequest,
+        on_done: F,
+    ) where
+        F: FnOnce(PlatformResult<()>) + 'static,
+    {
+        on_done(Err(PlatformError::NotAvailable));
+    }
+
+    pub fn close_notification(&self, _handle: NotificationHandle) -> PlatformResult<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Weak,
+};
+
+use windows::Win32::{
+    Foundation::{HINSTANCE, LPARAM, LRESULT, POINT, WPARAM},
+    UI::{
+        Input::KeyboardAndMouse::{
+            GetAsyncKeyState, VIRTUAL_KEY, VK_CONTROL, VK_LBUTTON, VK_LWIN, VK_MBUTTON, VK_MENU,
+            VK_RBUTTON, VK_RWIN, VK_SHIFT, VK_XBUTTON1, VK_XBUTTON2,
+        },
+        WindowsAndMessaging::{
+            CallNextHookEx, GetCursorPos, SetWindowsHookExW, UnhookWindowsHookEx, HC_ACTION, HHOOK,
+            MSLLHOOKSTRUCT, WH_MOUSE_LL, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MBUTTONDOWN,
+            WM_MBUTTONUP, WM_MOUSEMOVE, WM_RBUTTONDOWN, WM_RBUTTONUP, WM_XBUTTONDOWN, WM_XBUTTONUP,
+        },
+    },
+};
+
+use crate::shell::{
+    api_model::{
+        GlobalPointerEvent, GlobalPointerEventType, PointerModifiers, PointerState,
+        POINTER_BUTTON_BACK, POINTER_BUTTON_FORWARD, POINTER_BUTTON_MIDDLE, POINTER_BUTTON_PRIMARY,
+        POINTER_BUTTON_SECONDARY,
+    },
+    pointer_manager::PointerManagerDelegate,
+    Point,
+};
+
+use super::{
+    error::{PlatformError, PlatformResult},
+    util::HIWORD,
+};
+
+thread_local! {
+    // Low level hook procedure has no user data
+    static HOOK_DELEGATE: RefCell<Option<Weak<RefCell<dyn PointerManagerDelegate>>>> =
+        RefCell::new(None);
+}
+
+pub struct PlatformPointerManager {
+    delegate: Weak<RefCell<dyn PointerManagerDelegate>>,
+    hook: Cell<HHOOK>,
+}
+
+impl PlatformPointerManager {
+    pub fn new(delegate: Weak<RefCell<dyn PointerManagerDelegate>>) -> Self {
+        Self {
+            delegate,
+            hook: Cell::new(HHOOK(0)),
+        }
+    }
+
+    fn is_down(key: VIRTUAL_KEY) -> bool {
+        let state = unsafe { GetAsyncKeyState(key.0 as i32) };
+        state as u16 & 0x8000 != 0
+    }
+
+    fn current_buttons() -> i64 {
+        let mut buttons = 0;
+        for (key, button) in [
+            (VK_LBUTTON, POINTER_BUTTON_PRIMARY),
+            (VK_RBUTTON, POINTER_BUTTON_SECONDARY),
+            (VK_MBUTTON, POINTER_BUTTON_MIDDLE),
+            (VK_XBUTTON1, POINTER_BUTTON_BACK),
+            (VK_XBUTTON2, POINTER_BUTTON_FORWARD),
+        ] {
+            if Self::is_down(key) {
+                buttons |= button;
+            }
+        }
+        buttons
+    }
+
+    fn current_modifiers() -> PointerModifiers {
+        PointerModifiers {
+            shift: Self::is_down(VK_SHIFT),
+            control: Self::is_down(VK_CONTROL),
+            alt: Self::is_down(VK_MENU),
+            meta: Self::is_down(VK_LWIN) || Self::is_down(VK_RWIN),
+        }
+    }
+
+    // Position is in physical coordinates
+    pub fn get_pointer_state(&self) -> PlatformResult<PointerState> {
+        let mut position = POINT::default();
+        if !unsafe { GetCursorPos(&mut position as *mut _) }.as_bool() {
+            return Err(PlatformError::UnknownError);
+        }
+        Ok(PointerState {
+            position: Point::xy(position.x as f64, position.y as f64),
+            screen_id: None,
+            buttons: Self::current_buttons(),
+            modifiers: Self::current_modifiers(),
+        })
+    }
+
+    pub fn start_monitoring(&self) -> PlatformResult<()> {
+        if self.hook.get().0 != 0 {
+            return Ok(());
+        }
+        HOOK_DELEGATE.with(|d| d.replace(Some(self.delegate.clone())));
+        let hook =
+            unsafe { SetWindowsHookExW(WH_MOUSE_LL, Some(Self::hook_proc), HINSTANCE(0), 0) };
+        if hook.0 == 0 {
+            HOOK_DELEGATE.with(|d| d.replace(None));
+            return Err(PlatformError::NotAvailable);
+        }
+        self.hook.set(hook);
+        Ok(())
+    }
+
+    pub fn stop_monitoring(&self) {
+        let hook = self.hook.replace(HHOOK(0));
+        if hook.0 != 0 {
+            unsafe {
+                UnhookWindowsHookEx(hook);
+            }
+            HOOK_DELEGATE.with(|d| d.replace(None));
+        }
+    }
+
+    fn event_for_message(message: u32, info: &MSLLHOOKSTRUCT) -> Option<GlobalPointerEvent> {
+        let x_button = || match HIWORD(info.mouseData) {
+            1 => POINTER_BUTTON_BACK,
+            _ => POINTER_BUTTON_FORWARD,
+        };
+        let (event_type, button) = match message {
+            WM_MOUSEMOVE => (GlobalPointerEventType::Move, 0),
+            WM_LBUTTONDOWN => (GlobalPointerEventType::ButtonDown, POINTER_BUTTON_PRIMARY),
+            WM_LBUTTONUP => (GlobalPointerEventType::ButtonUp, POINTER_BUTTON_PRIMARY),
+            WM_RBUTTONDOWN => (GlobalPointerEventType::ButtonDown, POINTER_BUTTON_SECONDARY),
+            WM_RBUTTONUP => (GlobalPointerEventType::ButtonUp, POINTER_BUTTON_SECONDARY),
+            WM_MBUTTONDOWN => (GlobalPointerEventType::ButtonDown, POINTER_BUTTON_MIDDLE),
+            WM_MBUTTONUP => (GlobalPointerEventType::ButtonUp, POINTER_BUTTON_MIDDLE),
+            WM_XBUTTONDOWN => (GlobalPointerEventType::ButtonDown, x_button()),
+            WM_XBUTTONUP => (GlobalPointerEventType::ButtonUp, x_button()),
+            _ => return None,
+        };
+        // Hook is called before key state is updated
+        let buttons = match event_type {
+            GlobalPointerEventType::Move | GlobalPointerEventType::ModifiersChanged => {
+                Self::current_buttons()
+            }
+            GlobalPointerEventType::ButtonDown => Self::current_buttons() | button,
+            GlobalPointerEventType::ButtonUp => Self::current_buttons() & !button,
+        };
+        Some(GlobalPointerEvent {
+            event_type,
+            position: Point::xy(info.pt.x as f64, info.pt.y as f64),
+            screen_id: None,
+            button,
+            buttons,
+            modifiers: Self::current_modifiers(),
+        })
+    }
+
+    extern "system" fn hook_proc(code: i32, w_param: WPARAM, l_param: LPARAM) -> LRESULT {
+        if code == HC_ACTION as i32 {
+            let info = unsafe { &*(l_param.0 as *const MSLLHOOKSTRUCT) };
+            if let Some(event) = Self::event_for_message(w_param.0 as u32, info) {
+                let delegate = HOOK_DELEGATE.with(|d| d.borrow().clone());
+                if let Some(delegate) = delegate.and_then(|d| d.upgrade()) {
+                    delegate.borrow().on_pointer_event(event);
+                }
+            }
+        }
+        unsafe { CallNextHookEx(HHOOK(0), code, w_param, l_param) }
+    }
+}
@@ -35,6 +35,13 @@ impl PlatformScreenManager {
                 frame: d.logical.clone(),
                 work_area: d.work.clone(),
                 scaling_factor: d.scale,
+                name: d.name.clone(),
+                model: None,
+                // GetDeviceCaps(HORZSIZE) is not reliable; Would need to parse EDID
+                physical_size: None,
+                refresh_rate: d.refresh_rate,
+                rotation: d.rotation,
+                is_primary: d.is_primary,
             })
             .collect())
     }
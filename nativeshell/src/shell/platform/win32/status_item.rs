@@ -5,7 +5,6 @@ use std::{
 
 use windows::Win32::{
     Foundation::{HWND, RECT},
-    Graphics::Gdi::DeleteObject,
     UI::{
         Shell::{
             Shell_NotifyIconGetRect, Shell_NotifyIconW, NIF_ICON, NIF_MESSAGE, NIF_TIP, NIM_ADD,
@@ -13,9 +12,9 @@ use windows::Win32::{
             NOTIFYICONIDENTIFIER, NOTIFYICON_VERSION_4,
         },
         WindowsAndMessaging::{
-            CreateIconIndirect, DestroyIcon, SetForegroundWindow, TrackPopupMenuEx, HICON,
-            ICONINFO, TPM_BOTTOMALIGN, TPM_LEFTALIGN, TPM_RETURNCMD, TPM_RIGHTBUTTON, TPM_VERTICAL,
-            WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MOUSEMOVE, WM_RBUTTONDOWN, WM_RBUTTONUP,
+            DestroyIcon, SetForegroundWindow, TrackPopupMenuEx, HICON, TPM_BOTTOMALIGN,
+            TPM_LEFTALIGN, TPM_RETURNCMD, TPM_RIGHTBUTTON, TPM_VERTICAL, WM_LBUTTONDOWN,
+            WM_LBUTTONUP, WM_MOUSEMOVE, WM_RBUTTONDOWN, WM_RBUTTONUP,
         },
     },
 };
@@ -23,7 +22,7 @@ use windows::Win32::{
 use crate::{
     shell::{
         api_model::{ImageData, StatusItemActionType},
-        platform::util::{HIWORD, LOWORD},
+        platform::util::{image_data_to_hicon, HIWORD, LOWORD},
         status_item_manager::{StatusItemDelegate, StatusItemHandle},
         EngineHandle, IPoint, Point, Rect,
     },
@@ -106,7 +105,7 @@ impl PlatformStatusItem {
                 let d2 = (b.height - ideal_height).abs();
                 d1.cmp(&d2)
             })
-            .map(Self::image_to_icon)
+            .map(image_data_to_hicon)
             .unwrap_or(HICON(0));
         let mut flags = NIF_MESSAGE | NIF_TIP;
         if icon.0 != 0 {
@@ -138,22 +137,6 @@ impl PlatformStatusItem {
         }
     }
 
-    fn image_to_icon(image: &ImageData) -> HICON {
-        let bitmap = image_data_to_hbitmap(image);
-        let icon_info = ICONINFO {
-            fIcon: true.into(),
-            xHotspot: 0,
-            yHotspot: 0,
-            hbmMask: bitmap,
-            hbmColor: bitmap,
-        };
-        let res = unsafe { CreateIconIndirect(&icon_info as *const _) };
-        unsafe {
-            DeleteObject(bitmap);
-        };
-        res
-    }
-
     pub fn set_image(&self, image: Vec<ImageData>) -> PlatformResult<()> {
         self.image.replace(image);
         self.update();
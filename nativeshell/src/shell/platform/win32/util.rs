@@ -5,7 +5,7 @@ use windows::{
     Win32::{
         Foundation::{GetLastError, BOOL, HANDLE, HWND, LPARAM, PWSTR},
         Graphics::Gdi::{
-            CreateDIBSection, GetDC, ReleaseDC, BITMAPINFO, BITMAPINFOHEADER, BI_RGB,
+            CreateDIBSection, DeleteObject, GetDC, ReleaseDC, BITMAPINFO, BITMAPINFOHEADER, BI_RGB,
             DIB_RGB_COLORS, HBITMAP,
         },
         System::{
@@ -13,6 +13,7 @@ use windows::{
             DataExchange::GetClipboardFormatNameW,
             Diagnostics::Debug::FACILITY_WIN32,
         },
+        UI::WindowsAndMessaging::{CreateIconIndirect, HICON, ICONINFO},
     },
 };
 
@@ -210,3 +211,19 @@ pub fn image_data_to_hbitmap(image: &ImageData) -> HBITMAP {
         bitmap
     }
 }
+
+pub fn image_data_to_hicon(image: &ImageData) -> HICON {
+    let bitmap = image_data_to_hbitmap(image);
+    let icon_info = ICONINFO {
+        fIcon: true.into(),
+        xHotspot: 0,
+        yHotspot: 0,
+        hbmMask: bitmap,
+        hbmColor: bitmap,
+    };
+    let res = unsafe { CreateIconIndirect(&icon_info as *const _) };
+    unsafe {
+        DeleteObject(bitmap);
+    };
+    res
+}
@@ -11,11 +11,14 @@ use windows::Win32::{
         Input::KeyboardAndMouse::{EnableWindow, IsWindowEnabled, SetFocus},
         Shell::{DefSubclassProc, SetWindowSubclass},
         WindowsAndMessaging::{
-            DefWindowProcW, EndMenu, GetClientRect, GetSystemMenu, MoveWindow, SendMessageW,
-            SetForegroundWindow, SetParent, TrackPopupMenuEx, GWL_HWNDPARENT, MSG, SIZE_MAXIMIZED,
-            SIZE_MINIMIZED, TPM_RETURNCMD, WA_ACTIVE, WA_CLICKACTIVE, WM_ACTIVATE,
-            WM_DISPLAYCHANGE, WM_EXITSIZEMOVE, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_NCCALCSIZE,
-            WM_RBUTTONDOWN, WM_RBUTTONUP, WM_SETFOCUS, WM_SHOWWINDOW, WM_SIZE, WM_SYSCOMMAND,
+            DefWindowProcW, DestroyIcon, EndMenu, FlashWindowEx, GetClientRect, GetSystemMenu,
+            MoveWindow, SendMessageW, SetCursor, SetForegroundWindow, SetParent, TrackPopupMenuEx,
+            FLASHWINFO, FLASHW_STOP, FLASHW_TIMERNOFG, FLASHW_TRAY, GWL_HWNDPARENT, HCURSOR,
+            HTCLIENT, MSG, SIZE_MAXIMIZED, SIZE_MINIMIZED, TPM_RETURNCMD, WA_ACTIVE,
+            WA_CLICKACTIVE, WM_ACTIVATE, WM_DISPLAYCHANGE, WM_DPICHANGED, WM_EXITSIZEMOVE,
+            WM_KEYDOWN, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MOUSEMOVE, WM_NCCALCSIZE, WM_RBUTTONDOWN,
+            WM_RBUTTONUP, WM_SETCURSOR, WM_SETFOCUS, WM_SHOWWINDOW, WM_SIZE, WM_SYSCOMMAND,
+            WM_SYSKEYDOWN, WM_WINDOWPOSCHANGED,
         },
     },
 };
@@ -24,11 +27,12 @@ use crate::{
     codec::Value,
     shell::{
         api_model::{
-            BoolTransition, DragEffect, DragRequest, PopupMenuRequest, PopupMenuResponse,
-            WindowCollectionBehavior, WindowGeometry, WindowGeometryFlags, WindowGeometryRequest,
-            WindowStateFlags, WindowStyle,
+            AlertRequest, AlertResponse, BoolTransition, DragEffect, DragRequest, ImageData,
+            PopupMenuRequest, PopupMenuResponse, WindowCollectionBehavior, WindowCreateOptions,
+            WindowDragRegions, WindowEdge, WindowGeometry, WindowGeometryFlags,
+            WindowGeometryRequest, WindowStateFlags, WindowStyle,
         },
-        Context, IPoint, PlatformWindowDelegate, Point,
+        Context, IPoint, PlatformWindowDelegate, Point, Rect,
     },
     util::LateRefCell,
 };
@@ -41,6 +45,7 @@ use super::{
     flutter_sys::*,
     menu::PlatformMenu,
     screen_manager::PlatformScreenManager,
+    util::image_data_to_hicon,
     window_adapter::{SetWindowLongPtrW, WindowAdapter},
     window_base::{WindowBaseState, WindowDelegate},
     window_menu::{WindowMenu, WindowMenuDelegate},
@@ -58,6 +63,7 @@ pub struct PlatformWindow {
     weak_self: LateRefCell<Weak<PlatformWindow>>,
     parent: Option<Rc<PlatformWindow>>,
     modal_child: Cell<Option<HWND>>,
+    icons: RefCell<Vec<HICON>>,
     flutter_controller: LateRefCell<FlutterDesktopViewControllerRef>,
     delegate: Weak<dyn PlatformWindowDelegate>,
     modal_close_callback: RefCell<Option<Box<dyn FnOnce(PlatformResult<Value>)>>>,
@@ -65,11 +71,15 @@ pub struct PlatformWindow {
     show_when_ready: Cell<bool>,
     mouse_state: RefCell<MouseState>,
     window_state_flags: RefCell<WindowStateFlags>,
+    geometry_change_scheduled: Cell<bool>,
 }
 
 struct MouseState {
     // last button down message used to synthetize button up when displaying menu
     last_button_down: Option<MSG>,
+    hide_while_typing: bool,
+    // cursor hidden after key press until next mouse move
+    hidden_while_typing: bool,
 }
 
 impl PlatformWindow {
@@ -77,6 +87,7 @@ impl PlatformWindow {
         context: Context,
         delegate: Weak<dyn PlatformWindowDelegate>,
         parent: Option<Rc<PlatformWindow>>,
+        _options: &WindowCreateOptions,
     ) -> Self {
         PlatformWindow {
             context,
@@ -87,6 +98,7 @@ impl PlatformWindow {
             drag_context: LateRefCell::new(),
             parent,
             modal_child: Cell::new(None),
+            icons: RefCell::new(Vec::new()),
             weak_self: LateRefCell::new(),
             flutter_controller: LateRefCell::new(),
             delegate,
@@ -95,8 +107,11 @@ impl PlatformWindow {
             show_when_ready: Cell::new(false),
             mouse_state: RefCell::new(MouseState {
                 last_button_down: None,
+                hide_while_typing: false,
+                hidden_while_typing: false,
             }),
             window_state_flags: RefCell::new(WindowStateFlags::default()),
+            geometry_change_scheduled: Cell::new(false),
         }
     }
 
@@ -309,10 +324,109 @@ impl PlatformWindow {
         self.state.borrow().supported_geometry()
     }
 
+    pub fn get_normal_frame(&self) -> PlatformResult<Option<Rect>> {
+        self.state.borrow().get_normal_frame()
+    }
+
     pub fn set_title(&self, title: String) -> PlatformResult<()> {
         self.state.borrow().set_title(title)
     }
 
+    // Flashes taskbar button until window comes to foreground
+    pub fn set_urgent(&self, urgent: bool) -> PlatformResult<()> {
+        let info = FLASHWINFO {
+            cbSize: std::mem::size_of::<FLASHWINFO>() as u32,
+            hwnd: self.hwnd(),
+            dwFlags: if urgent {
+                FLASHW_TRAY | FLASHW_TIMERNOFG
+            } else {
+                FLASHW_STOP
+            },
+            uCount: 0,
+            dwTimeout: 0,
+        };
+        unsafe {
+            FlashWindowEx(&info as *const _);
+        }
+        Ok(())
+    }
+
+    pub fn set_icon(&self, images: Vec<ImageData>) -> PlatformResult<()> {
+        let scale = self.state.borrow().get_scaling_factor();
+        let best_icon = |size: f64| {
+            let size = (size * scale).round() as i32;
+            images
+                .iter()
+                .min_by_key(|image| (image.height - size).abs())
+                .map(image_data_to_hicon)
+        };
+        let icons = [(ICON_SMALL, best_icon(16.0)), (ICON_BIG, best_icon(32.0))];
+        let mut new_icons = Vec::new();
+        for (kind, icon) in icons {
+            let icon = icon.unwrap_or(HICON(0));
+            unsafe {
+                SendMessageW(
+                    self.hwnd(),
+                    WM_SETICON,
+                    WPARAM(kind as usize),
+                    LPARAM(icon.0),
+                );
+            }
+            if icon.0 != 0 {
+                new_icons.push(icon);
+            }
+        }
+        // Previous icons are no longer used by the window
+        for icon in self.icons.replace(new_icons) {
+            unsafe {
+                DestroyIcon(icon);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_application_id(&self, _application_id: String) -> PlatformResult<()> {
+        // TODO: Could be done through window property store (PKEY_AppUserModel_ID)
+        Err(PlatformError::NotAvailable)
+    }
+
+    pub fn capture_image(&self) -> PlatformResult<ImageData> {
+        // TODO: PrintWindow with PW_RENDERFULLCONTENT
+        Err(PlatformError::NotAvailable)
+    }
+
+    pub fn lock_pointer(&self) -> PlatformResult<()> {
+        // TODO: ClipCursor + ShowCursor, relative motion from WM_INPUT raw input
+        Err(PlatformError::NotAvailable)
+    }
+
+    pub fn confine_pointer(&self, _rect: Option<Rect>) -> PlatformResult<()> {
+        // TODO: ClipCursor, needs to be reapplied on activation
+        Err(PlatformError::NotAvailable)
+    }
+
+    pub fn release_pointer(&self) -> PlatformResult<()> {
+        Ok(())
+    }
+
+    pub fn set_hide_pointer_while_typing(&self, hide: bool) -> PlatformResult<()> {
+        let mut mouse_state = self.mouse_state.borrow_mut();
+        mouse_state.hide_while_typing = hide;
+        if !hide && mouse_state.hidden_while_typing {
+            mouse_state.hidden_while_typing = false;
+            // Let flutter restore the cursor
+            unsafe {
+                SendMessageW(
+                    self.child_hwnd(),
+                    WM_SETCURSOR,
+                    WPARAM(self.child_hwnd().0 as usize),
+                    LPARAM((WM_MOUSEMOVE << 16 | HTCLIENT) as isize),
+                )
+            };
+        }
+        Ok(())
+    }
+
     pub fn set_collection_behavior(
         &self,
         _behavior: WindowCollectionBehavior,
@@ -368,6 +482,28 @@ impl PlatformWindow {
         self.state.borrow().perform_window_drag()
     }
 
+    pub fn perform_window_resize(&self, edge: WindowEdge) -> PlatformResult<()> {
+        self.state.borrow().perform_window_resize(edge)
+    }
+
+    pub fn set_ignore_mouse_events(
+        &self,
+        _ignore: bool,
+        _forward_motion: bool,
+    ) -> PlatformResult<()> {
+        // TODO: WS_EX_TRANSPARENT requires layered window, which doesn't play
+        // well with DirectComposition surface
+        Err(PlatformError::NotAvailable)
+    }
+
+    pub fn set_input_region(&self, _region: Option<Vec<Rect>>) -> PlatformResult<()> {
+        Err(PlatformError::NotAvailable)
+    }
+
+    pub fn set_drag_regions(&self, regions: WindowDragRegions) -> PlatformResult<()> {
+        self.state.borrow().set_drag_regions(regions)
+    }
+
     pub fn is_enabled(&self) -> bool {
         unsafe { IsWindowEnabled(self.hwnd()).as_bool() }
     }
@@ -433,6 +569,14 @@ impl PlatformWindow {
         Ok(())
     }
 
+    // TODO: Implement using TaskDialogIndirect
+    pub fn show_alert<F>(&self, _request: AlertRequest, on_done: F)
+    where
+        F: FnOnce(PlatformResult<AlertResponse>) + 'static,
+    {
+        on_done(Err(PlatformError::NotAvailable));
+    }
+
     pub fn show_system_menu(&self) -> PlatformResult<()> {
         let menu = unsafe { GetSystemMenu(self.hwnd(), false) };
         let position = self.get_state().local_to_global(&Point::xy(0.0, 0.0));
@@ -514,6 +658,30 @@ impl PlatformWindow {
         self.update_state_flags(new_state_flags);
     }
 
+    // WM_WINDOWPOSCHANGED is received while the window is being created, before
+    // delegate can be called, and before WM_SIZE updates state flags; So the
+    // delegate is notified on next run loop turn.
+    fn schedule_geometry_changed(&self) {
+        if self.geometry_change_scheduled.replace(true) {
+            return;
+        }
+        let weak_self = self.weak_self.borrow().clone();
+        if let Some(context) = self.context.get() {
+            context
+                .run_loop
+                .borrow()
+                .schedule_now(move || {
+                    if let Some(s) = weak_self.upgrade() {
+                        s.geometry_change_scheduled.set(false);
+                        if let Some(delegate) = s.delegate() {
+                            delegate.geometry_changed();
+                        }
+                    }
+                })
+                .detach();
+        }
+    }
+
     fn on_wmactivate(&self, w_param: WPARAM) {
         let mut new_state_flags = self.window_state_flags.borrow().clone();
         new_state_flags.active = w_param.0 as u32 & (WA_ACTIVE | WA_CLICKACTIVE) != 0;
@@ -539,6 +707,10 @@ impl PlatformWindow {
                 self.layout_child();
                 self.force_redraw();
             }
+            // Scale factor may change on WM_DPICHANGED without window being resized
+            WM_WINDOWPOSCHANGED | WM_DPICHANGED => {
+                self.schedule_geometry_changed();
+            }
             WM_DISPLAYCHANGE => {
                 unsafe {
                     SendMessageW(self.child_hwnd(), WM_SHOWWINDOW, WPARAM(1), LPARAM(1));
@@ -675,6 +847,19 @@ impl PlatformWindow {
                 mouse_state.last_button_down.take();
             }
 
+            if (u_msg == WM_KEYDOWN || u_msg == WM_SYSKEYDOWN) && mouse_state.hide_while_typing {
+                mouse_state.hidden_while_typing = true;
+                unsafe { SetCursor(HCURSOR(0)) };
+            } else if u_msg == WM_SETCURSOR && mouse_state.hidden_while_typing {
+                // High word is the mouse message that triggered WM_SETCURSOR
+                if (l_param.0 >> 16) as u32 & 0xFFFF == WM_MOUSEMOVE {
+                    mouse_state.hidden_while_typing = false;
+                } else {
+                    unsafe { SetCursor(HCURSOR(0)) };
+                    return LRESULT(1);
+                }
+            }
+
             let r = self
                 .window_menu
                 .borrow()
@@ -8,7 +8,10 @@ use windows::Win32::{
     Foundation::{HWND, LPARAM, LRESULT, POINT, RECT, WPARAM},
     Graphics::{
         Dwm::DwmExtendFrameIntoClientArea,
-        Gdi::{ClientToScreen, ScreenToClient, HMONITOR},
+        Gdi::{
+            ClientToScreen, GetMonitorInfoW, MonitorFromRect, ScreenToClient, HMONITOR,
+            MONITORINFO, MONITOR_DEFAULTTONEAREST,
+        },
     },
     UI::{
         Controls::MARGINS,
@@ -25,8 +28,8 @@ use windows::Win32::{
             WINDOWPOS, WINDOW_EX_STYLE, WINDOW_STYLE, WM_CLOSE, WM_DESTROY, WM_DISPLAYCHANGE,
             WM_DWMCOMPOSITIONCHANGED, WM_NCCALCSIZE, WM_NCHITTEST, WM_NCLBUTTONDOWN,
             WM_WINDOWPOSCHANGING, WS_BORDER, WS_CAPTION, WS_DLGFRAME, WS_EX_LAYOUTRTL,
-            WS_EX_NOREDIRECTIONBITMAP, WS_MAXIMIZEBOX, WS_MINIMIZEBOX, WS_OVERLAPPEDWINDOW,
-            WS_POPUP, WS_SYSMENU, WS_THICKFRAME,
+            WS_EX_NOREDIRECTIONBITMAP, WS_EX_TOOLWINDOW, WS_MAXIMIZEBOX, WS_MINIMIZEBOX,
+            WS_OVERLAPPEDWINDOW, WS_POPUP, WS_SYSMENU, WS_THICKFRAME,
         },
     },
 };
@@ -34,7 +37,8 @@ use windows::Win32::{
 use crate::{
     shell::{
         api_model::{
-            WindowFrame, WindowGeometry, WindowGeometryFlags, WindowGeometryRequest, WindowStyle,
+            WindowDragRegions, WindowEdge, WindowFrame, WindowGeometry, WindowGeometryFlags,
+            WindowGeometryRequest, WindowStyle,
         },
         platform::error::PlatformError,
         IPoint, IRect, ISize, Point, Rect, Size,
@@ -57,6 +61,7 @@ pub struct WindowBaseState {
     max_content_size: RefCell<Size>,
     delegate: Weak<dyn WindowDelegate>,
     style: RefCell<WindowStyle>,
+    drag_regions: RefCell<WindowDragRegions>,
     pending_show_cmd: Cell<SHOW_WINDOW_CMD>,
     last_window_pos: RefCell<Option<WINDOWPOS>>,
 }
@@ -73,6 +78,7 @@ impl WindowBaseState {
             min_content_size: RefCell::new(Size::wh(0.0, 0.0)),
             max_content_size: RefCell::new(Size::wh(LARGE_SIZE, LARGE_SIZE)),
             style: Default::default(),
+            drag_regions: Default::default(),
             pending_show_cmd: Cell::new(SW_SHOW),
             last_window_pos: RefCell::new(None),
         }
@@ -281,6 +287,43 @@ impl WindowBaseState {
         })
     }
 
+    // Frame the window is restored to when maximized or minimized
+    pub fn get_normal_frame(&self) -> PlatformResult<Option<Rect>> {
+        let mut placement = WINDOWPLACEMENT {
+            length: mem::size_of::<WINDOWPLACEMENT>() as u32,
+            ..Default::default()
+        };
+        let mut rect = unsafe {
+            GetWindowPlacement(self.hwnd, &mut placement as *mut _).as_platform_result()?;
+            placement.rcNormalPosition
+        };
+        // Normal position is in workspace coordinates, unless this is a tool
+        // window
+        let ex_style = WINDOW_EX_STYLE(unsafe { GetWindowLongW(self.hwnd, GWL_EXSTYLE) } as u32);
+        if ex_style & WS_EX_TOOLWINDOW != WS_EX_TOOLWINDOW {
+            let mut info = MONITORINFO {
+                cbSize: mem::size_of::<MONITORINFO>() as u32,
+                ..Default::default()
+            };
+            unsafe {
+                let monitor = MonitorFromRect(&rect as *const _, MONITOR_DEFAULTTONEAREST);
+                if GetMonitorInfoW(monitor, &mut info as *mut _).as_bool() {
+                    let dx = info.rcWork.left - info.rcMonitor.left;
+                    let dy = info.rcWork.top - info.rcMonitor.top;
+                    rect.left += dx;
+                    rect.right += dx;
+                    rect.top += dy;
+                    rect.bottom += dy;
+                }
+            }
+        }
+        let size: Size = ISize::wh(rect.right - rect.left, rect.bottom - rect.top).into();
+        Ok(Some(Rect::origin_size(
+            &self.to_logical(&IPoint::xy(rect.left, rect.top)),
+            &size.scaled(1.0 / self.get_scaling_factor()),
+        )))
+    }
+
     fn get_frame_rect(&self) -> PlatformResult<Rect> {
         let mut rect: RECT = Default::default();
         unsafe {
@@ -596,6 +639,36 @@ impl WindowBaseState {
         Ok(())
     }
 
+    pub fn perform_window_resize(&self, edge: WindowEdge) -> PlatformResult<()> {
+        let hit = match edge {
+            WindowEdge::Top => HTTOP,
+            WindowEdge::Bottom => HTBOTTOM,
+            WindowEdge::Left => HTLEFT,
+            WindowEdge::Right => HTRIGHT,
+            WindowEdge::TopLeft => HTTOPLEFT,
+            WindowEdge::TopRight => HTTOPRIGHT,
+            WindowEdge::BottomLeft => HTBOTTOMLEFT,
+            WindowEdge::BottomRight => HTBOTTOMRIGHT,
+        };
+        unsafe {
+            ReleaseCapture();
+            SendMessageW(self.hwnd, WM_NCLBUTTONDOWN, WPARAM(hit as usize), LPARAM(0));
+        }
+        Ok(())
+    }
+
+    pub fn set_drag_regions(&self, regions: WindowDragRegions) -> PlatformResult<()> {
+        self.drag_regions.replace(regions);
+        Ok(())
+    }
+
+    // Drag regions are reported as caption, which gives us native dragging,
+    // double click to maximize and system menu
+    fn is_in_drag_region(&self, x: i32, y: i32) -> bool {
+        let regions = self.drag_regions.borrow();
+        !regions.is_empty() && regions.is_draggable(&self.global_to_local(&IPoint::xy(x, y)))
+    }
+
     pub fn has_redirection_surface(&self) -> bool {
         let style = WINDOW_EX_STYLE(unsafe { GetWindowLongW(self.hwnd, GWL_EXSTYLE) } as u32);
         (style & WS_EX_NOREDIRECTIONBITMAP).0 == 0
@@ -605,13 +678,22 @@ impl WindowBaseState {
         self.style.borrow().frame == WindowFrame::NoTitle
     }
 
+    // Frameless window with resize border hit-tested by us
+    fn has_resize_border(&self) -> bool {
+        let style = self.style.borrow();
+        style.frame == WindowFrame::NoFrame
+            && style.can_resize
+            && style.resize_border_width.is_some()
+    }
+
     fn do_hit_test(&self, x: i32, y: i32) -> u32 {
         let mut win_rect = RECT::default();
         unsafe {
             GetWindowRect(self.hwnd, &mut win_rect as *mut _);
         }
 
-        let border_width = (7.0 * self.get_scaling_factor()) as i32;
+        let border_width = self.style.borrow().resize_border_width.unwrap_or(7.0);
+        let border_width = (border_width * self.get_scaling_factor()) as i32;
 
         if x < win_rect.left + border_width && y < win_rect.top + border_width {
             HTTOPLEFT
@@ -629,6 +711,8 @@ impl WindowBaseState {
             HTLEFT
         } else if x > win_rect.right - border_width {
             HTRIGHT
+        } else if self.is_in_drag_region(x, y) {
+            HTCAPTION
         } else {
             HTCLIENT
         }
@@ -688,9 +772,12 @@ impl WindowBaseState {
                 }
             }
             WM_NCHITTEST => {
-                if self.remove_border() {
-                    let res = self.do_hit_test(GET_X_LPARAM(l_param), GET_Y_LPARAM(l_param));
+                let (x, y) = (GET_X_LPARAM(l_param), GET_Y_LPARAM(l_param));
+                if self.remove_border() || self.has_resize_border() {
+                    let res = self.do_hit_test(x, y);
                     Some(LRESULT(res as isize))
+                } else if self.is_in_drag_region(x, y) {
+                    Some(LRESULT(HTCAPTION as isize))
                 } else {
                     None
                 }
@@ -708,13 +795,16 @@ impl WindowBaseState {
     ) -> Option<LRESULT> {
         match msg {
             WM_NCHITTEST => {
-                if self.remove_border() {
+                if self.remove_border() || self.has_resize_border() {
                     let res = self.do_hit_test(GET_X_LPARAM(l_param), GET_Y_LPARAM(l_param));
                     if res != HTCLIENT {
                         Some(LRESULT(HTTRANSPARENT as isize))
                     } else {
                         Some(LRESULT(res as isize))
                     }
+                } else if self.is_in_drag_region(GET_X_LPARAM(l_param), GET_Y_LPARAM(l_param)) {
+                    // let parent window handle the hit test
+                    Some(LRESULT(HTTRANSPARENT as isize))
                 } else {
                     None
                 }
@@ -0,0 +1,170 @@
+use std::{cell::RefCell, rc::Weak};
+
+use crate::{
+    codec::{value::to_value, MethodCall, MethodCallReply, MethodCallResult, Value},
+    util::{Late, OkLog},
+    Context, Error, Result,
+};
+
+use super::{
+    api_constants::{channel, method},
+    api_model::{GlobalPointerEvent, PointerState},
+    platform::pointer_manager::PlatformPointerManager,
+    EngineHandle, EventChannelHandler, EventSink, MethodCallHandler, Point, RegisteredEventChannel,
+    RegisteredMethodCallHandler,
+};
+
+pub trait PointerManagerDelegate {
+    // Event position is in system coordinates
+    fn on_pointer_event(&self, event: GlobalPointerEvent);
+}
+
+pub struct PointerManager {
+    context: Context,
+    platform_manager: Late<PlatformPointerManager>,
+}
+
+impl PointerManager {
+    pub(super) fn new(context: Context) -> RegisteredMethodCallHandler<Self> {
+        Self {
+            context: context.clone(),
+            platform_manager: Late::new(),
+        }
+        .register(context, channel::POINTER_MANAGER)
+    }
+
+    pub fn platform_manager(&self) -> &PlatformPointerManager {
+        &self.platform_manager
+    }
+
+    // Returns pointer state with position in logical coordinates.
+    pub fn get_pointer_state(&self) -> Result<PointerState> {
+        let state = self.platform_manager.get_pointer_state()?;
+        let (position, screen_id) = self.to_logical(state.position)?;
+        Ok(PointerState {
+            position,
+            screen_id,
+            ..state
+        })
+    }
+
+    fn to_logical(&self, position: Point) -> Result<(Point, Option<i64>)> {
+        let context = self.context.get().ok_or(Error::InvalidContext)?;
+        let screen_manager = context.screen_manager.borrow();
+        let screen_manager = screen_manager.borrow();
+        let position = screen_manager
+            .platform_manager()
+            .system_to_logical(position)?;
+        let screen_id = screen_manager
+            .last_screens()
+            .iter()
+            .find(|s| s.frame.is_inside(&position))
+            .map(|s| s.id);
+        Ok((position, screen_id))
+    }
+
+    fn map_result<T>(result: Result<T>) -> MethodCallResult<Value>
+    where
+        T: serde::Serialize,
+    {
+        result.map(|v| to_value(v).unwrap()).map_err(|e| e.into())
+    }
+}
+
+impl MethodCallHandler for PointerManager {
+    fn on_method_call(
+        &mut self,
+        call: MethodCall<Value>,
+        reply: MethodCallReply<Value>,
+        _engine: EngineHandle,
+    ) {
+        if call.method.as_str() == method::pointer_manager::GET_POINTER_STATE {
+            reply.send(Self::map_result(self.get_pointer_state()));
+        }
+    }
+
+    fn assign_weak_self(&mut self, weak_self: Weak<RefCell<Self>>) {
+        self.platform_manager
+            .set(PlatformPointerManager::new(weak_self));
+    }
+}
+
+impl PointerManagerDelegate for PointerManager {
+    fn on_pointer_event(&self, event: GlobalPointerEvent) {
+        let (position, screen_id) = match self.to_logical(event.position.clone()) {
+            Ok(res) => res,
+            Err(_) => return,
+        };
+        let event = GlobalPointerEvent {
+            position,
+            screen_id,
+            ..event
+        };
+        if let Some(context) = self.context.get() {
+            context.pointer_event_channel.borrow().borrow().send(&event);
+        }
+    }
+}
+
+// Global pointer events stream. Platform monitoring is only active while
+// there is at least one listener. If monitoring is not supported (i.e. on
+// Wayland) listener receives an error.
+pub(crate) struct PointerEventChannel {
+    context: Context,
+    sinks: Vec<EventSink>,
+}
+
+impl PointerEventChannel {
+    pub(super) fn new(context: Context) -> RegisteredEventChannel<Self> {
+        Self {
+            context: context.clone(),
+            sinks: Vec::new(),
+        }
+        .register(context, channel::POINTER_EVENTS)
+    }
+
+    fn send(&self, event: &GlobalPointerEvent) {
+        let event = to_value(event).unwrap();
+        for sink in &self.sinks {
+            sink.send_message(&event).ok_log();
+        }
+    }
+
+    fn start_monitoring(&self) -> Result<()> {
+        let context = self.context.get().ok_or(Error::InvalidContext)?;
+        let pointer_manager = context.pointer_manager.borrow();
+        let res = pointer_manager
+            .borrow()
+            .platform_manager()
+            .start_monitoring();
+        res.map_err(Error::from)
+    }
+
+    fn stop_monitoring(&self) {
+        if let Some(context) = self.context.get() {
+            let pointer_manager = context.pointer_manager.borrow();
+            let pointer_manager = pointer_manager.borrow();
+            pointer_manager.platform_manager().stop_monitoring();
+        }
+    }
+}
+
+impl EventChannelHandler for PointerEventChannel {
+    fn register_event_sink(&mut self, sink: EventSink, _listen_argument: Value) {
+        if self.sinks.is_empty() {
+            if let Err(error) = self.start_monitoring() {
+                sink.send_error(&error.into()).ok_log();
+                return;
+            }
+        }
+        self.sinks.push(sink);
+    }
+
+    fn unregister_event_sink(&mut self, sink_id: i64) {
+        let was_monitoring = !self.sinks.is_empty();
+        self.sinks.retain(|s| s.id() != sink_id);
+        if was_monitoring && self.sinks.is_empty() {
+            self.stop_monitoring();
+        }
+    }
+}
@@ -11,6 +11,7 @@ use crate::{
 
 use super::{
     api_constants::{channel, method},
+    api_model::{Screen, ScreensChanged},
     platform::screen_manager::PlatformScreenManager,
     EngineHandle, MethodCallHandler, MethodInvokerProvider, RegisteredMethodCallHandler,
 };
@@ -20,21 +21,35 @@ pub trait ScreenManagerDelegate {
 }
 
 pub struct ScreenManager {
+    context: Context,
     platform_manager: Late<PlatformScreenManager>,
     invoker_provider: Late<MethodInvokerProvider>,
     engines: HashSet<EngineHandle>,
+    // Last known configuration, used to compute ScreensChanged
+    screens: RefCell<Vec<Screen>>,
 }
 
 impl ScreenManager {
     pub(super) fn new(context: Context) -> RegisteredMethodCallHandler<Self> {
         Self {
+            context: context.clone(),
             platform_manager: Late::new(),
             invoker_provider: Late::new(),
             engines: HashSet::new(),
+            screens: RefCell::new(Vec::new()),
         }
         .register(context, channel::SCREEN_MANAGER)
     }
 
+    pub fn platform_manager(&self) -> &PlatformScreenManager {
+        &self.platform_manager
+    }
+
+    // Screen configuration as of last change notification
+    pub(super) fn last_screens(&self) -> Vec<Screen> {
+        self.screens.borrow().clone()
+    }
+
     fn map_result<T>(result: Result<T>) -> MethodCallResult<Value>
     where
         T: serde::Serialize,
@@ -83,6 +98,8 @@ impl MethodCallHandler for ScreenManager {
     fn assign_weak_self(&mut self, weak_self: Weak<RefCell<Self>>) {
         self.platform_manager
             .set(PlatformScreenManager::new(weak_self));
+        self.screens
+            .replace(self.platform_manager.get_screens().unwrap_or_default());
     }
 
     fn assign_invoker_provider(&mut self, provider: MethodInvokerProvider) {
@@ -92,11 +109,31 @@ impl MethodCallHandler for ScreenManager {
 
 impl ScreenManagerDelegate for ScreenManager {
     fn screen_configuration_changed(&self) {
+        let screens = self.platform_manager.get_screens().unwrap_or_default();
+        let changed = ScreensChanged::new(&self.screens.replace(screens), &self.screens.borrow());
+        // Platforms may report same change multiple times
+        if changed.is_empty() {
+            return;
+        }
+        let changed = to_value(changed).unwrap();
         for engine in &self.engines {
             let invoker = self.invoker_provider.get_method_invoker_for_engine(*engine);
             invoker
-                .call_method(method::screen_manager::SCREENS_CHANGED, Value::Null, |_| {})
+                .call_method(
+                    method::screen_manager::SCREENS_CHANGED,
+                    changed.clone(),
+                    |_| {},
+                )
                 .ok_log();
         }
+        if let Some(context) = self.context.get() {
+            let windows = context.window_manager.borrow().get_all_windows();
+            for window in windows {
+                let window = context.window_manager.borrow().get_window(window);
+                if let Some(window) = window {
+                    window.update_screen(&self.screens.borrow());
+                }
+            }
+        }
     }
 }
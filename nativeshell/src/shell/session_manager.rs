@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use crate::{
+    codec::{value::ValueError, Value},
+    Error, Result,
+};
+
+use super::{
+    api_model::{GeometryPreference, Screen, WindowGeometry, WindowGeometryRequest},
+    window_placement::screen_for_frame,
+    Context, ContextRef, Rect, WindowHandle,
+};
+
+const SESSION_VERSION: u32 = 1;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub version: u32,
+    pub windows: Vec<SessionWindow>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionWindow {
+    // Handles are only valid within the session document; They are used to
+    // restore parent relationships and will be different after restore.
+    pub handle: i64,
+    pub parent: Option<i64>,
+    pub init_data: Value,
+    // Frame of the window when not maximized or full screen
+    pub frame: Option<Rect>,
+    pub screen_id: Option<i64>,
+    pub maximized: bool,
+    pub full_screen: bool,
+}
+
+// Saves and restores set of all open windows. Session is serialized as JSON,
+// so init data must be representable in JSON (maps must have string keys).
+pub struct SessionManager {
+    context: Context,
+}
+
+impl SessionManager {
+    pub(super) fn new(context: &ContextRef) -> Self {
+        Self {
+            context: context.weak(),
+        }
+    }
+
+    pub fn save_session(&self) -> Result<Session> {
+        let context = self.context.get().ok_or(Error::InvalidContext)?;
+        let window_manager = context.window_manager.borrow();
+        let mut handles = window_manager.get_all_windows();
+        handles.sort_by_key(|h| h.0);
+        let mut windows = Vec::new();
+        for handle in handles {
+            let window = match window_manager.get_window(handle) {
+                Some(window) => window,
+                None => continue,
+            };
+            let frame = window.get_normal_frame()?;
+            let flags = window.get_window_state_flags()?;
+            windows.push(SessionWindow {
+                handle: handle.0,
+                parent: window.parent.map(|p| p.0),
+                init_data: window.init_data.clone(),
+                frame,
+                screen_id: window.get_screen_id().ok(),
+                maximized: flags.maximized.is_set(),
+                full_screen: flags.full_screen.is_set(),
+            });
+        }
+        Ok(Session {
+            version: SESSION_VERSION,
+            windows,
+        })
+    }
+
+    pub fn save_session_to_string(&self) -> Result<String> {
+        let session = self.save_session()?;
+        serde_json::to_string(&session)
+            .map_err(|e| Error::Value(ValueError::Message(e.to_string())))
+    }
+
+    // Recreates windows from session; Returns handles of created windows in
+    // same order as in session. Window frames are clamped to work area of
+    // currently connected screens.
+    pub fn restore_session(&self, session: Session) -> Result<Vec<WindowHandle>> {
+        if session.version > SESSION_VERSION {
+            return Err(Error::Value(ValueError::Message(format!(
+                "Unsupported session version {}",
+                session.version
+            ))));
+        }
+        let context = self.context.get().ok_or(Error::InvalidContext)?;
+        let (screens, main_screen) = {
+            let screen_manager = context.screen_manager.borrow();
+            let screen_manager = screen_manager.borrow();
+            let platform_manager = screen_manager.platform_manager();
+            (
+                platform_manager.get_screens().unwrap_or_default(),
+                platform_manager.get_main_screen().ok(),
+            )
+        };
+
+        // Parents are always created before their children, which means they
+        // have lower handles
+        let mut windows = session.windows;
+        windows.sort_by_key(|w| w.handle);
+
+        let mut handles = HashMap::<i64, WindowHandle>::new();
+        let mut res = Vec::new();
+        for window in windows {
+            let parent = window.parent.and_then(|p| handles.get(&p).cloned());
+            let handle = context
+                .window_manager
+                .borrow_mut()
+                .create_window(window.init_data, parent)?;
+            handles.insert(window.handle, handle);
+            res.push(handle);
+
+            let controller = context
+                .window_manager
+                .borrow()
+                .get_window_controller(handle)
+                .ok_or(Error::InvalidWindowHandle)?;
+            if let Some(frame) = window.frame {
+                let frame = Self::clamp_frame(frame, window.screen_id, &screens, main_screen);
+                controller.set_geometry(WindowGeometryRequest {
+                    // Platforms without frame size (Linux) use content size
+                    // for normal frame
+                    geometry: WindowGeometry {
+                        frame_origin: Some(frame.origin()),
+                        frame_size: Some(frame.size()),
+                        content_size: Some(frame.size()),
+                        ..Default::default()
+                    },
+                    preference: GeometryPreference::PreferFrame,
+                })?;
+            }
+            if window.maximized {
+                controller.set_maximized(true)?;
+            }
+            if window.full_screen {
+                controller.set_full_screen(true)?;
+            }
+        }
+        Ok(res)
+    }
+
+    pub fn restore_session_from_string(&self, session: &str) -> Result<Vec<WindowHandle>> {
+        let session: Session = serde_json::from_str(session)
+            .map_err(|e| Error::Value(ValueError::Message(e.to_string())))?;
+        self.restore_session(session)
+    }
+
+    // Keeps the original screen if frame is still on it, otherwise uses
+    // screen with largest overlap or main screen.
+    fn clamp_frame(
+        frame: Rect,
+        screen_id: Option<i64>,
+        screens: &[Screen],
+        main_screen: Option<i64>,
+    ) -> Rect {
+        let screen = screens
+            .iter()
+            .find(|s| Some(s.id) == screen_id && s.frame.is_inside(&frame.center()))
+            .or_else(|| screen_for_frame(&frame, screens, main_screen));
+        match screen {
+            Some(screen) => frame.clamp(&screen.work_area),
+            None => frame,
+        }
+    }
+}
@@ -96,7 +96,10 @@ impl StatusItemManager {
         Ok(handle)
     }
 
-    fn get_platform_status_item(&self, item: StatusItemHandle) -> Result<Rc<PlatformStatusItem>> {
+    pub(crate) fn get_platform_status_item(
+        &self,
+        item: StatusItemHandle,
+    ) -> Result<Rc<PlatformStatusItem>> {
         self.status_item_map
             .get(&item)
             .cloned()
@@ -1,5 +1,5 @@
 use std::{
-    cell::Cell,
+    cell::{Cell, RefCell},
     rc::{Rc, Weak},
 };
 
@@ -15,14 +15,17 @@ use crate::{
 use super::{
     api_constants::*,
     api_model::{
-        DragEffect, DragRequest, DragResult, DraggingInfo, HidePopupMenuRequest, PopupMenuRequest,
-        PopupMenuResponse, SetMenuRequest, WindowActivateRequest, WindowCollectionBehavior,
-        WindowDeactivateRequest, WindowGeometry, WindowGeometryFlags, WindowGeometryRequest,
+        AlertRequest, AlertResponse, DragEffect, DragRequest, DragResult, DraggingInfo,
+        GeometryPreference, HidePopupMenuRequest, IgnoreMouseEventsRequest, ImageData,
+        PopupMenuRequest, PopupMenuResponse, Screen, SetMenuRequest, WindowActivateRequest,
+        WindowCollectionBehavior, WindowDeactivateRequest, WindowDragRegions, WindowEdge,
+        WindowGeometry, WindowGeometryFlags, WindowGeometryRequest, WindowPlacement,
         WindowStateFlags, WindowStyle,
     },
     platform::window::PlatformWindow,
-    Context, EngineHandle, MenuDelegate, WindowMethodCallReply, WindowMethodCallResult,
-    WindowMethodInvoker,
+    window_placement::{place_window, PlacementInput},
+    Context, EngineHandle, MenuDelegate, Point, Rect, WindowEvent, WindowMethodCallReply,
+    WindowMethodCallResult, WindowMethodInvoker,
 };
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Copy, Clone, Hash, Eq, PartialEq)]
@@ -36,6 +39,13 @@ pub(super) struct Window {
     pub(super) init_data: Value,
     pub(super) parent: Option<WindowHandle>,
     pub(super) initialized: Cell<bool>,
+    screen_id: Cell<Option<i64>>,
+    scale_factor: Cell<Option<f64>>,
+    show_called: Cell<bool>,
+    ready_to_show_called: Cell<bool>,
+    pending_placement: RefCell<Option<WindowPlacement>>,
+    // Last frame while window was not maximized, minimized or full screen
+    normal_frame: RefCell<Option<Rect>>,
     weak_self: LateRefCell<Weak<Self>>,
 }
 
@@ -55,6 +65,12 @@ impl Window {
             init_data,
             parent,
             initialized: Cell::new(false),
+            screen_id: Cell::new(None),
+            scale_factor: Cell::new(None),
+            show_called: Cell::new(false),
+            ready_to_show_called: Cell::new(false),
+            pending_placement: RefCell::new(None),
+            normal_frame: RefCell::new(None),
             weak_self: LateRefCell::new(),
         }
     }
@@ -83,6 +99,53 @@ impl Window {
         }
     }
 
+    fn notify(&self, event: WindowEvent) {
+        if let Some(context) = self.context.get() {
+            let notifications = context.window_manager.borrow().window_event_notifications();
+            for notification in notifications {
+                notification(self.window_handle, &event);
+            }
+        }
+    }
+
+    // Sends screenChanged and scaleFactorChanged events if window screen or
+    // its scaling factor differs from last call. First call only records
+    // current state.
+    pub(super) fn update_screen(&self, screens: &[Screen]) {
+        let screen_id = match self.platform_window.borrow().get_screen_id() {
+            Ok(screen_id) => screen_id,
+            Err(_) => return,
+        };
+        if let Some(previous) = self.screen_id.replace(Some(screen_id)) {
+            if previous != screen_id {
+                self.broadcast_message(event::window::SCREEN_CHANGED, screen_id.into());
+                self.notify(WindowEvent::ScreenChanged(screen_id));
+            }
+        }
+        let scale_factor = screens
+            .iter()
+            .find(|s| s.id == screen_id)
+            .map(|s| s.scaling_factor);
+        if let Some(scale_factor) = scale_factor {
+            if let Some(previous) = self.scale_factor.replace(Some(scale_factor)) {
+                if previous != scale_factor {
+                    self.broadcast_message(
+                        event::window::SCALE_FACTOR_CHANGED,
+                        scale_factor.into(),
+                    );
+                    self.notify(WindowEvent::ScaleFactorChanged(scale_factor));
+                }
+            }
+        }
+    }
+
+    fn last_screens(&self) -> Vec<Screen> {
+        match self.context.get() {
+            Some(context) => context.screen_manager.borrow().borrow().last_screens(),
+            None => Vec::new(),
+        }
+    }
+
     fn drop_target_invoker(&self) -> Option<WindowMethodInvoker> {
         if let Some(context) = self.context.get() {
             context.window_method_channel.borrow().get_method_invoker(
@@ -111,102 +174,257 @@ impl Window {
         self.platform_window.borrow().clone()
     }
 
-    fn show(&self) -> Result<()> {
+    pub(super) fn show(&self) -> Result<()> {
+        self.show_called.set(true);
+        self.apply_pending_placement()?;
         self.platform_window().show().map_err(|e| e.into())
     }
 
     fn ready_to_show(&self) -> Result<()> {
+        self.ready_to_show_called.set(true);
+        self.apply_pending_placement()?;
         self.platform_window().ready_to_show().map_err(|e| e.into())
     }
 
-    fn close(&self) -> Result<()> {
+    pub(super) fn set_placement(&self, placement: WindowPlacement) -> Result<()> {
+        self.pending_placement.replace(Some(placement));
+        self.apply_pending_placement()
+    }
+
+    // Placement is deferred until window is about to become visible so that
+    // it is not affected by geometry changes done during initialization
+    fn apply_pending_placement(&self) -> Result<()> {
+        if !self.show_called.get() || !self.ready_to_show_called.get() {
+            return Ok(());
+        }
+        let placement = match self.pending_placement.take() {
+            Some(placement) => placement,
+            None => return Ok(()),
+        };
+        let context = self.context.get().ok_or(Error::InvalidContext)?;
+        let frame_of = |window: &Window| {
+            let geometry = window.get_geometry().ok()?;
+            Some(Rect::origin_size(
+                geometry.frame_origin.as_ref()?,
+                geometry.frame_size.as_ref()?,
+            ))
+        };
+        let frame = match frame_of(self) {
+            Some(frame) => frame,
+            None => return Ok(()), // platform doesn't support frame geometry
+        };
+        let (parent, last_window) = {
+            let window_manager = context.window_manager.borrow();
+            let parent = self.parent.and_then(|p| window_manager.get_window(p));
+            let last_window = window_manager
+                .get_all_windows()
+                .into_iter()
+                .filter(|h| *h != self.window_handle)
+                .max_by_key(|h| h.0)
+                .and_then(|h| window_manager.get_window(h));
+            (parent, last_window)
+        };
+        let (screens, main_screen) = {
+            let screen_manager = context.screen_manager.borrow();
+            let screen_manager = screen_manager.borrow();
+            let platform_manager = screen_manager.platform_manager();
+            (
+                platform_manager.get_screens().unwrap_or_default(),
+                platform_manager.get_main_screen().ok(),
+            )
+        };
+        let frame = place_window(
+            &placement,
+            &PlacementInput {
+                frame,
+                parent_frame: parent.as_deref().and_then(frame_of),
+                last_window_frame: last_window.as_deref().and_then(frame_of),
+                screens: &screens,
+                main_screen,
+            },
+        );
+        self.set_geometry(WindowGeometryRequest {
+            geometry: WindowGeometry {
+                frame_origin: Some(frame.origin()),
+                frame_size: Some(frame.size()),
+                ..Default::default()
+            },
+            preference: GeometryPreference::PreferFrame,
+        })?;
+        Ok(())
+    }
+
+    pub(super) fn close(&self) -> Result<()> {
         self.platform_window().close().map_err(|e| e.into())
     }
 
-    fn close_with_result(&self, result: Value) -> Result<()> {
+    pub(super) fn close_with_result(&self, result: Value) -> Result<()> {
         self.platform_window()
             .close_with_result(result)
             .map_err(|e| e.into())
     }
 
-    fn hide(&self) -> Result<()> {
+    pub(super) fn hide(&self) -> Result<()> {
         self.platform_window().hide().map_err(|e| e.into())
     }
 
-    fn activate(&self, request: WindowActivateRequest) -> Result<bool> {
+    pub(super) fn activate(&self, request: WindowActivateRequest) -> Result<bool> {
         self.platform_window()
             .activate(request.activate_application)
             .map_err(|e| e.into())
     }
 
-    fn deactivate(&self, request: WindowDeactivateRequest) -> Result<bool> {
+    pub(super) fn deactivate(&self, request: WindowDeactivateRequest) -> Result<bool> {
         self.platform_window()
             .deactivate(request.deactivate_application)
             .map_err(|e| e.into())
     }
 
-    fn set_geometry(&self, geometry: WindowGeometryRequest) -> Result<WindowGeometryFlags> {
+    pub(super) fn set_geometry(
+        &self,
+        geometry: WindowGeometryRequest,
+    ) -> Result<WindowGeometryFlags> {
         self.platform_window()
             .set_geometry(geometry)
             .map_err(|e| e.into())
     }
 
-    fn get_geometry(&self) -> Result<WindowGeometry> {
+    pub(super) fn get_geometry(&self) -> Result<WindowGeometry> {
         self.platform_window().get_geometry().map_err(|e| e.into())
     }
 
-    fn supported_geometry(&self) -> Result<WindowGeometryFlags> {
+    // Frame the window would have if it was not maximized, minimized or full
+    // screen
+    pub(super) fn get_normal_frame(&self) -> Result<Option<Rect>> {
+        if let Some(frame) = self.platform_window().get_normal_frame()? {
+            return Ok(Some(frame));
+        }
+        let flags = self.get_window_state_flags()?;
+        if flags.maximized.is_set() || flags.minimized.is_set() || flags.full_screen.is_set() {
+            if let Some(frame) = self.normal_frame.borrow().clone() {
+                return Ok(Some(frame));
+            }
+        }
+        Ok(Self::frame(&self.get_geometry()?))
+    }
+
+    fn frame(geometry: &WindowGeometry) -> Option<Rect> {
+        match (&geometry.frame_origin, &geometry.frame_size) {
+            (Some(origin), Some(size)) => Some(Rect::origin_size(origin, size)),
+            _ => None,
+        }
+    }
+
+    fn update_normal_frame(&self, geometry: &WindowGeometry) {
+        let normal = match self.get_window_state_flags() {
+            Ok(flags) => {
+                !flags.maximized.is_set()
+                    && !flags.minimized.is_set()
+                    && !flags.full_screen.is_set()
+            }
+            Err(_) => false,
+        };
+        if let (true, Some(frame)) = (normal, Self::frame(geometry)) {
+            self.normal_frame.replace(Some(frame));
+        }
+    }
+
+    pub(super) fn supported_geometry(&self) -> Result<WindowGeometryFlags> {
         self.platform_window()
             .supported_geometry()
             .map_err(|e| e.into())
     }
 
-    fn get_screen_id(&self) -> Result<i64> {
+    pub(super) fn get_screen_id(&self) -> Result<i64> {
         self.platform_window().get_screen_id().map_err(|e| e.into())
     }
 
-    fn set_style(&self, style: WindowStyle) -> Result<()> {
+    pub(super) fn set_style(&self, style: WindowStyle) -> Result<()> {
         self.platform_window()
             .set_style(style)
             .map_err(|e| e.into())
     }
 
-    fn get_window_state_flags(&self) -> Result<WindowStateFlags> {
+    pub(super) fn get_window_state_flags(&self) -> Result<WindowStateFlags> {
         self.platform_window()
             .get_window_state_flags()
             .map_err(|e| e.into())
     }
 
-    fn set_title(&self, title: String) -> Result<()> {
+    pub(super) fn set_title(&self, title: String) -> Result<()> {
         self.platform_window()
             .set_title(title)
             .map_err(|e| e.into())
     }
 
-    fn set_minimized(&self, minimized: bool) -> Result<()> {
+    pub(super) fn set_minimized(&self, minimized: bool) -> Result<()> {
         self.platform_window()
             .set_minimized(minimized)
             .map_err(|e| e.into())
     }
 
-    fn set_maximized(&self, maximized: bool) -> Result<()> {
+    pub(super) fn set_maximized(&self, maximized: bool) -> Result<()> {
         self.platform_window()
             .set_maximized(maximized)
             .map_err(|e| e.into())
     }
 
-    fn set_full_screen(&self, full_screen: bool) -> Result<()> {
+    fn set_icon(&self, images: Vec<ImageData>) -> Result<()> {
+        self.platform_window()
+            .set_icon(images)
+            .map_err(|e| e.into())
+    }
+
+    fn set_application_id(&self, application_id: String) -> Result<()> {
+        self.platform_window()
+            .set_application_id(application_id)
+            .map_err(|e| e.into())
+    }
+
+    pub(super) fn set_full_screen(&self, full_screen: bool) -> Result<()> {
         self.platform_window()
             .set_full_screen(full_screen)
             .map_err(|e| e.into())
     }
 
-    fn set_collection_behavior(&self, behavior: WindowCollectionBehavior) -> Result<()> {
+    pub(super) fn set_collection_behavior(&self, behavior: WindowCollectionBehavior) -> Result<()> {
         self.platform_window()
             .set_collection_behavior(behavior)
             .map_err(|e| e.into())
     }
 
+    pub(super) fn set_urgent(&self, urgent: bool) -> Result<()> {
+        self.platform_window()
+            .set_urgent(urgent)
+            .map_err(|e| e.into())
+    }
+
+    pub(super) fn capture_image(&self) -> Result<ImageData> {
+        self.platform_window().capture_image().map_err(|e| e.into())
+    }
+
+    pub(super) fn lock_pointer(&self) -> Result<()> {
+        self.platform_window().lock_pointer().map_err(|e| e.into())
+    }
+
+    pub(super) fn confine_pointer(&self, rect: Option<Rect>) -> Result<()> {
+        self.platform_window()
+            .confine_pointer(rect)
+            .map_err(|e| e.into())
+    }
+
+    pub(super) fn release_pointer(&self) -> Result<()> {
+        self.platform_window()
+            .release_pointer()
+            .map_err(|e| e.into())
+    }
+
+    pub(super) fn set_hide_pointer_while_typing(&self, hide: bool) -> Result<()> {
+        self.platform_window()
+            .set_hide_pointer_while_typing(hide)
+            .map_err(|e| e.into())
+    }
+
     fn save_position_to_string(&self) -> Result<String> {
         self.platform_window()
             .save_position_to_string()
@@ -225,6 +443,30 @@ impl Window {
             .map_err(|e| e.into())
     }
 
+    fn perform_window_resize(&self, edge: WindowEdge) -> Result<()> {
+        self.platform_window()
+            .perform_window_resize(edge)
+            .map_err(|e| e.into())
+    }
+
+    fn set_drag_regions(&self, regions: WindowDragRegions) -> Result<()> {
+        self.platform_window()
+            .set_drag_regions(regions)
+            .map_err(|e| e.into())
+    }
+
+    fn set_ignore_mouse_events(&self, request: IgnoreMouseEventsRequest) -> Result<()> {
+        self.platform_window()
+            .set_ignore_mouse_events(request.ignore, request.forward_motion)
+            .map_err(|e| e.into())
+    }
+
+    fn set_input_region(&self, region: Option<Vec<Rect>>) -> Result<()> {
+        self.platform_window()
+            .set_input_region(region)
+            .map_err(|e| e.into())
+    }
+
     fn begin_drag_session(&self, request: DragRequest) -> Result<()> {
         self.platform_window()
             .begin_drag_session(request)
@@ -265,6 +507,17 @@ impl Window {
         }
     }
 
+    fn show_alert<F>(&self, mut request: AlertRequest, on_done: F)
+    where
+        F: FnOnce(Result<AlertResponse>) + 'static,
+    {
+        if request.buttons.is_empty() {
+            request.buttons.push("OK".into());
+        }
+        self.platform_window()
+            .show_alert(request, |r| on_done(r.map_err(|e| e.into())));
+    }
+
     fn show_system_menu(&self) -> Result<()> {
         self.platform_window()
             .show_system_menu()
@@ -357,6 +610,9 @@ impl Window {
             method::window::SUPPORTED_GEOMETRY => {
                 return Self::reply(reply, &arg, |()| self.supported_geometry());
             }
+            method::window::SET_PLACEMENT => {
+                return Self::reply(reply, &arg, |placement| self.set_placement(placement));
+            }
             method::window::GET_SCREEN_ID => {
                 return Self::reply(reply, &arg, |()| self.get_screen_id());
             }
@@ -369,6 +625,12 @@ impl Window {
             method::window::SET_TITLE => {
                 return Self::reply(reply, &arg, |title| self.set_title(title));
             }
+            method::window::SET_ICON => {
+                return Self::reply(reply, &arg, |images| self.set_icon(images));
+            }
+            method::window::SET_APPLICATION_ID => {
+                return Self::reply(reply, &arg, |id| self.set_application_id(id));
+            }
             method::window::SET_MAXIMIZED => {
                 return Self::reply(reply, &arg, |v| self.set_maximized(v));
             }
@@ -383,6 +645,24 @@ impl Window {
                     self.set_collection_behavior(behavior)
                 });
             }
+            method::window::SET_URGENT => {
+                return Self::reply(reply, &arg, |urgent| self.set_urgent(urgent));
+            }
+            method::window::CAPTURE_IMAGE => {
+                return Self::reply(reply, &arg, |()| self.capture_image());
+            }
+            method::window::LOCK_POINTER => {
+                return Self::reply(reply, &arg, |()| self.lock_pointer());
+            }
+            method::window::CONFINE_POINTER => {
+                return Self::reply(reply, &arg, |rect| self.confine_pointer(rect));
+            }
+            method::window::RELEASE_POINTER => {
+                return Self::reply(reply, &arg, |()| self.release_pointer());
+            }
+            method::window::SET_HIDE_POINTER_WHILE_TYPING => {
+                return Self::reply(reply, &arg, |hide| self.set_hide_pointer_while_typing(hide));
+            }
             method::window::SAVE_POSITION_TO_STRING => {
                 return Self::reply(reply, &arg, |()| self.save_position_to_string());
             }
@@ -394,6 +674,18 @@ impl Window {
             method::window::PERFORM_WINDOW_DRAG => {
                 return Self::reply(reply, &arg, |()| self.perform_window_drag());
             }
+            method::window::PERFORM_WINDOW_RESIZE => {
+                return Self::reply(reply, &arg, |edge| self.perform_window_resize(edge));
+            }
+            method::window::SET_DRAG_REGIONS => {
+                return Self::reply(reply, &arg, |regions| self.set_drag_regions(regions));
+            }
+            method::window::SET_IGNORE_MOUSE_EVENTS => {
+                return Self::reply(reply, &arg, |request| self.set_ignore_mouse_events(request));
+            }
+            method::window::SET_INPUT_REGION => {
+                return Self::reply(reply, &arg, |region| self.set_input_region(region));
+            }
             method::window::SHOW_POPUP_MENU => {
                 let request: std::result::Result<PopupMenuRequest, _> = from_value(&arg);
                 match request {
@@ -407,6 +699,16 @@ impl Window {
             method::window::HIDE_POPUP_MENU => {
                 return Self::reply(reply, &arg, |req| self.hide_popup_menu(req));
             }
+            method::window::SHOW_ALERT => {
+                let request: std::result::Result<AlertRequest, _> = from_value(&arg);
+                match request {
+                    Ok(request) => {
+                        return self
+                            .show_alert(request, move |res| reply.send(Self::map_result(res)))
+                    }
+                    Err(err) => return reply.send(Self::map_result::<()>(Err(err.into()))),
+                }
+            }
             method::window::SHOW_SYSTEM_MENU => {
                 return Self::reply(reply, &arg, |()| self.show_system_menu());
             }
@@ -430,6 +732,9 @@ pub trait PlatformWindowDelegate {
     fn state_flags_changed(&self);
     fn geometry_changed(&self);
 
+    // Delta is in logical coordinates
+    fn relative_pointer_motion(&self, delta: Point);
+
     fn dragging_exited(&self);
     fn dragging_updated(&self, info: &DraggingInfo);
     fn perform_drop(&self, info: &DraggingInfo);
@@ -442,15 +747,18 @@ pub trait PlatformWindowDelegate {
 impl PlatformWindowDelegate for Window {
     fn visibility_changed(&self, visible: bool) {
         self.broadcast_message(event::window::VISIBILITY_CHANGED, Value::Bool(visible));
+        self.notify(WindowEvent::VisibilityChanged(visible));
     }
 
     fn did_request_close(&self) {
         self.broadcast_message(event::window::CLOSE_REQUEST, Value::Null);
+        self.notify(WindowEvent::CloseRequest);
     }
 
     fn will_close(&self) {
         if let Some(context) = self.context.get() {
             self.broadcast_message(event::window::CLOSE, Value::Null);
+            self.notify(WindowEvent::Close);
             context.window_manager.borrow_mut().remove_window(self);
         }
     }
@@ -458,15 +766,33 @@ impl PlatformWindowDelegate for Window {
     fn state_flags_changed(&self) {
         let flags = self.platform_window.borrow().get_window_state_flags();
         if let Ok(flags) = flags {
-            self.broadcast_message(event::window::STATE_FLAGS_CHANGED, to_value(flags).unwrap());
+            self.broadcast_message(
+                event::window::STATE_FLAGS_CHANGED,
+                to_value(&flags).unwrap(),
+            );
+            self.notify(WindowEvent::StateFlagsChanged(flags));
         }
     }
 
     fn geometry_changed(&self) {
         let geometry = self.platform_window.borrow().get_geometry();
         if let Ok(geometry) = geometry {
-            self.broadcast_message(event::window::GEOMETRY_CHANGED, to_value(geometry).unwrap());
+            self.update_normal_frame(&geometry);
+            self.broadcast_message(
+                event::window::GEOMETRY_CHANGED,
+                to_value(&geometry).unwrap(),
+            );
+            self.notify(WindowEvent::GeometryChanged(geometry));
         }
+        self.update_screen(&self.last_screens());
+    }
+
+    fn relative_pointer_motion(&self, delta: Point) {
+        self.broadcast_message(
+            event::window::RELATIVE_POINTER_MOTION,
+            to_value(&delta).unwrap(),
+        );
+        self.notify(WindowEvent::RelativePointerMotion(delta));
     }
 
     fn dragging_exited(&self) {
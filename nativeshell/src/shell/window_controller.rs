@@ -0,0 +1,177 @@
+use std::rc::Rc;
+
+use crate::{codec::Value, Error, Result};
+
+use super::{
+    api_model::{
+        ImageData, WindowActivateRequest, WindowCollectionBehavior, WindowDeactivateRequest,
+        WindowGeometry, WindowGeometryFlags, WindowGeometryRequest, WindowPlacement,
+        WindowStateFlags, WindowStyle,
+    },
+    Context, Handle, Point, Rect, Window, WindowHandle,
+};
+
+// Window events delivered to notifications registered through
+// WindowManager::register_window_event_notification. These are the same
+// events that are broadcasted to Dart (event:Window.*).
+#[derive(Debug, Clone)]
+pub enum WindowEvent {
+    VisibilityChanged(bool),
+    GeometryChanged(WindowGeometry),
+    StateFlagsChanged(WindowStateFlags),
+    ScreenChanged(i64),
+    ScaleFactorChanged(f64),
+    RelativePointerMotion(Point),
+    CloseRequest,
+    Close,
+}
+
+// Allows controlling window from Rust code. Controller only holds the window
+// handle, all methods fail with Error::InvalidWindowHandle once the window
+// is closed.
+#[derive(Clone)]
+pub struct WindowController {
+    context: Context,
+    window_handle: WindowHandle,
+}
+
+impl WindowController {
+    pub(super) fn new(context: Context, window_handle: WindowHandle) -> Self {
+        Self {
+            context,
+            window_handle,
+        }
+    }
+
+    pub fn window_handle(&self) -> WindowHandle {
+        self.window_handle
+    }
+
+    fn window(&self) -> Result<Rc<Window>> {
+        let context = self.context.get().ok_or(Error::InvalidContext)?;
+        let window = context
+            .window_manager
+            .borrow()
+            .get_window(self.window_handle);
+        window.ok_or(Error::InvalidWindowHandle)
+    }
+
+    pub fn show(&self) -> Result<()> {
+        self.window()?.show()
+    }
+
+    pub fn hide(&self) -> Result<()> {
+        self.window()?.hide()
+    }
+
+    pub fn close(&self) -> Result<()> {
+        self.window()?.close()
+    }
+
+    pub fn close_with_result(&self, result: Value) -> Result<()> {
+        self.window()?.close_with_result(result)
+    }
+
+    pub fn activate(&self, activate_application: bool) -> Result<bool> {
+        self.window()?.activate(WindowActivateRequest {
+            activate_application,
+        })
+    }
+
+    pub fn deactivate(&self, deactivate_application: bool) -> Result<bool> {
+        self.window()?.deactivate(WindowDeactivateRequest {
+            deactivate_application,
+        })
+    }
+
+    pub fn set_geometry(&self, geometry: WindowGeometryRequest) -> Result<WindowGeometryFlags> {
+        self.window()?.set_geometry(geometry)
+    }
+
+    pub fn get_geometry(&self) -> Result<WindowGeometry> {
+        self.window()?.get_geometry()
+    }
+
+    pub fn supported_geometry(&self) -> Result<WindowGeometryFlags> {
+        self.window()?.supported_geometry()
+    }
+
+    pub fn set_placement(&self, placement: WindowPlacement) -> Result<()> {
+        self.window()?.set_placement(placement)
+    }
+
+    pub fn get_screen_id(&self) -> Result<i64> {
+        self.window()?.get_screen_id()
+    }
+
+    pub fn set_style(&self, style: WindowStyle) -> Result<()> {
+        self.window()?.set_style(style)
+    }
+
+    pub fn get_window_state_flags(&self) -> Result<WindowStateFlags> {
+        self.window()?.get_window_state_flags()
+    }
+
+    pub fn set_title(&self, title: String) -> Result<()> {
+        self.window()?.set_title(title)
+    }
+
+    pub fn set_minimized(&self, minimized: bool) -> Result<()> {
+        self.window()?.set_minimized(minimized)
+    }
+
+    pub fn set_maximized(&self, maximized: bool) -> Result<()> {
+        self.window()?.set_maximized(maximized)
+    }
+
+    pub fn set_full_screen(&self, full_screen: bool) -> Result<()> {
+        self.window()?.set_full_screen(full_screen)
+    }
+
+    pub fn set_collection_behavior(&self, behavior: WindowCollectionBehavior) -> Result<()> {
+        self.window()?.set_collection_behavior(behavior)
+    }
+
+    pub fn set_urgent(&self, urgent: bool) -> Result<()> {
+        self.window()?.set_urgent(urgent)
+    }
+
+    pub fn capture_image(&self) -> Result<ImageData> {
+        self.window()?.capture_image()
+    }
+
+    pub fn lock_pointer(&self) -> Result<()> {
+        self.window()?.lock_pointer()
+    }
+
+    pub fn confine_pointer(&self, rect: Option<Rect>) -> Result<()> {
+        self.window()?.confine_pointer(rect)
+    }
+
+    pub fn release_pointer(&self) -> Result<()> {
+        self.window()?.release_pointer()
+    }
+
+    pub fn set_hide_pointer_while_typing(&self, hide: bool) -> Result<()> {
+        self.window()?.set_hide_pointer_while_typing(hide)
+    }
+
+    // Same as WindowManager::register_window_event_notification, but only
+    // reports events for this window.
+    pub fn register_event_notification<F>(&self, notification: F) -> Result<Handle>
+    where
+        F: Fn(&WindowEvent) + 'static,
+    {
+        let context = self.context.get().ok_or(Error::InvalidContext)?;
+        let window_handle = self.window_handle;
+        let handle = context
+            .window_manager
+            .borrow_mut()
+            .register_window_event_notification(move |handle, event| {
+                if handle == window_handle {
+                    notification(event);
+                }
+            });
+        Ok(handle)
+    }
+}
@@ -13,9 +13,10 @@ use crate::{
 
 use super::{
     api_constants::*,
+    api_model::{ImageData, WindowCreateOptions},
     platform::window::{PlatformWindow, PlatformWindowType},
-    Context, ContextRef, EngineHandle, PlatformWindowDelegate, Window, WindowHandle,
-    WindowMethodCall, WindowMethodCallReply, WindowMethodCallResult,
+    Context, ContextRef, EngineHandle, Handle, PlatformWindowDelegate, Window, WindowController,
+    WindowEvent, WindowHandle, WindowMethodCall, WindowMethodCallReply, WindowMethodCallResult,
 };
 
 pub struct WindowManager {
@@ -23,6 +24,8 @@ pub struct WindowManager {
     windows: HashMap<WindowHandle, Rc<Window>>,
     next_handle: WindowHandle,
     engine_to_window: HashMap<EngineHandle, WindowHandle>,
+    next_notification: i64,
+    event_notifications: HashMap<i64, Rc<dyn Fn(WindowHandle, &WindowEvent)>>,
 }
 
 #[derive(serde::Deserialize)]
@@ -30,6 +33,8 @@ pub struct WindowManager {
 struct WindowCreateRequest {
     parent: WindowHandle,
     init_data: Value,
+    #[serde(default)]
+    options: WindowCreateOptions,
 }
 
 #[derive(serde::Serialize)]
@@ -65,6 +70,8 @@ impl WindowManager {
             windows: HashMap::new(),
             next_handle: WindowHandle(1),
             engine_to_window: HashMap::new(),
+            next_notification: 1,
+            event_notifications: HashMap::new(),
         }
     }
 
@@ -72,6 +79,15 @@ impl WindowManager {
         &mut self,
         init_data: Value,
         parent: Option<WindowHandle>,
+    ) -> Result<WindowHandle> {
+        self.create_window_with_options(init_data, parent, Default::default())
+    }
+
+    pub fn create_window_with_options(
+        &mut self,
+        init_data: Value,
+        parent: Option<WindowHandle>,
+        options: WindowCreateOptions,
     ) -> Result<WindowHandle> {
         if let Some(context) = self.context.get() {
             let window_handle = self.next_handle;
@@ -106,6 +122,7 @@ impl WindowManager {
                 self.context.clone(),
                 Rc::downgrade(&(window.clone() as Rc<dyn PlatformWindowDelegate>)),
                 parent_platform_window,
+                &options,
             ));
 
             self.windows.insert(window_handle, window.clone());
@@ -121,6 +138,26 @@ impl WindowManager {
             );
             window.platform_window.set(platform_window);
 
+            // Window manager is mutably borrowed here; applying style may call
+            // back into the window synchronously (i.e. resize notification on
+            // macOS), which needs to borrow window manager again.
+            let weak = Rc::downgrade(&window);
+            let context_weak = self.context.clone();
+            context
+                .run_loop
+                .borrow()
+                .schedule_now(move || {
+                    if let (Some(window), Some(context)) = (weak.upgrade(), context_weak.get()) {
+                        if let Some(style) = options.style {
+                            window.set_style(style).ok_log();
+                        }
+                        window.update_screen(
+                            &context.screen_manager.borrow().borrow().last_screens(),
+                        );
+                    }
+                })
+                .detach();
+
             context
                 .engine_manager
                 .borrow_mut()
@@ -142,6 +179,57 @@ impl WindowManager {
         self.windows.get(&handle).map(|w| w.engine_handle)
     }
 
+    pub fn get_window_controller(&self, handle: WindowHandle) -> Option<WindowController> {
+        self.windows
+            .get(&handle)
+            .map(|_| WindowController::new(self.context.clone(), handle))
+    }
+
+    pub fn get_all_windows(&self) -> Vec<WindowHandle> {
+        self.windows.keys().cloned().collect()
+    }
+
+    // Notification is called for events of all windows; It is safe to access
+    // window manager from within the notification.
+    #[must_use]
+    pub fn register_window_event_notification<F>(&mut self, notification: F) -> Handle
+    where
+        F: Fn(WindowHandle, &WindowEvent) + 'static,
+    {
+        let handle = self.next_notification;
+        self.next_notification += 1;
+
+        self.event_notifications
+            .insert(handle, Rc::new(notification));
+
+        let context = self.context.clone();
+        Handle::new(move || {
+            if let Some(context) = context.get() {
+                context
+                    .window_manager
+                    .borrow_mut()
+                    .event_notifications
+                    .remove(&handle);
+            }
+        })
+    }
+
+    pub(super) fn get_window(&self, handle: WindowHandle) -> Option<Rc<Window>> {
+        self.windows.get(&handle).cloned()
+    }
+
+    pub(super) fn window_event_notifications(&self) -> Vec<Rc<dyn Fn(WindowHandle, &WindowEvent)>> {
+        self.event_notifications.values().cloned().collect()
+    }
+
+    // Captures current contents of given window as RGBA image in physical pixels.
+    pub fn capture_window_image(&self, handle: WindowHandle) -> Result<ImageData> {
+        self.windows
+            .get(&handle)
+            .ok_or(Error::InvalidWindowHandle)?
+            .capture_image()
+    }
+
     pub(super) fn remove_window(&mut self, window: &Window) {
         if let Some(context) = self.context.get() {
             let engine_handle = window.engine_handle;
@@ -191,8 +279,9 @@ impl WindowManager {
         &mut self,
         argument: Value,
         parent: WindowHandle,
+        options: WindowCreateOptions,
     ) -> WindowMethodCallResult {
-        self.create_window(argument, Some(parent))
+        self.create_window_with_options(argument, Some(parent), options)
             .map_err(MethodCallError::from)
             .map(|win| to_value(WindowCreateResponse { window_handle: win }).unwrap())
     }
@@ -247,12 +336,11 @@ impl WindowManager {
             }
             method::window_manager::CREATE_WINDOW => {
                 let create_request: WindowCreateRequest = from_value(&call.arguments).unwrap();
-                reply.send(
-                    context
-                        .window_manager
-                        .borrow_mut()
-                        .on_create_window(create_request.init_data, create_request.parent),
-                );
+                reply.send(context.window_manager.borrow_mut().on_create_window(
+                    create_request.init_data,
+                    create_request.parent,
+                    create_request.options,
+                ));
             }
             _ => {
                 let window = {
@@ -188,7 +188,7 @@ impl Drop for WindowMethodChannel {
     }
 }
 
-fn encode_method_call(call: WindowMethodCall) -> Value {
+pub(crate) fn encode_method_call(call: WindowMethodCall) -> Value {
     Value::Map(hash_map! {
         "targetWindowHandle".into() : call.target_window_handle.0.into(),
         "method".into() : call.method.into(),
@@ -206,7 +206,7 @@ fn encode_message(handle: &WindowHandle, channel: &str, message: &str, arguments
     })
 }
 
-fn decode_method_call(call: Value) -> WindowMethodCall {
+pub(crate) fn decode_method_call(call: Value) -> WindowMethodCall {
     if let Value::Map(mut map) = call {
         let target_window_handle = map.remove(&"targetWindowHandle".into());
         let method = map.remove(&"method".into());
@@ -233,7 +233,7 @@ fn decode_method_call(call: Value) -> WindowMethodCall {
     }
 }
 
-fn decode_result(result: Value) -> WindowMethodCallResult {
+pub(crate) fn decode_result(result: Value) -> WindowMethodCallResult {
     if let Value::Map(mut map) = result {
         let code = map.remove(&"code".into());
         let message = map.remove(&"message".into());
@@ -272,7 +272,7 @@ fn encode_error(code: &str, message: Option<&str>, details: Value) -> Value {
     })
 }
 
-fn encode_result(result: WindowMethodCallResult) -> Value {
+pub(crate) fn encode_result(result: WindowMethodCallResult) -> Value {
     match result {
         Ok(value) => Value::Map(hash_map! {
             "result".into() : value,
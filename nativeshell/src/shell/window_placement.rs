@@ -0,0 +1,115 @@
+use super::{
+    api_model::{Screen, WindowPlacement, WindowPosition},
+    Point, Rect,
+};
+
+// Offset of cascaded window from previous window
+const CASCADE_OFFSET: f64 = 24.0;
+
+// Returns screen with largest overlap with given frame, or main screen if
+// frame is not on any screen.
+pub(super) fn screen_for_frame<'a>(
+    frame: &Rect,
+    screens: &'a [Screen],
+    main_screen: Option<i64>,
+) -> Option<&'a Screen> {
+    let area = |s: &Screen| {
+        s.frame
+            .intersect(frame)
+            .map(|r| r.width * r.height)
+            .unwrap_or(0.0)
+    };
+    screens
+        .iter()
+        .filter(|s| area(s) > 0.0)
+        .max_by(|a, b| area(a).partial_cmp(&area(b)).unwrap())
+        .or_else(|| screens.iter().find(|s| Some(s.id) == main_screen))
+        .or_else(|| screens.first())
+}
+
+pub(super) struct PlacementInput<'a> {
+    pub frame: Rect,
+    pub parent_frame: Option<Rect>,
+    // Frame of most recently created window (other than the one being placed)
+    pub last_window_frame: Option<Rect>,
+    pub screens: &'a [Screen],
+    pub main_screen: Option<i64>,
+}
+
+// Returns new frame for the window; All coordinates are logical.
+pub(super) fn place_window(placement: &WindowPlacement, input: &PlacementInput) -> Rect {
+    let screens = input.screens;
+    let requested_screen = placement
+        .screen_id
+        .and_then(|id| screens.iter().find(|s| s.id == id));
+    let current_screen = screen_for_frame(&input.frame, screens, input.main_screen);
+
+    let center_in = |bounds: &Rect| {
+        let center = bounds.center();
+        Rect::xywh(
+            center.x - input.frame.width / 2.0,
+            center.y - input.frame.height / 2.0,
+            input.frame.width,
+            input.frame.height,
+        )
+    };
+
+    let center_on_screen = || {
+        let screen = requested_screen
+            .or_else(|| screens.iter().find(|s| Some(s.id) == input.main_screen))
+            .or(current_screen);
+        match screen {
+            Some(screen) => center_in(&screen.work_area),
+            None => input.frame.clone(),
+        }
+    };
+
+    // Keeps window offset relative to work area when moving to another screen
+    let move_to_requested_screen = |frame: Rect| match (requested_screen, current_screen) {
+        (Some(requested), Some(current)) if requested.id != current.id => {
+            let offset = current.work_area.to_local(&frame.origin());
+            frame.translated(&Point::xy(
+                requested.work_area.x + offset.x - frame.x,
+                requested.work_area.y + offset.y - frame.y,
+            ))
+        }
+        _ => frame,
+    };
+
+    let mut frame = match placement.position {
+        WindowPosition::Default => move_to_requested_screen(input.frame.clone()),
+        WindowPosition::CenterOnParent => match &input.parent_frame {
+            Some(parent) => center_in(parent),
+            None => center_on_screen(),
+        },
+        WindowPosition::CenterOnScreen => center_on_screen(),
+        WindowPosition::Cascade => match &input.last_window_frame {
+            Some(last) => {
+                let frame = Rect::origin_size(
+                    &last
+                        .origin()
+                        .translated(&Point::xy(CASCADE_OFFSET, CASCADE_OFFSET)),
+                    &input.frame.size(),
+                );
+                // Start from work area origin when running out of space
+                let screen = screen_for_frame(last, screens, input.main_screen);
+                match screen {
+                    Some(screen) if !screen.work_area.contains(&frame) => {
+                        Rect::origin_size(&screen.work_area.origin(), &input.frame.size())
+                    }
+                    _ => frame,
+                }
+            }
+            None => center_on_screen(),
+        },
+    };
+
+    if placement.constrain_to_work_area {
+        let screen =
+            requested_screen.or_else(|| screen_for_frame(&frame, screens, input.main_screen));
+        if let Some(screen) = screen {
+            frame = frame.clamp(&screen.work_area);
+        }
+    }
+    frame
+}